@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+
+use eframe::egui::{self, Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+
+use crate::{
+    consts::{ALGORITHM_PALETTE, UI_SPACE},
+    error::GraphEditorError,
+    graph::{algorithms::Traversal, trace::Trace, Graph, NodeId},
+    graph_workspace::GraphWorkspace,
+    player::Player,
+    scene::ColorMap,
+    utils::widgets::node_picker,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Bfs,
+    Dfs,
+    WeakComponents,
+    StrongComponents,
+    Cycle,
+    TopologicalSort,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 6] = [
+        Algorithm::Bfs,
+        Algorithm::Dfs,
+        Algorithm::WeakComponents,
+        Algorithm::StrongComponents,
+        Algorithm::Cycle,
+        Algorithm::TopologicalSort,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Bfs => "Breadth-first search",
+            Algorithm::Dfs => "Depth-first search",
+            Algorithm::WeakComponents => "Weak components",
+            Algorithm::StrongComponents => "Strong components",
+            Algorithm::Cycle => "Cycle detection",
+            Algorithm::TopologicalSort => "Topological sort",
+        }
+    }
+
+    pub fn needs_start(&self) -> bool {
+        matches!(self, Algorithm::Bfs | Algorithm::Dfs)
+    }
+
+    /// Steps of algorithm are recorded and can be replayed.
+    pub fn can_animate(&self) -> bool {
+        matches!(self, Algorithm::Bfs | Algorithm::Dfs)
+    }
+}
+
+/// Result shown as table and as temporary colors on canvas.
+struct AlgorithmOutput {
+    summary: String,
+    header: [&'static str; 2],
+    rows: Vec<[String; 2]>,
+    recolor: ColorMap,
+}
+
+/// Runs analysis algorithms on current graph.
+pub struct AlgorithmEditor {
+    algorithm: Algorithm,
+    start: Option<NodeId>,
+    /// Result is computed again every frame, so it follows edits of graph.
+    running: bool,
+    player: Option<Player>,
+}
+
+impl Default for AlgorithmEditor {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Bfs,
+            start: None,
+            running: false,
+            player: None,
+        }
+    }
+}
+
+impl AlgorithmEditor {
+    pub fn name(&self) -> &'static str {
+        "Algorithms"
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, graph_workspace: &mut GraphWorkspace) {
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new(self.name()).size(24.0));
+        });
+
+        ui.separator();
+
+        let previous_algorithm = self.algorithm;
+        egui::ComboBox::from_id_salt("algorithm")
+            .selected_text(self.algorithm.name())
+            .width(ui.available_width())
+            .show_ui(ui, |ui| {
+                for algorithm in Algorithm::ALL {
+                    ui.selectable_value(&mut self.algorithm, algorithm, algorithm.name());
+                }
+            });
+        if self.algorithm != previous_algorithm {
+            self.running = false;
+            self.player = None;
+        }
+
+        self.start = self.start.filter(|id| graph_workspace.node(id).is_some());
+        if self.start.is_none() {
+            self.start = graph_workspace.selected_node_id();
+        }
+
+        if self.algorithm.needs_start() {
+            ui.add_space(UI_SPACE);
+            ui.horizontal(|ui| {
+                ui.label("Start");
+                node_picker(ui, "algorithm_start", graph_workspace, &mut self.start);
+            });
+        }
+
+        ui.add_space(UI_SPACE);
+
+        ui.horizontal(|ui| {
+            let can_run = self.start.is_some() || !self.algorithm.needs_start();
+            if ui.add_enabled(can_run, egui::Button::new("Run")).clicked() {
+                self.running = true;
+                self.player = None;
+            }
+            if self.algorithm.can_animate()
+                && ui
+                    .add_enabled(can_run, egui::Button::new("Animate"))
+                    .clicked()
+            {
+                self.running = false;
+                self.player = Some(Player::new(self.trace(graph_workspace.graph())));
+            }
+            if ui.button("Clear").clicked() {
+                self.running = false;
+                self.player = None;
+            }
+        });
+
+        if let Some(player) = &mut self.player {
+            ui.separator();
+            player.ui(ui, graph_workspace);
+            graph_workspace.set_overlay(Some(player.overlay()));
+        }
+
+        if !self.running {
+            return;
+        }
+
+        ui.separator();
+
+        match self.run(graph_workspace.graph()) {
+            Ok(output) => {
+                ui.label(RichText::new(output.summary).strong());
+                ui.add_space(UI_SPACE);
+                show_table(ui, output.header, &output.rows);
+
+                graph_workspace.set_recolor(Some(output.recolor));
+            }
+            Err(err) => {
+                ui.colored_label(ui.visuals().error_fg_color, err.message());
+            }
+        }
+    }
+
+    /// Recorded steps of traversal.
+    fn trace(&self, graph: &Graph) -> Trace {
+        let mut trace = Trace::default();
+
+        match (self.algorithm, self.start) {
+            (Algorithm::Bfs, Some(start)) => {
+                graph.bfs(start, &mut trace);
+            }
+            (Algorithm::Dfs, Some(start)) => {
+                graph.dfs(start, &mut trace);
+            }
+            _ => {}
+        }
+
+        trace
+    }
+
+    fn run(&self, graph: &Graph) -> Result<AlgorithmOutput, GraphEditorError> {
+        let label = |id: &NodeId| graph.node(id).map_or("", |node| &node.label).to_owned();
+
+        let output = match self.algorithm {
+            Algorithm::Bfs | Algorithm::Dfs => {
+                let traversal = match self.start {
+                    Some(start) if self.algorithm == Algorithm::Bfs => {
+                        graph.bfs(start, &mut Trace::default())
+                    }
+                    Some(start) => graph.dfs(start, &mut Trace::default()),
+                    None => Traversal::default(),
+                };
+
+                AlgorithmOutput {
+                    summary: format!(
+                        "Visited {} of {} nodes",
+                        traversal.nodes.len(),
+                        graph.nodes().len()
+                    ),
+                    header: ["#", "Node (depth)"],
+                    rows: traversal
+                        .nodes
+                        .iter()
+                        .zip(&traversal.depths)
+                        .enumerate()
+                        .map(|(index, (id, depth))| {
+                            [(index + 1).to_string(), format!("{} ({depth})", label(id))]
+                        })
+                        .collect(),
+                    recolor: traversal_recolor(&traversal),
+                }
+            }
+            Algorithm::WeakComponents | Algorithm::StrongComponents => {
+                let components = if self.algorithm == Algorithm::WeakComponents {
+                    graph.weak_components()
+                } else {
+                    graph.strong_components()
+                };
+
+                let component_of: HashMap<_, _> = components
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, component)| component.iter().map(move |id| (*id, index)))
+                    .collect();
+
+                let mut recolor = ColorMap::default();
+                for (id, index) in &component_of {
+                    recolor.nodes.insert(*id, palette_color(*index));
+                }
+                // edges inside of component get its color
+                for (id, edge) in graph.edges() {
+                    if let Some(index) = component_of.get(&edge.start_id) {
+                        if component_of.get(&edge.end_id) == Some(index) {
+                            recolor.edges.insert(*id, palette_color(*index));
+                        }
+                    }
+                }
+
+                AlgorithmOutput {
+                    summary: format!("{} components", components.len()),
+                    header: ["Component", "Nodes"],
+                    rows: components
+                        .iter()
+                        .enumerate()
+                        .map(|(index, component)| {
+                            let labels: Vec<_> = component.iter().map(label).collect();
+                            [(index + 1).to_string(), labels.join(", ")]
+                        })
+                        .collect(),
+                    recolor,
+                }
+            }
+            Algorithm::Cycle => {
+                let cycle = graph.find_cycle().unwrap_or_default();
+
+                let mut recolor = ColorMap::default();
+                for id in &cycle.nodes {
+                    recolor.nodes.insert(*id, Color32::RED);
+                }
+                for id in &cycle.edges {
+                    recolor.edges.insert(*id, Color32::RED);
+                }
+
+                AlgorithmOutput {
+                    summary: if cycle.nodes.is_empty() {
+                        "There are no cycles".to_owned()
+                    } else {
+                        format!("Cycle of length {}", cycle.edges.len())
+                    },
+                    header: ["#", "Node"],
+                    rows: cycle
+                        .nodes
+                        .iter()
+                        .enumerate()
+                        .map(|(index, id)| [(index + 1).to_string(), label(id)])
+                        .collect(),
+                    recolor,
+                }
+            }
+            Algorithm::TopologicalSort => {
+                let order = graph.topological_sort()?;
+
+                let mut recolor = ColorMap::default();
+                for (id, layer) in &order {
+                    recolor.nodes.insert(*id, palette_color(*layer));
+                }
+
+                AlgorithmOutput {
+                    summary: format!(
+                        "{} layers",
+                        order.iter().map(|(_, layer)| layer + 1).max().unwrap_or(0)
+                    ),
+                    header: ["#", "Node (layer)"],
+                    rows: order
+                        .iter()
+                        .enumerate()
+                        .map(|(index, (id, layer))| {
+                            [(index + 1).to_string(), format!("{} ({layer})", label(id))]
+                        })
+                        .collect(),
+                    recolor,
+                }
+            }
+        };
+
+        Ok(output)
+    }
+}
+
+/// Visited nodes and tree edges colored by depth.
+fn traversal_recolor(traversal: &Traversal) -> ColorMap {
+    let mut recolor = ColorMap::default();
+
+    for (id, depth) in traversal.nodes.iter().zip(&traversal.depths) {
+        recolor.nodes.insert(*id, palette_color(*depth));
+    }
+    // start node has no tree edge
+    for (id, depth) in traversal.tree_edges.iter().zip(&traversal.depths[1..]) {
+        recolor.edges.insert(*id, palette_color(*depth));
+    }
+
+    recolor
+}
+
+fn palette_color(index: usize) -> Color32 {
+    ALGORITHM_PALETTE[index % ALGORITHM_PALETTE.len()]
+}
+
+fn show_table(ui: &mut egui::Ui, header: [&str; 2], rows: &[[String; 2]]) {
+    TableBuilder::new(ui)
+        .id_salt("algorithm_result")
+        .striped(true)
+        .column(Column::auto().at_least(40.0))
+        .column(Column::remainder().clip(true))
+        .header(20.0, |mut row| {
+            for name in header {
+                row.col(|ui| {
+                    ui.strong(name);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(20.0, rows.len(), |mut row| {
+                for cell in &rows[row.index()] {
+                    row.col(|ui| {
+                        ui.label(cell);
+                    });
+                }
+            });
+        });
+}
@@ -3,4 +3,7 @@ pub enum EditorVariant {
     Node,
     Edge,
     CommentLine,
+    Path,
+    Algorithm,
+    Styling,
 }
\ No newline at end of file
@@ -1,7 +1,13 @@
+use crate::format::ExchangeFormat;
+
 pub enum FileOperation {
     FileOpen,
     FileSave,
     FileSaveAs,
+    Import(ExchangeFormat),
+    Export(ExchangeFormat),
+    SvgExport,
+    PngExport,
     ScreenshotSave,
     None,
-}
\ No newline at end of file
+}
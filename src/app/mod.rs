@@ -4,30 +4,61 @@ mod file_operation;
 use std::path::PathBuf;
 
 use crate::{
+    algorithm_editor::AlgorithmEditor,
     app::{editor_variant::EditorVariant, file_operation::FileOperation},
     comment_line::editor::CommentsEditor,
+    consts::{
+        MAX_GRID_SPACING, MAX_LAYOUT_EDGE_LENGTH, MAX_LAYOUT_SPACING, MAX_RENDER_SCALE,
+        MIN_GRID_SPACING, MIN_LAYOUT_EDGE_LENGTH, MIN_LAYOUT_SPACING, MIN_RENDER_SCALE,
+    },
     edge_editor::EdgeEditor,
     edges_table::EdgesTable,
     error::GraphEditorError,
+    format::ExchangeFormat,
     graph_workspace::GraphWorkspace,
+    layout::{
+        layered::{LayerDirection, LayeredLayout},
+        presets::{PresetLayout, TreeStyle},
+    },
     node_editor::NodeEditor,
+    path_editor::PathEditor,
+    render::RenderOptions,
+    styling_editor::StylingEditor,
     toast::Toast,
     utils::image::{crop_color_image, save_color_image_to_png},
 };
-use eframe::egui::{self, ColorImage, Context, Margin, SidePanel, Ui, UserData, ViewportCommand};
+use eframe::egui::{
+    self, ColorImage, Context, Key, KeyboardShortcut, Margin, Modifiers, SidePanel, Ui, UserData,
+    ViewportCommand,
+};
 use egui_file_dialog::FileDialog;
 
+const UNDO_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+const REDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+const COPY_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::C);
+const CUT_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::X);
+const PASTE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::V);
+
 pub struct GraphEditor {
     graph_workspace: GraphWorkspace,
     node_editor: NodeEditor,
+    path_editor: PathEditor,
+    algorithm_editor: AlgorithmEditor,
+    styling_editor: StylingEditor,
     edges_table: EdgesTable,
     edge_editor: EdgeEditor,
     comments_editor: CommentsEditor,
     selected_editor: EditorVariant,
     file_dialog: FileDialog,
     file_operation: FileOperation,
+    render_options: RenderOptions,
+    layered_layout: LayeredLayout,
+    preset_layout: PresetLayout,
     current_file: Option<PathBuf>,
     toast: Option<Toast>,
+    /// Last copied subgraph, pasted from menu (shortcut pastes from system clipboard).
+    clipboard: Option<String>,
     taking_screenshot: bool,
     screenshot: Option<ColorImage>,
 }
@@ -37,22 +68,48 @@ impl Default for GraphEditor {
         Self {
             graph_workspace: GraphWorkspace::new(),
             node_editor: NodeEditor,
-            edges_table: EdgesTable,
+            path_editor: PathEditor::default(),
+            algorithm_editor: AlgorithmEditor::default(),
+            styling_editor: StylingEditor,
+            edges_table: EdgesTable::default(),
             edge_editor: EdgeEditor,
             comments_editor: CommentsEditor::new(),
             selected_editor: EditorVariant::Node,
             file_dialog: FileDialog::new(),
             file_operation: FileOperation::None,
+            render_options: RenderOptions::default(),
+            layered_layout: LayeredLayout::default(),
+            preset_layout: PresetLayout::default(),
             current_file: None,
             toast: None,
+            clipboard: None,
             taking_screenshot: false,
             screenshot: None,
         }
     }
 }
 
+impl GraphEditor {
+    /// Editor with document opened from file, errors are shown as toast.
+    pub fn with_file(ctx: &Context, file_path: PathBuf) -> Self {
+        let mut editor = Self::default();
+
+        match editor.graph_workspace.graph_from_file(&file_path) {
+            Ok(()) => editor.update_current_file(ctx, file_path),
+            Err(err) => editor.handle_error(err),
+        }
+
+        editor
+    }
+}
+
 impl eframe::App for GraphEditor {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_history_shortcuts(ctx);
+        self.handle_clipboard_events(ctx);
+
+        let selected_edge_id = self.graph_workspace.selected_edge_id();
+
         egui::CentralPanel::default().show(ctx, |ui| {
             self.show_menu(ui);
             self.show_editor_panel(ui);
@@ -63,8 +120,16 @@ impl eframe::App for GraphEditor {
             }
 
             self.graph_workspace.setup(ctx, ui);
-            self.graph_workspace.draw_components(ui);
+            self.graph_workspace.draw_components();
             self.handle_interactions();
+            self.graph_workspace.update_layout(ctx);
+            self.graph_workspace.seal_history_on_release(ctx);
+
+            // show edge picked on canvas or in table
+            let new_selected_edge_id = self.graph_workspace.selected_edge_id();
+            if new_selected_edge_id.is_some() && new_selected_edge_id != selected_edge_id {
+                self.selected_editor = EditorVariant::Edge;
+            }
 
             self.show_toast(ui);
 
@@ -95,8 +160,126 @@ impl GraphEditor {
                     self.file_operation = FileOperation::FileSaveAs;
                     self.file_dialog.save_file();
                 }
+
+                ui.separator();
+
+                for format in ExchangeFormat::ALL {
+                    if ui.button(format!("Import {}", format.name())).clicked() {
+                        self.file_operation = FileOperation::Import(format);
+                        self.file_dialog.pick_file();
+                    }
+                    if ui.button(format!("Export {}", format.name())).clicked() {
+                        self.file_operation = FileOperation::Export(format);
+                        self.file_dialog.save_file();
+                    }
+                }
+
+                ui.separator();
+
+                if ui.button("Export SVG").clicked() {
+                    self.file_operation = FileOperation::SvgExport;
+                    self.file_dialog.save_file();
+                }
+                if ui.button("Export PNG").clicked() {
+                    self.file_operation = FileOperation::PngExport;
+                    self.file_dialog.save_file();
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Scale");
+                    ui.add(
+                        egui::DragValue::new(&mut self.render_options.scale)
+                            .range(MIN_RENDER_SCALE..=MAX_RENDER_SCALE)
+                            .speed(0.05)
+                            .suffix("x"),
+                    );
+                    ui.label("Background");
+                    egui::color_picker::color_edit_button_srgba(
+                        ui,
+                        &mut self.render_options.background,
+                        egui::color_picker::Alpha::OnlyBlend,
+                    );
+                });
+            });
+
+            ui.menu_button("Edit", |ui| {
+                let undo = ui.add_enabled(
+                    self.graph_workspace.can_undo(),
+                    egui::Button::new("Undo")
+                        .shortcut_text(ui.ctx().format_shortcut(&UNDO_SHORTCUT)),
+                );
+                if undo.clicked() {
+                    self.graph_workspace.undo();
+                }
+
+                let redo = ui.add_enabled(
+                    self.graph_workspace.can_redo(),
+                    egui::Button::new("Redo")
+                        .shortcut_text(ui.ctx().format_shortcut(&REDO_SHORTCUT)),
+                );
+                if redo.clicked() {
+                    self.graph_workspace.redo();
+                }
+
+                ui.separator();
+
+                let has_selection = self.graph_workspace.selected_node_id().is_some();
+
+                let copy = ui.add_enabled(
+                    has_selection,
+                    egui::Button::new("Copy")
+                        .shortcut_text(ui.ctx().format_shortcut(&COPY_SHORTCUT)),
+                );
+                if copy.clicked() {
+                    let text = self.graph_workspace.copy_selection();
+                    self.put_to_clipboard(ui.ctx(), text);
+                }
+
+                let cut = ui.add_enabled(
+                    has_selection,
+                    egui::Button::new("Cut").shortcut_text(ui.ctx().format_shortcut(&CUT_SHORTCUT)),
+                );
+                if cut.clicked() {
+                    let text = self.graph_workspace.cut_selection();
+                    self.put_to_clipboard(ui.ctx(), text);
+                }
+
+                let paste = ui.add_enabled(
+                    self.clipboard.is_some(),
+                    egui::Button::new("Paste")
+                        .shortcut_text(ui.ctx().format_shortcut(&PASTE_SHORTCUT)),
+                );
+                if paste.clicked() {
+                    if let Some(text) = &self.clipboard {
+                        if let Err(err) = self.graph_workspace.paste(text) {
+                            self.handle_error(err);
+                        }
+                    }
+                }
+            });
+
+            ui.menu_button("View", |ui| {
+                if ui.button("Fit to graph").clicked() {
+                    self.graph_workspace.fit_view();
+                }
+                if ui.button("Reset view").clicked() {
+                    self.graph_workspace.reset_view();
+                }
+
+                ui.separator();
+
+                let snapping = self.graph_workspace.snapping_mut();
+                ui.checkbox(&mut snapping.show_grid, "Show grid");
+                ui.checkbox(&mut snapping.snap_to_grid, "Snap to grid");
+                ui.add(
+                    egui::DragValue::new(&mut snapping.grid_spacing)
+                        .range(MIN_GRID_SPACING..=MAX_GRID_SPACING)
+                        .prefix("Grid spacing: "),
+                );
+                ui.checkbox(&mut snapping.smart_guides, "Smart guides");
             });
 
+            ui.menu_button("Layout", |ui| self.show_layout_menu(ui));
+
             if ui.button("New").clicked() {
                 self.graph_workspace.add_node();
             }
@@ -107,14 +290,124 @@ impl GraphEditor {
                 EditorVariant::CommentLine,
                 "Comment line",
             );
+            ui.selectable_value(&mut self.selected_editor, EditorVariant::Path, "Path");
+            ui.selectable_value(
+                &mut self.selected_editor,
+                EditorVariant::Algorithm,
+                "Algorithms",
+            );
+            ui.selectable_value(&mut self.selected_editor, EditorVariant::Styling, "Styling");
 
             if ui.button("Screenshot").clicked() {
                 self.taking_screenshot = true;
             }
+
+            ui.label(format!("{:.0}%", self.graph_workspace.zoom() * 100.0));
         });
     }
 
+    fn show_layout_menu(&mut self, ui: &mut Ui) {
+        ui.label("Force-directed");
+        if ui.button("Run once").clicked() {
+            self.graph_workspace.run_force_layout();
+            ui.close_menu();
+        }
+        let mut continuous = self.graph_workspace.is_continuous_layout();
+        if ui.checkbox(&mut continuous, "Run continuously").changed() {
+            self.graph_workspace.set_continuous_layout(continuous);
+        }
+        ui.add(
+            egui::DragValue::new(&mut self.graph_workspace.force_layout_mut().edge_length)
+                .range(MIN_LAYOUT_EDGE_LENGTH..=MAX_LAYOUT_EDGE_LENGTH)
+                .prefix("Edge length: "),
+        );
+
+        ui.separator();
+
+        ui.label("Layered");
+        ui.horizontal(|ui| {
+            let direction = &mut self.layered_layout.direction;
+            ui.selectable_value(direction, LayerDirection::TopToBottom, "Top to bottom");
+            ui.selectable_value(direction, LayerDirection::LeftToRight, "Left to right");
+        });
+        ui.add(
+            egui::DragValue::new(&mut self.layered_layout.layer_spacing)
+                .range(MIN_LAYOUT_SPACING..=MAX_LAYOUT_SPACING)
+                .prefix("Layer spacing: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut self.layered_layout.node_spacing)
+                .range(MIN_LAYOUT_SPACING..=MAX_LAYOUT_SPACING)
+                .prefix("Node spacing: "),
+        );
+        if ui.button("Apply layered").clicked() {
+            self.graph_workspace
+                .run_layered_layout(&self.layered_layout);
+            ui.close_menu();
+        }
+
+        ui.separator();
+
+        ui.label("Presets");
+        let presets = &mut self.preset_layout;
+        ui.add(
+            egui::DragValue::new(&mut presets.node_spacing)
+                .range(MIN_LAYOUT_SPACING..=MAX_LAYOUT_SPACING)
+                .prefix("Node spacing: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut presets.level_spacing)
+                .range(MIN_LAYOUT_SPACING..=MAX_LAYOUT_SPACING)
+                .prefix("Level spacing: "),
+        );
+
+        ui.horizontal(|ui| {
+            if ui.button("Circle").clicked() {
+                let targets = self.preset_layout.circle(self.graph_workspace.graph());
+                self.graph_workspace.animate_layout(targets);
+                ui.close_menu();
+            }
+            ui.checkbox(&mut self.preset_layout.circle_by_label, "By label");
+        });
+        if ui.button("Grid").clicked() {
+            let targets = self.preset_layout.grid(self.graph_workspace.graph());
+            self.graph_workspace.animate_layout(targets);
+            ui.close_menu();
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .button("Tree")
+                .on_hover_text("Rooted at selected node")
+                .clicked()
+            {
+                match self.graph_workspace.selected_node_id() {
+                    Some(root) => {
+                        let targets = self.preset_layout.tree(self.graph_workspace.graph(), root);
+                        self.graph_workspace.animate_layout(targets);
+                    }
+                    None => self.handle_error(GraphEditorError::NoRootNode),
+                }
+                ui.close_menu();
+            }
+            let style = &mut self.preset_layout.tree_style;
+            ui.selectable_value(style, TreeStyle::Tidy, "Tidy");
+            ui.selectable_value(style, TreeStyle::Radial, "Radial");
+        });
+        if ui.button("Bipartite").clicked() {
+            match self.preset_layout.bipartite(self.graph_workspace.graph()) {
+                Ok(targets) => self.graph_workspace.animate_layout(targets),
+                Err(err) => self.handle_error(err),
+            }
+            ui.close_menu();
+        }
+    }
+
     fn show_editor_panel(&mut self, ui: &mut Ui) {
+        // path and algorithm result are shown only while their editor is open
+        self.graph_workspace.set_highlighted_path(None);
+        self.graph_workspace.set_recolor(None);
+        self.graph_workspace.set_overlay(None);
+
         SidePanel::right("editor_panel")
             .exact_width(250.0)
             .show(ui.ctx(), |ui| {
@@ -128,8 +421,16 @@ impl GraphEditor {
                             self.edge_editor.ui(ui, &mut self.graph_workspace);
                         }
                         EditorVariant::CommentLine => {
-                            self.comments_editor
-                                .ui(ui, self.graph_workspace.comment_lines());
+                            self.comments_editor.ui(ui, &mut self.graph_workspace);
+                        }
+                        EditorVariant::Path => {
+                            self.path_editor.ui(ui, &mut self.graph_workspace);
+                        }
+                        EditorVariant::Algorithm => {
+                            self.algorithm_editor.ui(ui, &mut self.graph_workspace);
+                        }
+                        EditorVariant::Styling => {
+                            self.styling_editor.ui(ui, &mut self.graph_workspace);
                         }
                     });
             });
@@ -153,7 +454,7 @@ impl GraphEditor {
                 if let Some(file_path) = self.file_dialog.take_picked() {
                     self.graph_workspace.graph_from_file(&file_path)?;
 
-                    self.update_current_file(ui, file_path);
+                    self.update_current_file(ui.ctx(), file_path);
                     self.file_operation = FileOperation::None;
                 }
             }
@@ -178,12 +479,47 @@ impl GraphEditor {
                 if let Some(file_path) = self.file_dialog.take_picked() {
                     self.graph_workspace.save_graph_to_file(&file_path)?;
 
-                    self.update_current_file(ui, file_path);
+                    self.update_current_file(ui.ctx(), file_path);
 
                     self.toast = Some(Toast::success("Saved successfully"));
                     self.file_operation = FileOperation::None;
                 }
             }
+            FileOperation::Import(format) => {
+                if let Some(file_path) = self.file_dialog.take_picked() {
+                    self.file_operation = FileOperation::None;
+                    self.graph_workspace.import_graph(&file_path, format)?;
+
+                    // imported file is not saved as document
+                    self.current_file = None;
+                    self.update_window_title(ui.ctx(), "Graph Editor");
+                }
+            }
+            FileOperation::Export(format) => {
+                if let Some(file_path) = self.file_dialog.take_picked() {
+                    self.file_operation = FileOperation::None;
+                    self.graph_workspace.export_graph(&file_path, format)?;
+
+                    self.toast = Some(Toast::success("Exported successfully"));
+                }
+            }
+            FileOperation::SvgExport => {
+                if let Some(file_path) = self.file_dialog.take_picked() {
+                    self.file_operation = FileOperation::None;
+                    self.graph_workspace.export_svg(&file_path)?;
+
+                    self.toast = Some(Toast::success("Exported successfully"));
+                }
+            }
+            FileOperation::PngExport => {
+                if let Some(file_path) = self.file_dialog.take_picked() {
+                    self.file_operation = FileOperation::None;
+                    self.graph_workspace
+                        .export_png(&file_path, &self.render_options)?;
+
+                    self.toast = Some(Toast::success("Exported successfully"));
+                }
+            }
             FileOperation::ScreenshotSave => {
                 if let Some(file_path) = self.file_dialog.take_picked() {
                     if let Some(image) = &self.screenshot {
@@ -201,7 +537,16 @@ impl GraphEditor {
     }
 
     fn handle_interactions(&mut self) {
+        // pointer drags view, not graph
+        if self.graph_workspace.is_panning() {
+            self.graph_workspace.set_dragging_node(None);
+            self.graph_workspace.clear_hovered_edge();
+            return;
+        }
+
         if self.selected_editor == EditorVariant::CommentLine {
+            self.graph_workspace.clear_hovered_edge();
+
             if self.comments_editor.draw_mode_active() {
                 self.graph_workspace
                     .handle_comment_draw(self.comments_editor.selected_stroke());
@@ -214,6 +559,56 @@ impl GraphEditor {
         }
     }
 
+    /// Handle Ctrl+Z / Ctrl+Shift+Z, unless some text field is focused
+    /// (it has its own undo).
+    fn handle_history_shortcuts(&mut self, ctx: &Context) {
+        if ctx.memory(|m| m.focused().is_some()) {
+            return;
+        }
+
+        // redo is checked first, because undo shortcut matches it too
+        if ctx.input_mut(|i| i.consume_shortcut(&REDO_SHORTCUT)) {
+            self.graph_workspace.redo();
+        } else if ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT)) {
+            self.graph_workspace.undo();
+        }
+    }
+
+    /// Handle copy, cut and paste of nodes, unless some text field is focused
+    /// (it handles clipboard itself).
+    fn handle_clipboard_events(&mut self, ctx: &Context) {
+        if ctx.memory(|m| m.focused().is_some()) {
+            return;
+        }
+
+        for event in ctx.input(|i| i.events.clone()) {
+            match event {
+                egui::Event::Copy => {
+                    let text = self.graph_workspace.copy_selection();
+                    self.put_to_clipboard(ctx, text);
+                }
+                egui::Event::Cut => {
+                    let text = self.graph_workspace.cut_selection();
+                    self.put_to_clipboard(ctx, text);
+                }
+                // text may come from another editor window
+                egui::Event::Paste(text) => {
+                    if let Err(err) = self.graph_workspace.paste(&text) {
+                        self.handle_error(err);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn put_to_clipboard(&mut self, ctx: &Context, text: Option<String>) {
+        if let Some(text) = text {
+            ctx.copy_text(text.clone());
+            self.clipboard = Some(text);
+        }
+    }
+
     fn handle_error(&mut self, err: GraphEditorError) {
         self.toast = Some(Toast::error(err.message()))
     }
@@ -244,7 +639,7 @@ impl GraphEditor {
                         None
                     }
                 })
-                .last()
+                .next_back()
         });
 
         if let Some(image) = image {
@@ -269,8 +664,8 @@ impl GraphEditor {
         ctx.send_viewport_cmd(ViewportCommand::Title(title.to_owned()));
     }
 
-    fn update_current_file(&mut self, ui: &mut Ui, file_path: PathBuf) {
-        self.update_window_title(ui.ctx(), &format!("Graph Editor | {}", file_path.display()));
+    fn update_current_file(&mut self, ctx: &Context, file_path: PathBuf) {
+        self.update_window_title(ctx, &format!("Graph Editor | {}", file_path.display()));
         self.current_file = Some(file_path);
     }
 }
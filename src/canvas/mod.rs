@@ -0,0 +1,528 @@
+pub mod view;
+
+use eframe::{
+    egui::{
+        self, vec2, Color32, Key, Painter, Pos2, Rect, Response, Sense, Shape, Stroke, Ui, Vec2,
+    },
+    epaint::CubicBezierShape,
+};
+
+use crate::{
+    canvas::view::View,
+    comment_line::{group::CommentsGroup, CommentLine},
+    consts::{
+        CONTROL_OFFSET, EDGE_HANDLE_RADIUS, EDGE_HIT_TOLERANCE, GRID_COLOR, GUIDE_COLOR,
+        MIN_GRID_SCREEN_SPACING, MIN_NODE_SIZE, PATH_COLOR, SCROLL_ZOOM_SPEED, SELECTION_COLOR,
+        SELECTION_OUTLINE_OFFSET,
+    },
+    graph::{edge::EdgeId, path::GraphPath, Graph, NodeId},
+    layout::align::Guides,
+    scene::{self, ColorMap},
+    selection::Selection,
+    utils::geometry::{
+        edge::{convex_polygon_contains, edges_geometry},
+        route::EdgeRoutes,
+        shape::ShapeGeometry,
+    },
+};
+
+#[derive(Default)]
+pub struct Canvas {
+    response: Option<Response>,
+    painter: Option<Painter>,
+    view: View,
+}
+
+// creation, setup and utils
+impl Canvas {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn response(&self) -> &Response {
+        self.response
+            .as_ref()
+            .expect("Canvas::setup() must be called first!")
+    }
+
+    fn painter(&self) -> &Painter {
+        self.painter
+            .as_ref()
+            .expect("Canvas::setup() must be called first!")
+    }
+
+    pub fn painter_rect(&self) -> Rect {
+        self.painter().clip_rect()
+    }
+
+    pub fn pixels_per_point(&self) -> f32 {
+        self.painter().pixels_per_point()
+    }
+
+    pub fn setup(&mut self, ctx: &eframe::egui::Context, ui: &mut Ui) {
+        let size = ctx.available_rect().shrink2(vec2(8.0, 18.0)).size();
+
+        let (response, painter) = ui.allocate_painter(size, Sense::click_and_drag());
+        painter.rect_filled(response.rect, 0.0, Color32::WHITE);
+
+        self.response = Some(response);
+        self.painter = Some(painter);
+
+        self.handle_view_input(ctx);
+    }
+
+    pub fn set_cursor_icon(&self, cursor_icon: egui::CursorIcon) {
+        self.response().ctx.set_cursor_icon(cursor_icon);
+    }
+}
+
+// view
+impl Canvas {
+    pub fn view(&self) -> &View {
+        &self.view
+    }
+
+    pub fn view_mut(&mut self) -> &mut View {
+        &mut self.view
+    }
+
+    /// Pointer position over canvas in world coordinates.
+    pub fn hover_pos(&self) -> Option<Pos2> {
+        self.response()
+            .hover_pos()
+            .map(|pos| self.view.to_world(pos))
+    }
+
+    /// Position of pointer interacting with canvas in world coordinates.
+    pub fn interact_pos(&self) -> Option<Pos2> {
+        self.response()
+            .interact_pointer_pos()
+            .map(|pos| self.view.to_world(pos))
+    }
+
+    /// View is panned while middle button or space is held.
+    pub fn is_panning(&self) -> bool {
+        let ctx = &self.response().ctx;
+
+        // space typed into focused text field doesn't pan
+        let space_down =
+            ctx.memory(|m| m.focused().is_none()) && ctx.input(|i| i.key_down(Key::Space));
+
+        space_down || ctx.input(|i| i.pointer.middle_down())
+    }
+
+    /// Pan by dragging and zoom around pointer by scrolling.
+    fn handle_view_input(&mut self, ctx: &egui::Context) {
+        if self.is_panning() {
+            let response = self.response();
+
+            if response.dragged() {
+                let delta = response.drag_delta();
+                self.set_cursor_icon(egui::CursorIcon::Grabbing);
+                self.view.pan(delta);
+            } else if response.hovered() {
+                self.set_cursor_icon(egui::CursorIcon::Grab);
+            }
+        }
+
+        if let Some(pointer_pos) = self.response().hover_pos() {
+            let (scroll, zoom) = ctx.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = zoom * (scroll * SCROLL_ZOOM_SPEED).exp();
+
+            if factor != 1.0 {
+                self.view.zoom_around(pointer_pos, factor);
+            }
+        }
+    }
+
+    /// Draw world shapes in screen coordinates.
+    fn add_shapes(&self, shapes: impl IntoIterator<Item = Shape>) {
+        let transform = self.view.transform();
+
+        self.painter().extend(shapes.into_iter().map(|mut shape| {
+            shape.transform(transform);
+            shape
+        }));
+    }
+}
+
+// grid
+impl Canvas {
+    /// Grid lines in world coordinates, sparser when zoomed out.
+    pub fn draw_grid(&self, spacing: f32) {
+        let mut spacing = spacing;
+        while spacing * self.view.zoom() < MIN_GRID_SCREEN_SPACING {
+            spacing *= 2.0;
+        }
+
+        let rect = self.painter_rect();
+        let (min, max) = (self.view.to_world(rect.min), self.view.to_world(rect.max));
+        let stroke = Stroke::new(1.0, GRID_COLOR);
+
+        let mut x = (min.x / spacing).ceil() * spacing;
+        while x <= max.x {
+            let screen_x = self.view.to_screen(Pos2::new(x, 0.0)).x;
+            self.painter().vline(screen_x, rect.y_range(), stroke);
+            x += spacing;
+        }
+        let mut y = (min.y / spacing).ceil() * spacing;
+        while y <= max.y {
+            let screen_y = self.view.to_screen(Pos2::new(0.0, y)).y;
+            self.painter().hline(rect.x_range(), screen_y, stroke);
+            y += spacing;
+        }
+    }
+
+    /// Lines through centers of nodes which dragged node is aligned with.
+    pub fn draw_guides(&self, guides: &Guides) {
+        let rect = self.painter_rect();
+        let stroke = Stroke::new(1.0, GUIDE_COLOR);
+
+        if let Some(x) = guides.vertical {
+            let screen_x = self.view.to_screen(Pos2::new(x, 0.0)).x;
+            self.painter().vline(screen_x, rect.y_range(), stroke);
+        }
+        if let Some(y) = guides.horizontal {
+            let screen_y = self.view.to_screen(Pos2::new(0.0, y)).y;
+            self.painter().hline(rect.x_range(), screen_y, stroke);
+        }
+    }
+}
+
+// edges
+impl Canvas {
+    /// Draw possible edge from new_edge_start node to pointer pos.
+    fn draw_possible_edge(&mut self, new_edge_start: Option<NodeId>, graph: &Graph) {
+        if let (Some(edge_start), Some(pointer_pos)) = (new_edge_start, self.hover_pos()) {
+            self.set_cursor_icon(egui::CursorIcon::PointingHand);
+            let start_node = &graph.nodes()[&edge_start];
+
+            if start_node.is_clicked(pointer_pos) {
+                // draw loop
+                // Start point is north of node
+                let start_pos = start_node.border_point_in_direction(-Vec2::Y);
+                // End point is west of node
+                let end_pos = start_node.border_point_in_direction(-Vec2::X);
+
+                // Calc offset based on node size
+                let offset = CONTROL_OFFSET * (start_node.size / MIN_NODE_SIZE);
+
+                // Calc controls for curve
+                let control1 = start_pos - Vec2::new(0.0, offset);
+                let control2 = end_pos - Vec2::new(offset, 0.0);
+
+                self.add_shapes([CubicBezierShape::from_points_stroke(
+                    [start_pos, control1, control2, end_pos],
+                    false,
+                    Color32::TRANSPARENT,
+                    Stroke::new(2.0, Color32::BLACK),
+                )
+                .into()]);
+            } else {
+                self.add_shapes([Shape::line_segment(
+                    [start_node.position, pointer_pos],
+                    Stroke::new(2.0, Color32::BLACK),
+                )]);
+            }
+        }
+    }
+}
+
+// comment lines
+impl Canvas {
+    /// Extend current comment line with pointer position.
+    /// Return true if comment line was extended.
+    pub fn handle_comment_draw(
+        &mut self,
+        stroke: Stroke,
+        comment_lines: &mut CommentsGroup,
+    ) -> bool {
+        self.set_cursor_icon(egui::CursorIcon::Cell);
+
+        if comment_lines.is_empty() {
+            comment_lines.insert(CommentLine::from(stroke));
+        }
+
+        let pointer_pos = self.interact_pos();
+
+        let current_line = comment_lines.last_added_mut().unwrap();
+
+        // update selected params
+        if current_line.is_empty() {
+            current_line.stroke = stroke;
+        }
+
+        if let Some(pointer_pos) = pointer_pos {
+            // if last point of line is not in pointer_pos
+            // => line is extended (added segment)
+            if current_line.points.last() != Some(&pointer_pos) {
+                current_line.points.push(pointer_pos);
+                return true;
+            }
+        } else if !current_line.is_empty() {
+            comment_lines.insert(CommentLine::new());
+        }
+
+        false
+    }
+
+    /// Erase comment line under pointer.
+    /// Return true if comment line was erased.
+    pub fn handle_comment_erase(&mut self, comment_lines: &mut CommentsGroup) -> bool {
+        let square_center = match self.response().hover_pos() {
+            Some(center) => center,
+            None => return false,
+        };
+
+        self.set_cursor_icon(egui::CursorIcon::None);
+
+        // Create eraser square in hover_pos
+        let hover_square = Rect::from_center_size(square_center, Vec2::new(10.0, 10.0));
+
+        self.painter().rect_stroke(
+            hover_square,
+            0.0,
+            Stroke::new(1.0, Color32::BLACK),
+            egui::StrokeKind::Outside,
+        );
+
+        if let Some(pointer_pos) = self.response().interact_pointer_pos() {
+            // Create eraser square in pointer_pos
+            let interact_square = Rect::from_center_size(pointer_pos, Vec2::new(10.0, 10.0));
+
+            self.painter().rect_stroke(
+                interact_square,
+                0.0,
+                Stroke::new(1.0, Color32::BLACK),
+                egui::StrokeKind::Outside,
+            );
+
+            // Find comment_line intersected by interact_square (in world coordinates)
+            let world_square = Rect::from_center_size(
+                self.view.to_world(pointer_pos),
+                interact_square.size() / self.view.zoom(),
+            );
+            let mut selected_line_id = None;
+            for (id, line) in comment_lines.iter() {
+                if line.is_intersect_square(world_square) {
+                    selected_line_id = Some(id);
+                    break;
+                }
+            }
+
+            // Erase comment_line intersected by interact_square
+            if let Some(id) = selected_line_id {
+                comment_lines.remove(*id);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Draw possible edge, all nodes and edges, comment lines and selection.
+    pub fn draw_components(
+        &mut self,
+        graph: &Graph,
+        routes: &EdgeRoutes,
+        new_edge_start: Option<NodeId>,
+        comment_lines: &CommentsGroup,
+        highlight: &SelectionHighlight,
+    ) {
+        self.draw_possible_edge(new_edge_start, graph);
+
+        // drawn under edges, so that they look outlined
+        self.draw_edges_highlight(graph, routes, highlight);
+
+        let shapes = self
+            .painter()
+            .fonts(|fonts| scene::shapes(graph, Some(routes), comment_lines, fonts));
+        self.add_shapes(shapes);
+
+        if let Some(path) = highlight.path {
+            self.draw_nodes_highlight(graph, path.nodes.iter().map(|id| (id, PATH_COLOR)));
+        }
+        if let Some(overlay) = highlight.overlay {
+            self.draw_nodes_highlight(graph, overlay.nodes.iter().map(|(id, color)| (id, *color)));
+        }
+        self.draw_nodes_highlight(
+            graph,
+            highlight.nodes.iter().map(|id| (id, SELECTION_COLOR)),
+        );
+        self.draw_edge_handles(graph, routes, highlight);
+        self.draw_selection_rect(highlight.rect);
+    }
+}
+
+/// Selected and hovered components, highlighted over graph.
+pub struct SelectionHighlight<'a> {
+    pub nodes: &'a Selection<NodeId>,
+    pub edges: &'a Selection<EdgeId>,
+    pub hovered_edge: Option<EdgeId>,
+    /// Bend point dragged by user: its edge and index.
+    pub dragging_bend: Option<(EdgeId, usize)>,
+    /// Found shortest path.
+    pub path: Option<&'a GraphPath>,
+    /// Highlight colors of replayed algorithm steps.
+    pub overlay: Option<&'a ColorMap>,
+    /// Rectangle selection in world coordinates.
+    pub rect: Option<Rect>,
+}
+
+// selection
+impl Canvas {
+    /// Outline given nodes with given colors.
+    fn draw_nodes_highlight<'a>(
+        &self,
+        graph: &Graph,
+        nodes: impl Iterator<Item = (&'a NodeId, Color32)>,
+    ) {
+        for (node, color) in nodes.filter_map(|(id, color)| Some((graph.node(id)?, color))) {
+            let outline = ShapeGeometry::new(
+                node.shape,
+                self.view.to_screen(node.position),
+                node.half_size() * self.view.zoom(),
+            )
+            .expand(SELECTION_OUTLINE_OFFSET);
+
+            self.painter()
+                .add(outline.shape(Color32::TRANSPARENT, Stroke::new(2.0, color)));
+        }
+    }
+
+    /// Draw wide stroke under selected, hovered, path and overlay edges.
+    fn draw_edges_highlight(
+        &self,
+        graph: &Graph,
+        routes: &EdgeRoutes,
+        highlight: &SelectionHighlight,
+    ) {
+        if highlight.edges.is_empty()
+            && highlight.hovered_edge.is_none()
+            && highlight.path.is_none()
+            && highlight.overlay.is_none()
+        {
+            return;
+        }
+
+        let outline = 2.0 * SELECTION_OUTLINE_OFFSET / self.view.zoom();
+
+        let geometry = edges_geometry(graph, Some(routes));
+        let shapes = geometry.into_iter().filter_map(|(id, geometry)| {
+            let color = if highlight.edges.contains(&id) {
+                SELECTION_COLOR
+            } else if highlight.hovered_edge == Some(id) {
+                SELECTION_COLOR.gamma_multiply(0.4)
+            } else if highlight.path.is_some_and(|path| path.edges.contains(&id)) {
+                PATH_COLOR
+            } else if let Some(color) = highlight.overlay.and_then(|o| o.edges.get(&id)) {
+                *color
+            } else {
+                return None;
+            };
+
+            let edge = &graph.edges()[&id];
+            Some(scene::curve_shape(
+                &geometry.curve,
+                Stroke::new(edge.width + outline, color),
+            ))
+        });
+
+        self.add_shapes(shapes.collect::<Vec<_>>());
+    }
+
+    /// Handles of selected edges: filled ones at ends to reconnect edge
+    /// and bend points of polyline edges, dragged bend point is filled too.
+    fn draw_edge_handles(
+        &self,
+        graph: &Graph,
+        routes: &EdgeRoutes,
+        highlight: &SelectionHighlight,
+    ) {
+        if highlight.edges.is_empty() {
+            return;
+        }
+
+        for (id, geometry) in edges_geometry(graph, Some(routes)) {
+            if !highlight.edges.contains(&id) {
+                continue;
+            }
+            for point in [geometry.curve.start(), geometry.curve.end()] {
+                self.painter().circle(
+                    self.view.to_screen(point),
+                    EDGE_HANDLE_RADIUS,
+                    SELECTION_COLOR,
+                    Stroke::new(1.5, Color32::WHITE),
+                );
+            }
+        }
+
+        for &id in highlight.edges.iter() {
+            let Some(edge) = graph.edge(&id).filter(|edge| edge.is_polyline()) else {
+                continue;
+            };
+
+            for (index, point) in edge.bend_points.iter().enumerate() {
+                let fill = if highlight.dragging_bend == Some((id, index)) {
+                    SELECTION_COLOR
+                } else {
+                    Color32::WHITE
+                };
+                self.painter().circle(
+                    self.view.to_screen(*point),
+                    EDGE_HANDLE_RADIUS,
+                    fill,
+                    Stroke::new(1.5, SELECTION_COLOR),
+                );
+            }
+        }
+    }
+
+    fn draw_selection_rect(&self, selection_rect: Option<Rect>) {
+        if let Some(rect) = selection_rect {
+            let rect = self.view.transform() * rect;
+
+            self.painter()
+                .rect_filled(rect, 0.0, SELECTION_COLOR.gamma_multiply(0.1));
+            self.painter().rect_stroke(
+                rect,
+                0.0,
+                Stroke::new(1.0, SELECTION_COLOR),
+                egui::StrokeKind::Inside,
+            );
+        }
+    }
+
+    /// Edge closest to pointer: by its curve or label background.
+    /// Orthogonal edges missing in `routes` are routed now.
+    pub fn edge_at(
+        &self,
+        graph: &Graph,
+        routes: Option<&EdgeRoutes>,
+        pointer_pos: Pos2,
+    ) -> Option<EdgeId> {
+        let tolerance = EDGE_HIT_TOLERANCE / self.view.zoom();
+
+        self.painter().fonts(|fonts| {
+            edges_geometry(graph, routes)
+                .into_iter()
+                .filter_map(|(id, geometry)| {
+                    let edge = &graph.edges()[&id];
+
+                    let on_label = scene::edge_label_background(edge, &geometry, fonts)
+                        .is_some_and(|background| {
+                            convex_polygon_contains(&background, pointer_pos)
+                        });
+                    let distance = if on_label {
+                        0.0
+                    } else {
+                        geometry.curve.distance(pointer_pos) - edge.width / 2.0
+                    };
+
+                    (distance <= tolerance).then_some((id, distance))
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(id, _)| id)
+        })
+    }
+}
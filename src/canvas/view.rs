@@ -0,0 +1,59 @@
+use eframe::{
+    egui::{Pos2, Rect, Vec2},
+    emath::TSTransform,
+};
+
+use crate::consts::{MAX_ZOOM, MIN_ZOOM};
+
+/// Transform from world coordinates (stored in document) to screen coordinates.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct View {
+    transform: TSTransform,
+}
+
+impl View {
+    pub fn transform(&self) -> TSTransform {
+        self.transform
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.transform.scaling
+    }
+
+    pub fn to_screen(&self, world_pos: Pos2) -> Pos2 {
+        self.transform * world_pos
+    }
+
+    pub fn to_world(&self, screen_pos: Pos2) -> Pos2 {
+        self.transform.inverse() * screen_pos
+    }
+
+    /// Move view by screen delta.
+    pub fn pan(&mut self, delta: Vec2) {
+        self.transform.translation += delta;
+    }
+
+    /// Multiply zoom by factor, keeping world point under screen position in place.
+    pub fn zoom_around(&mut self, screen_pos: Pos2, factor: f32) {
+        let zoom = (self.zoom() * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let world_pos = self.to_world(screen_pos);
+
+        self.transform.scaling = zoom;
+        self.transform.translation = screen_pos.to_vec2() - world_pos.to_vec2() * zoom;
+    }
+
+    /// Zoom and center view, so that world rect fills screen rect.
+    pub fn fit(&mut self, world_rect: Rect, screen_rect: Rect) {
+        let zoom = (screen_rect.width() / world_rect.width())
+            .min(screen_rect.height() / world_rect.height())
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+
+        self.transform.scaling = zoom;
+        self.transform.translation =
+            screen_rect.center().to_vec2() - world_rect.center().to_vec2() * zoom;
+    }
+
+    pub fn reset(&mut self) {
+        self.transform = TSTransform::IDENTITY;
+    }
+}
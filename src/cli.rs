@@ -0,0 +1,269 @@
+//! Command line interface: conversion, rendering and validation without window.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use eframe::egui::Color32;
+
+use crate::{
+    consts::{MAX_RENDER_SCALE, MIN_RENDER_SCALE},
+    document::Document,
+    error::GraphEditorError,
+    format::{color_from_str, svg, ExchangeFormat},
+    graph::styling,
+    render::{self, RenderOptions},
+};
+
+pub const USAGE: &str = "\
+Usage:
+  graph-editor-egui [FILE]                    open editor, optionally with document FILE
+  graph-editor-egui convert INPUT OUTPUT      convert between graph formats
+  graph-editor-egui render INPUT OUTPUT [--scale N | --dpi N] [--background COLOR]
+                                              render graph to PNG or SVG image
+  graph-editor-egui check INPUT               print validation errors
+  graph-editor-egui help                      print this message
+
+Formats are chosen by file extension:
+  .json           editor document
+  .dot, .gv       Graphviz DOT
+  .graphml        GraphML
+  .png, .svg      images (render only)";
+
+pub enum Command {
+    Gui(Option<PathBuf>),
+    Convert {
+        input: PathBuf,
+        output: PathBuf,
+    },
+    Render {
+        input: PathBuf,
+        output: PathBuf,
+        options: RenderOptions,
+    },
+    Check {
+        input: PathBuf,
+    },
+    Help,
+}
+
+/// Kind of file, chosen by its extension.
+#[derive(Clone, Copy)]
+enum FileKind {
+    Document,
+    Exchange(ExchangeFormat),
+    Svg,
+    Png,
+}
+
+impl FileKind {
+    fn from_path(path: &Path) -> Option<Self> {
+        if let Some(format) = ExchangeFormat::from_path(path) {
+            return Some(FileKind::Exchange(format));
+        }
+
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(FileKind::Document),
+            "svg" => Some(FileKind::Svg),
+            "png" => Some(FileKind::Png),
+            _ => None,
+        }
+    }
+}
+
+impl Command {
+    /// Parse command from arguments (without program name).
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+
+        let command = match args.next().as_deref() {
+            None => return Ok(Command::Gui(None)),
+            Some("help" | "--help" | "-h") => Command::Help,
+            Some("convert") => Command::Convert {
+                input: positional(&mut args, "INPUT")?,
+                output: positional(&mut args, "OUTPUT")?,
+            },
+            Some("render") => {
+                let input = positional(&mut args, "INPUT")?;
+                let output = positional(&mut args, "OUTPUT")?;
+                let options = parse_render_options(&mut args)?;
+
+                Command::Render {
+                    input,
+                    output,
+                    options,
+                }
+            }
+            Some("check") => Command::Check {
+                input: positional(&mut args, "INPUT")?,
+            },
+            Some(option) if option.starts_with('-') => {
+                return Err(format!("unknown option `{option}`"))
+            }
+            Some(file) => Command::Gui(Some(PathBuf::from(file))),
+        };
+
+        match args.next() {
+            Some(extra) => Err(format!("unexpected argument `{extra}`")),
+            None => Ok(command),
+        }
+    }
+}
+
+fn positional(args: &mut impl Iterator<Item = String>, name: &str) -> Result<PathBuf, String> {
+    match args.next() {
+        Some(arg) if !arg.starts_with("--") => Ok(PathBuf::from(arg)),
+        _ => Err(format!("missing {name} argument")),
+    }
+}
+
+fn parse_render_options(args: &mut impl Iterator<Item = String>) -> Result<RenderOptions, String> {
+    let mut options = RenderOptions::default();
+
+    while let Some(option) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value of `{option}`"))
+        };
+
+        match option.as_str() {
+            "--scale" => options.scale = parse_number(&value()?)?,
+            "--dpi" => options.scale = RenderOptions::scale_from_dpi(parse_number(&value()?)?),
+            "--background" => {
+                let value = value()?;
+                options.background = color_from_str(&value)
+                    .map(Color32::from)
+                    .ok_or_else(|| format!("invalid color `{value}`"))?;
+            }
+            _ => return Err(format!("unknown option `{option}`")),
+        }
+    }
+
+    if !(MIN_RENDER_SCALE..=MAX_RENDER_SCALE).contains(&options.scale) {
+        return Err(format!(
+            "scale {} is out of range [{MIN_RENDER_SCALE}; {MAX_RENDER_SCALE}]",
+            options.scale
+        ));
+    }
+
+    Ok(options)
+}
+
+fn parse_number(value: &str) -> Result<f32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number `{value}`"))
+}
+
+/// Run command which doesn't need window.
+pub fn run(command: Command) -> ExitCode {
+    let result = match command {
+        Command::Gui(_) => unreachable!("editor is started by main"),
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Command::Convert { input, output } => convert(&input, &output),
+        Command::Render {
+            input,
+            output,
+            options,
+        } => render(&input, &output, &options),
+        Command::Check { input } => check(&input),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn convert(input: &Path, output: &Path) -> Result<(), String> {
+    let document = open_valid(input)?;
+
+    match file_kind(output)? {
+        FileKind::Document => document.save_to_file(output),
+        FileKind::Exchange(format) => fs::write(output, format.export(&document.graph))
+            .map_err(|_| GraphEditorError::FailedSaveFile),
+        FileKind::Svg | FileKind::Png => {
+            return Err(format!(
+                "`{}` is an image, use `render` command",
+                output.display()
+            ))
+        }
+    }
+    .map_err(|err| err.message())
+}
+
+fn render(input: &Path, output: &Path, options: &RenderOptions) -> Result<(), String> {
+    let document = open_valid(input)?;
+    let styled = styling::apply_rules(&document.graph);
+    let graph = styled.as_ref().unwrap_or(&document.graph);
+    let comment_lines = &document.comment_lines;
+
+    match file_kind(output)? {
+        FileKind::Png => render::save_png(output, graph, comment_lines, options),
+        FileKind::Svg => fs::write(output, svg::export(graph, comment_lines))
+            .map_err(|_| GraphEditorError::FailedSaveFile),
+        FileKind::Document | FileKind::Exchange(_) => {
+            return Err(format!(
+                "`{}` is not an image, use `convert` command",
+                output.display()
+            ))
+        }
+    }
+    .map_err(|err| err.message())
+}
+
+fn check(input: &Path) -> Result<(), String> {
+    let document = open(input)?;
+    let errors = document.graph.validate();
+
+    for error in &errors {
+        println!("{}: {error}", input.display());
+    }
+
+    match errors.len() {
+        0 => Ok(()),
+        count => Err(format!("{count} problem(s) found")),
+    }
+}
+
+fn file_kind(path: &Path) -> Result<FileKind, String> {
+    FileKind::from_path(path).ok_or_else(|| format!("unknown format of `{}`", path.display()))
+}
+
+fn open(input: &Path) -> Result<Document, String> {
+    match file_kind(input)? {
+        FileKind::Document => Document::try_from(&input.to_path_buf()),
+        FileKind::Exchange(format) => Document::import(input, format),
+        FileKind::Svg | FileKind::Png => {
+            return Err(format!("`{}` can't be opened", input.display()))
+        }
+    }
+    .map_err(|err| format!("{}: {}", input.display(), err.message()))
+}
+
+/// Open document and refuse to process it, if it has problems.
+fn open_valid(input: &Path) -> Result<Document, String> {
+    let document = open(input)?;
+    let errors = document.graph.validate();
+
+    if errors.is_empty() {
+        return Ok(document);
+    }
+
+    for error in &errors {
+        eprintln!("{}: {error}", input.display());
+    }
+    Err(format!(
+        "`{}` is not valid, see `check` command",
+        input.display()
+    ))
+}
@@ -1,8 +1,6 @@
 use eframe::egui::{self, Button, Color32, Layout, Rgba, RichText, Stroke};
 
-use crate::consts::UI_SPACE;
-
-use super::group::CommentsGroup;
+use crate::{consts::UI_SPACE, graph_workspace::GraphWorkspace};
 
 pub struct CommentsEditor {
     draw_active: bool,
@@ -29,7 +27,7 @@ impl CommentsEditor {
         "Comments Editor"
     }
 
-    pub fn ui(&mut self, ui: &mut egui::Ui, comment_lines: &mut CommentsGroup) {
+    pub fn ui(&mut self, ui: &mut egui::Ui, graph_workspace: &mut GraphWorkspace) {
         ui.vertical_centered(|ui| {
             ui.label(RichText::new(self.name()).size(24.0));
         });
@@ -74,7 +72,7 @@ impl CommentsEditor {
                 )
                 .clicked()
             {
-                comment_lines.clear();
+                graph_workspace.clear_comment_lines();
             }
         });
     }
@@ -1,11 +1,12 @@
 use eframe::egui::{Color32, Pos2, Rect, Rgba, Stroke};
 use orientation::Orientation;
+use serde::{Deserialize, Serialize};
 
 pub mod editor;
 pub mod group;
 pub mod orientation;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CommentLine {
     pub points: Vec<Pos2>,
     pub stroke: Stroke,
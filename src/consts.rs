@@ -1,5 +1,19 @@
+use eframe::egui::Color32;
+
 pub const ARROW_LEN_COEF: f32 = 4.0;
 pub const ARROW_HALF_ANGLE: f32 = std::f32::consts::TAU / 15.0;
+pub const ARROW_CIRCLE_COEF: f32 = 1.6;
+pub const DASH_LENGTH_COEF: f32 = 4.0;
+pub const DASH_GAP_COEF: f32 = 3.0;
+pub const DOT_SPACING_COEF: f32 = 2.5;
+/// Distance of each of double lines from edge curve, relative to edge width.
+pub const DOUBLE_LINE_OFFSET_COEF: f32 = 0.75;
+/// Clearance between orthogonal edge and nodes it goes around.
+pub const ROUTE_MARGIN: f32 = 12.0;
+/// Extra length which orthogonal edge may take to avoid one bend.
+pub const ROUTE_BEND_PENALTY: f32 = 40.0;
+/// Distance between parallel orthogonal edges.
+pub const ROUTE_SPACING: f32 = 8.0;
 pub const DELTA_ANGLE: f32 = std::f32::consts::PI / 10.0;
 pub const CONTROL_OFFSET: f32 = 50.0;
 
@@ -9,6 +23,9 @@ pub const MAX_NODE_LABEL_SIZE: f32 = 100.0;
 pub const MIN_NODE_SIZE: f32 = 20.0;
 pub const MAX_NODE_SIZE: f32 = 100.0;
 
+pub const MIN_NODE_ASPECT_RATIO: f32 = 0.25;
+pub const MAX_NODE_ASPECT_RATIO: f32 = 4.0;
+
 pub const DEFAULT_NODE_X_POSITION: f32 = 100.0;
 pub const DEFAULT_NODE_Y_POSITION: f32 = 100.0;
 
@@ -18,10 +35,75 @@ pub const MAX_EDGE_WIDTH: f32 = 5.0;
 pub const MIN_LOOP_EDGE_ANGLE: f32 = 0.0;
 pub const MAX_LOOP_EDGE_ANGLE: f32 = 360.0;
 
+pub const DEFAULT_EDGE_WEIGHT: f32 = 1.0;
+
 pub const MIN_EDGE_LABEL_SIZE: f32 = 10.0;
 pub const MAX_EDGE_LABEL_SIZE: f32 = 36.0;
 
 pub const MIN_EDGE_LABEL_PADDING: f32 = -100.0;
 pub const MAX_EDGE_LABEL_PADDING: f32 = 100.0;
 
-pub const UI_SPACE: f32 = 5.0;
\ No newline at end of file
+pub const UI_SPACE: f32 = 5.0;
+
+pub const SELECTION_COLOR: Color32 = Color32::from_rgb(0, 120, 215);
+pub const SELECTION_OUTLINE_OFFSET: f32 = 4.0;
+/// Radius of handles of selected edge (ends and bend points) on screen.
+pub const EDGE_HANDLE_RADIUS: f32 = 5.0;
+pub const PASTE_OFFSET: f32 = 20.0;
+pub const EDGE_HIT_TOLERANCE: f32 = 5.0;
+pub const PATH_COLOR: Color32 = Color32::from_rgb(255, 140, 0);
+pub const TRACE_CURRENT_COLOR: Color32 = Color32::from_rgb(220, 20, 60);
+pub const TRACE_FRONTIER_COLOR: Color32 = Color32::from_rgb(255, 165, 0);
+pub const TRACE_VISITED_COLOR: Color32 = Color32::from_rgb(50, 160, 50);
+pub const TRACE_TREE_COLOR: Color32 = Color32::from_rgb(30, 144, 255);
+pub const MIN_PLAYER_SPEED: f32 = 0.25;
+pub const MAX_PLAYER_SPEED: f32 = 20.0;
+/// Colors of groups in algorithm results (components, layers).
+pub const ALGORITHM_PALETTE: [Color32; 8] = [
+    Color32::from_rgb(230, 25, 75),
+    Color32::from_rgb(60, 180, 75),
+    Color32::from_rgb(0, 130, 200),
+    Color32::from_rgb(245, 130, 48),
+    Color32::from_rgb(145, 30, 180),
+    Color32::from_rgb(70, 240, 240),
+    Color32::from_rgb(240, 50, 230),
+    Color32::from_rgb(210, 245, 60),
+];
+
+pub const DEFAULT_LAYOUT_EDGE_LENGTH: f32 = 80.0;
+pub const MIN_LAYOUT_EDGE_LENGTH: f32 = 20.0;
+pub const MAX_LAYOUT_EDGE_LENGTH: f32 = 400.0;
+pub const DEFAULT_LAYER_SPACING: f32 = 80.0;
+pub const DEFAULT_NODE_SPACING: f32 = 40.0;
+pub const MIN_LAYOUT_SPACING: f32 = 10.0;
+pub const MAX_LAYOUT_SPACING: f32 = 400.0;
+pub const DEFAULT_GRID_SPACING: f32 = 20.0;
+pub const MIN_GRID_SPACING: f32 = 5.0;
+pub const MAX_GRID_SPACING: f32 = 200.0;
+/// Grid lines closer than this on screen are not drawn.
+pub const MIN_GRID_SCREEN_SPACING: f32 = 8.0;
+pub const GRID_COLOR: Color32 = Color32::from_gray(225);
+pub const GUIDE_COLOR: Color32 = Color32::from_rgb(255, 0, 200);
+/// Screen distance at which dragged node sticks to guide.
+pub const GUIDE_SNAP_DISTANCE: f32 = 6.0;
+/// Pull of every node to center of graph in force-directed layout.
+pub const LAYOUT_GRAVITY: f32 = 0.2;
+/// Seconds of moving nodes to positions found by layout.
+pub const LAYOUT_ANIMATION_TIME: f32 = 0.5;
+
+pub const HISTORY_LIMIT: usize = 100;
+
+pub const POINTS_PER_INCH: f32 = 72.0;
+
+pub const MIN_RENDER_SCALE: f32 = 0.25;
+pub const MAX_RENDER_SCALE: f32 = 16.0;
+pub const DEFAULT_RENDER_SCALE: f32 = 2.0;
+/// Largest PNG image, about 1 GB of memory while it is rasterized.
+pub const MAX_RENDER_PIXELS: u64 = 50_000_000;
+/// Space around drawing in PNG and SVG images.
+pub const RENDER_MARGIN: f32 = 20.0;
+
+pub const MIN_ZOOM: f32 = 0.1;
+pub const MAX_ZOOM: f32 = 10.0;
+pub const SCROLL_ZOOM_SPEED: f32 = 0.002;
+pub const FIT_VIEW_MARGIN: f32 = 40.0;
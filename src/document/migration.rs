@@ -0,0 +1,160 @@
+use serde_json::{json, Value};
+
+use crate::error::GraphEditorError;
+
+/// Version of document written by this editor. Raise it with a migration
+/// whenever saved fields change, so older editors refuse newer files
+/// instead of dropping unknown fields on save.
+pub const CURRENT_VERSION: u32 = 3;
+
+/// Upgrade of document json from version `n` to `n + 1`,
+/// where `n` is index in `MIGRATIONS` plus one.
+type Migration = fn(Value) -> Value;
+
+const MIGRATIONS: [Migration; (CURRENT_VERSION - 1) as usize] =
+    [graph_to_document, add_optional_fields];
+
+/// Split file json into version and document json.
+///
+/// Files without version are written by old editors:
+/// - version 1 contains graph only,
+/// - version 2 contains graph and comment lines.
+pub fn unwrap_versioned(value: Value) -> Result<(u32, Value), GraphEditorError> {
+    match value.get("version") {
+        Some(version) => {
+            let version = version
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .filter(|&v| v >= 1)
+                .ok_or(GraphEditorError::FailedOpenFile)?;
+
+            let mut value = value;
+            let document = value
+                .get_mut("document")
+                .map(Value::take)
+                .ok_or(GraphEditorError::FailedOpenFile)?;
+
+            Ok((version, document))
+        }
+        None if value.get("graph").is_some() => Ok((2, value)),
+        None => Ok((1, value)),
+    }
+}
+
+/// Upgrade document json of given version to `CURRENT_VERSION`.
+pub fn migrate(version: u32, document: Value) -> Result<Value, GraphEditorError> {
+    if version > CURRENT_VERSION {
+        return Err(GraphEditorError::UnsupportedFileVersion(version));
+    }
+
+    let document = MIGRATIONS[(version - 1) as usize..]
+        .iter()
+        .fold(document, |document, migration| migration(document));
+
+    Ok(document)
+}
+
+/// v1 -> v2: graph is placed into document, comment lines are added.
+fn graph_to_document(graph: Value) -> Value {
+    json!({ "graph": graph })
+}
+
+/// v2 -> v3: nodes and edges got fields which older editors would drop on save:
+/// aspect ratio, pin, line style, arrowheads, routing, bend points, weight,
+/// attributes and foreign data; graph got foreign keys and styling rules.
+/// All of them have defaults, so json stays the same.
+fn add_optional_fields(document: Value) -> Value {
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        document::Document,
+        graph::{NodeId, NodeShape},
+    };
+
+    /// Graph saved by the first editor, before documents and versions.
+    const V1_FILE: &str = r#"{
+        "nodes": {
+            "1": {
+                "position": { "x": 10.0, "y": 20.0 },
+                "size": 30.0,
+                "shape": "Square",
+                "color": [0.0, 0.0, 1.0, 1.0],
+                "label": "a",
+                "label_size_matches_node_size": true,
+                "label_size": 12.0
+            },
+            "2": {
+                "position": { "x": 100.0, "y": 20.0 },
+                "size": 20.0,
+                "shape": "Circle",
+                "color": [1.0, 0.0, 0.0, 1.0],
+                "label": "b",
+                "label_size_matches_node_size": false,
+                "label_size": 14.0
+            }
+        },
+        "edges": {
+            "1": {
+                "start_id": 1,
+                "end_id": 2,
+                "oriented": false,
+                "color": [0.0, 0.0, 0.0, 1.0],
+                "label": "ab",
+                "label_size": 10.0,
+                "padding_x": 0.0,
+                "padding_y": 0.0,
+                "width": 3.0,
+                "loop_rotation_angle": 0.0
+            }
+        },
+        "node_id_counter": 2,
+        "edge_id_counter": 1
+    }"#;
+
+    #[test]
+    fn v1_file_is_migrated_to_current_version() {
+        let value: Value = serde_json::from_str(V1_FILE).unwrap();
+        let (version, document) = unwrap_versioned(value).unwrap();
+        assert_eq!(version, 1);
+
+        let document = migrate(version, document).unwrap();
+        let document: Document = serde_json::from_value(document).unwrap();
+        let graph = &document.graph;
+
+        assert_eq!(graph.nodes().len(), 2);
+        let a = graph.node(&NodeId(1)).unwrap();
+        assert_eq!(a.label, "a");
+        assert_eq!(a.shape, NodeShape::Square);
+        assert_eq!(a.size, 30.0);
+
+        let edge = graph.edges().values().next().unwrap();
+        assert_eq!((edge.start_id, edge.end_id), (NodeId(1), NodeId(2)));
+        assert!(!edge.oriented);
+        assert_eq!(edge.width, 3.0);
+        assert!(edge.bend_points.is_empty());
+        assert!(document.comment_lines.is_empty());
+    }
+
+    #[test]
+    fn saved_document_has_current_version() {
+        let value = json!({ "version": CURRENT_VERSION, "document": { "graph": {
+            "nodes": {}, "edges": {}, "node_id_counter": 0, "edge_id_counter": 0
+        } } });
+
+        assert!(Document::from_json(value).is_ok());
+    }
+
+    #[test]
+    fn newer_version_is_unsupported() {
+        let value = json!({ "version": CURRENT_VERSION + 1, "document": {} });
+
+        assert!(matches!(
+            Document::from_json(value),
+            Err(GraphEditorError::UnsupportedFileVersion(version)) if version == CURRENT_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+pub mod migration;
+
+use std::fs::File;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::comment_line::group::CommentsGroup;
+use crate::document::migration::CURRENT_VERSION;
+use crate::error::GraphEditorError;
+use crate::format::ExchangeFormat;
+use crate::graph::Graph;
+
+/// Everything saved to a file: graph and comment lines above it.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Document {
+    pub graph: Graph,
+    #[serde(default)]
+    pub comment_lines: CommentsGroup,
+}
+
+/// File envelope: document together with version of its format.
+#[derive(Serialize)]
+struct VersionedDocument<'a> {
+    version: u32,
+    document: &'a Document,
+}
+
+impl Document {
+    pub fn new(graph: Graph, comment_lines: CommentsGroup) -> Self {
+        Self {
+            graph,
+            comment_lines,
+        }
+    }
+
+    /// Parse document from file json, upgrading it from older versions.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, GraphEditorError> {
+        let (version, document) = migration::unwrap_versioned(value)?;
+        let document = migration::migrate(version, document)?;
+
+        serde_json::from_value(document)
+            .map_err(|err| GraphEditorError::FailedParseFile(err.to_string()))
+    }
+
+    /// Read graph from file of given exchange format, without comment lines.
+    pub fn import(file_path: &Path, format: ExchangeFormat) -> Result<Self, GraphEditorError> {
+        let text =
+            std::fs::read_to_string(file_path).map_err(|_| GraphEditorError::FailedOpenFile)?;
+
+        Ok(Self::new(format.import(&text)?, CommentsGroup::new()))
+    }
+
+    pub fn save_to_file(&self, file_path: &Path) -> Result<(), GraphEditorError> {
+        let versioned = VersionedDocument {
+            version: CURRENT_VERSION,
+            document: self,
+        };
+        let document_json = serde_json::to_string_pretty(&versioned)
+            .map_err(|_| GraphEditorError::FailedSaveFile)?;
+
+        let mut file = File::create(file_path).map_err(|_| GraphEditorError::FailedSaveFile)?;
+        file.write_all(document_json.as_bytes())
+            .map_err(|_| GraphEditorError::FailedSaveFile)?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<&PathBuf> for Document {
+    type Error = GraphEditorError;
+
+    fn try_from(value: &PathBuf) -> Result<Self, Self::Error> {
+        let file: File = File::open(value).map_err(|_| GraphEditorError::FailedOpenFile)?;
+        let reader = BufReader::new(file);
+
+        let value = serde_json::from_reader(reader)
+            .map_err(|err| GraphEditorError::FailedParseFile(err.to_string()))?;
+
+        Document::from_json(value)
+    }
+}
@@ -7,7 +7,10 @@ use crate::{
         MAX_EDGE_LABEL_PADDING, MAX_EDGE_LABEL_SIZE, MAX_EDGE_WIDTH, MAX_LOOP_EDGE_ANGLE,
         MIN_EDGE_LABEL_PADDING, MIN_EDGE_LABEL_SIZE, MIN_EDGE_WIDTH, MIN_LOOP_EDGE_ANGLE, UI_SPACE,
     },
+    graph::{ArrowHead, EdgeRouting, LineStyle},
     graph_workspace::GraphWorkspace,
+    history::EditKind,
+    utils::widgets::attributes_editor,
 };
 
 pub struct EdgeEditor;
@@ -38,96 +41,222 @@ impl EdgeEditor {
             });
             ui.separator();
 
+            let selected_id = graph_workspace.selected_edge_id().unwrap();
             let selected_edge = graph_workspace.selected_edge_mut().unwrap();
+            let mut changed = false;
+
             ui.horizontal(|ui| {
-                color_edit_button_rgba(
+                changed |= color_edit_button_rgba(
                     ui,
                     &mut selected_edge.color,
                     egui::color_picker::Alpha::Opaque,
-                );
+                )
+                .changed();
+                ui.add_space(UI_SPACE);
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut selected_edge.width)
+                            .range(MIN_EDGE_WIDTH..=MAX_EDGE_WIDTH)
+                            .speed(0.2)
+                            .prefix("Width: "),
+                    )
+                    .changed();
+
                 ui.add_space(UI_SPACE);
-                ui.add(
-                    DragValue::new(&mut selected_edge.width)
-                        .range(MIN_EDGE_WIDTH..=MAX_EDGE_WIDTH)
-                        .speed(0.2)
-                        .prefix("Width: "),
+                let oriented = if selected_edge.oriented {
+                    "Oriented"
+                } else {
+                    "Unoriented"
+                };
+                changed |= ui
+                    .toggle_value(&mut selected_edge.oriented, oriented)
+                    .changed();
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Line:");
+                egui::ComboBox::from_id_salt("edge_line_style")
+                    .selected_text(selected_edge.line_style.name())
+                    .show_ui(ui, |ui| {
+                        for style in LineStyle::ALL {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut selected_edge.line_style,
+                                    style,
+                                    style.name(),
+                                )
+                                .changed();
+                        }
+                    });
+            });
+
+            // unoriented edges have no arrowheads
+            ui.add_enabled_ui(selected_edge.oriented, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Arrows:");
+                    for (salt, head) in [
+                        ("edge_source_arrow", &mut selected_edge.source_arrow),
+                        ("edge_target_arrow", &mut selected_edge.target_arrow),
+                    ] {
+                        egui::ComboBox::from_id_salt(salt)
+                            .selected_text(head.name())
+                            .width(70.0)
+                            .show_ui(ui, |ui| {
+                                for variant in ArrowHead::ALL {
+                                    changed |= ui
+                                        .selectable_value(head, variant, variant.name())
+                                        .changed();
+                                }
+                            });
+                    }
+                })
+                .response
+                .on_hover_text("Arrowheads at start and end node");
+            });
+
+            // loops are always curved
+            ui.add_enabled_ui(!selected_edge.is_loop(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Route:");
+                    egui::ComboBox::from_id_salt("edge_routing")
+                        .selected_text(selected_edge.routing.name())
+                        .show_ui(ui, |ui| {
+                            for routing in EdgeRouting::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut selected_edge.routing,
+                                        routing,
+                                        routing.name(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                });
+            });
+
+            if selected_edge.is_polyline() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Bend points: {}", selected_edge.bend_points.len()));
+                    if ui
+                        .add_enabled(
+                            !selected_edge.bend_points.is_empty(),
+                            Button::new("Clear bend points"),
+                        )
+                        .clicked()
+                    {
+                        selected_edge.bend_points.clear();
+                        changed = true;
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Double-click edge to add bend point, double-click point to remove it",
                 );
+            }
 
-                // loop direction is unnecessary
-                if !selected_edge.is_loop() {
-                    ui.add_space(UI_SPACE);
-                    let oriented = if selected_edge.oriented {
-                        "Oriented"
-                    } else {
-                        "Unoriented"
-                    };
-                    ui.toggle_value(&mut selected_edge.oriented, oriented);
+            ui.separator();
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut selected_edge.weight)
+                            .speed(0.1)
+                            .prefix("Weight: "),
+                    )
+                    .changed();
+
+                let label_weight = selected_edge.label_weight();
+                if ui
+                    .add_enabled(label_weight.is_some(), Button::new("From label"))
+                    .on_hover_text("Use number written in label as weight")
+                    .clicked()
+                {
+                    selected_edge.weight = label_weight.unwrap();
+                    changed = true;
                 }
             });
 
             if selected_edge.is_loop() {
                 ui.separator();
-                ui.add(
-                    DragValue::new(&mut selected_edge.loop_rotation_angle)
-                        .range(MIN_LOOP_EDGE_ANGLE..=MAX_LOOP_EDGE_ANGLE)
-                        .speed(1)
-                        .prefix("Loop rot. angle: ")
-                        .suffix("°"),
-                );
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut selected_edge.loop_rotation_angle)
+                            .range(MIN_LOOP_EDGE_ANGLE..=MAX_LOOP_EDGE_ANGLE)
+                            .speed(1)
+                            .prefix("Loop rot. angle: ")
+                            .suffix("°"),
+                    )
+                    .changed();
             }
 
             ui.separator();
             ui.vertical(|ui| {
                 ui.horizontal(|ui| {
                     ui.label("Label: ");
-                    ui.text_edit_singleline(&mut selected_edge.label);
+                    changed |= ui.text_edit_singleline(&mut selected_edge.label).changed();
                 });
 
                 ui.add_space(UI_SPACE);
 
                 ui.vertical(|ui| {
                     ui.horizontal(|ui| {
-                        ui.add(
-                            DragValue::new(&mut selected_edge.label_size)
-                                .range(MIN_EDGE_LABEL_SIZE..=MAX_EDGE_LABEL_SIZE)
-                                .speed(0.2)
-                                .prefix("Size: "),
-                        );
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut selected_edge.label_size)
+                                    .range(MIN_EDGE_LABEL_SIZE..=MAX_EDGE_LABEL_SIZE)
+                                    .speed(0.2)
+                                    .prefix("Size: "),
+                            )
+                            .changed();
                         if ui.button("⟲").clicked() {
                             selected_edge.label_size = 10.0;
+                            changed = true;
                         }
                     });
 
                     ui.add_space(UI_SPACE);
 
                     ui.horizontal(|ui| {
-                        ui.add(
-                            DragValue::new(&mut selected_edge.padding_x)
-                                .range(MIN_EDGE_LABEL_PADDING..=MAX_EDGE_LABEL_PADDING)
-                                .speed(1.0)
-                                .prefix("X: "),
-                        );
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut selected_edge.padding_x)
+                                    .range(MIN_EDGE_LABEL_PADDING..=MAX_EDGE_LABEL_PADDING)
+                                    .speed(1.0)
+                                    .prefix("X: "),
+                            )
+                            .changed();
                         if ui.button("⟲").clicked() {
                             selected_edge.padding_x = 0.0;
+                            changed = true;
                         }
                     });
 
                     ui.add_space(UI_SPACE);
 
                     ui.horizontal(|ui| {
-                        ui.add(
-                            DragValue::new(&mut selected_edge.padding_y)
-                                .range(MIN_EDGE_LABEL_PADDING..=MAX_EDGE_LABEL_PADDING)
-                                .speed(1.0)
-                                .prefix("Y: "),
-                        );
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut selected_edge.padding_y)
+                                    .range(MIN_EDGE_LABEL_PADDING..=MAX_EDGE_LABEL_PADDING)
+                                    .speed(1.0)
+                                    .prefix("Y: "),
+                            )
+                            .changed();
                         if ui.button("⟲").clicked() {
                             selected_edge.padding_y = 0.0;
+                            changed = true;
                         }
                     });
                 });
             });
 
+            ui.separator();
+            ui.label("Attributes:");
+            changed |= attributes_editor(ui, "edge_attributes", &mut selected_edge.attributes);
+
+            if changed {
+                graph_workspace.record(EditKind::EdgeProperty(selected_id));
+            }
+
             ui.separator();
 
             ui.with_layout(Layout::right_to_left(egui::Align::TOP), |ui| {
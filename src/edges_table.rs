@@ -1,9 +1,16 @@
 use eframe::egui::{self, frame, Margin};
 use egui_extras::{Column, TableBuilder};
 
-use crate::{graph::edge::EdgeId, graph_workspace::GraphWorkspace};
+use crate::{
+    graph::edge::EdgeId, graph_workspace::GraphWorkspace, history::EditKind,
+    utils::widgets::attribute_value_edit,
+};
 
-pub struct EdgesTable;
+#[derive(Default)]
+pub struct EdgesTable {
+    /// Primary selected edge in previous frame, table scrolls to it when it changes.
+    last_selected: Option<EdgeId>,
+}
 
 impl EdgesTable {
     pub fn name(&self) -> &'static str {
@@ -11,17 +18,38 @@ impl EdgesTable {
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui, graph_workspace: &mut GraphWorkspace) {
-        let width = (ui.available_width() - 30.0).max(0.0) / 3.0;
+        // every attribute used by some edge has own column
+        let attribute_columns = graph_workspace.graph().edge_attribute_columns();
+        let width = (ui.available_width() - 30.0).max(0.0) / (3 + attribute_columns.len()) as f32;
+
+        // used to get edges in same order (after removing, adding etc.)
+        let ids: Vec<_> = graph_workspace.edges_ids();
 
-        let table = TableBuilder::new(ui)
+        let selected = graph_workspace.selected_edge_id();
+        let scroll_to_row = if selected != self.last_selected {
+            self.last_selected = selected;
+            selected.and_then(|selected| ids.iter().position(|id| *id == selected))
+        } else {
+            None
+        };
+
+        let mut table = TableBuilder::new(ui)
             .min_scrolled_height(100.0)
             .cell_layout(egui::Layout::top_down(egui::Align::Center))
             .column(Column::auto().at_least(width).at_most(width).clip(true))
             .column(Column::auto().at_least(width).at_most(width).clip(true))
             .column(Column::auto())
             .column(Column::auto().at_least(width).at_most(width).clip(true))
+            .columns(
+                Column::auto().at_least(width).at_most(width).clip(true),
+                attribute_columns.len(),
+            )
             .sense(egui::Sense::click());
 
+        if let Some(row) = scroll_to_row {
+            table = table.scroll_to_row(row, Some(egui::Align::Center));
+        }
+
         table
             .header(20.0, |mut header| {
                 header.col(|ui| {
@@ -36,11 +64,13 @@ impl EdgesTable {
                 header.col(|ui| {
                     ui.strong("Label");
                 });
+                for (name, _) in &attribute_columns {
+                    header.col(|ui| {
+                        ui.strong(name);
+                    });
+                }
             })
             .body(|body| {
-                // used to get edges in same order (after removing, adding etc.)
-                let ids: Vec<_> = graph_workspace.edges_ids();
-
                 // rows is more efficient, than row
                 // https://docs.rs/egui_extras/0.30.0/egui_extras/struct.TableBody.html#method.rows
                 // that's why using ids - to keep edges order
@@ -48,9 +78,7 @@ impl EdgesTable {
                     let row_index = row.index();
                     let edge_id = &ids[row_index];
 
-                    if let Some(selected_id) = graph_workspace.selected_edge_id() {
-                        row.set_selected(selected_id == edge_id);
-                    }
+                    row.set_selected(graph_workspace.selected_edges().contains(edge_id));
 
                     let edge_nodes = graph_workspace.edge_nodes(edge_id).unwrap();
 
@@ -62,24 +90,38 @@ impl EdgesTable {
                     });
 
                     let edge = graph_workspace.edge_mut(edge_id).unwrap();
+                    let mut changed = false;
                     row.col(|ui| {
                         if !edge.is_loop() {
-                            ui.checkbox(&mut edge.oriented, "");
+                            changed |= ui.checkbox(&mut edge.oriented, "").changed();
                         }
                     });
                     row.col(|ui| {
                         frame::Frame::default()
                             .inner_margin(Margin::symmetric(2, 0))
                             .show(ui, |ui| {
-                                ui.text_edit_singleline(&mut edge.label);
+                                changed |= ui.text_edit_singleline(&mut edge.label).changed();
                             });
                     });
+                    for (name, _) in &attribute_columns {
+                        row.col(|ui| {
+                            if let Some(value) = edge.attributes.get_mut(name) {
+                                changed |= attribute_value_edit(ui, value).changed();
+                            }
+                        });
+                    }
+
+                    if changed {
+                        graph_workspace.record(EditKind::EdgeProperty(*edge_id));
+                    }
 
                     self.toggle_row_selection(edge_id, &row.response(), graph_workspace);
                 });
             });
     }
 
+    /// Click selects only clicked edge (or unselects it),
+    /// Shift/Ctrl-click adds edge to selection or removes it.
     fn toggle_row_selection(
         &mut self,
         edge_id: &EdgeId,
@@ -87,12 +129,16 @@ impl EdgesTable {
         graph: &mut GraphWorkspace,
     ) {
         if row_response.clicked() {
-            if let Some(selected_id) = graph.selected_edge_id() {
-                if selected_id == edge_id {
-                    graph.set_selected_edge_id(None);
-                } else {
-                    graph.set_selected_edge_id(Some(*edge_id));
-                }
+            let toggle = row_response
+                .ctx
+                .input(|i| i.modifiers.shift || i.modifiers.command);
+
+            if toggle {
+                graph.selected_edges_mut().toggle(*edge_id);
+            } else if graph.selected_edge_id() == Some(*edge_id)
+                && graph.selected_edges().len() == 1
+            {
+                graph.set_selected_edge_id(None);
             } else {
                 graph.set_selected_edge_id(Some(*edge_id));
             }
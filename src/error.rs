@@ -1,15 +1,61 @@
-pub enum GraphEditorError{ 
+use crate::document::migration::CURRENT_VERSION;
+
+#[derive(Debug)]
+pub enum GraphEditorError {
     FailedOpenFile,
     FailedSaveFile,
-    FailedTakeScreenshot
+    FailedTakeScreenshot,
+    UnsupportedFileVersion(u32),
+    FailedParseFile(String),
+    NoPath,
+    /// Labels of nodes on cycle.
+    NegativeCycle(Vec<String>),
+    UnorientedEdges,
+    /// Labels of nodes on cycle.
+    NotAcyclic(Vec<String>),
+    NoRootNode,
+    /// Labels of nodes on odd cycle.
+    NotBipartite(Vec<String>),
+    /// Width and height of image in pixels.
+    ImageTooLarge(f64, f64),
+    /// Problems of pasted nodes and edges.
+    InvalidPaste(Vec<String>),
 }
 
 impl GraphEditorError {
-    pub fn message(&self) -> &str {
+    pub fn message(&self) -> String {
         match self {
-            GraphEditorError::FailedOpenFile => "Failed to open the file",
-            GraphEditorError::FailedSaveFile => "Failed to save the file",
-            GraphEditorError::FailedTakeScreenshot => "Failed to take the screenshot"
+            GraphEditorError::FailedOpenFile => "Failed to open the file".to_owned(),
+            GraphEditorError::FailedSaveFile => "Failed to save the file".to_owned(),
+            GraphEditorError::FailedTakeScreenshot => "Failed to take the screenshot".to_owned(),
+            GraphEditorError::UnsupportedFileVersion(version) => format!(
+                "File version {version} is newer than supported ({CURRENT_VERSION}), update the editor"
+            ),
+            GraphEditorError::FailedParseFile(reason) => {
+                format!("Failed to parse the file: {reason}")
+            }
+            GraphEditorError::NoPath => "There is no path between the nodes".to_owned(),
+            GraphEditorError::NegativeCycle(labels) => format!(
+                "Path cost is unbounded, negative cycle: {}",
+                labels.join(" ➡ ")
+            ),
+            GraphEditorError::UnorientedEdges => {
+                "All edges must be oriented (except loops)".to_owned()
+            }
+            GraphEditorError::NotAcyclic(labels) => {
+                format!("Graph has a cycle: {}", labels.join(" ➡ "))
+            }
+            GraphEditorError::NoRootNode => "Select a node to be the root of tree".to_owned(),
+            GraphEditorError::NotBipartite(labels) => format!(
+                "Graph is not bipartite, odd cycle: {}",
+                labels.join(" — ")
+            ),
+            GraphEditorError::ImageTooLarge(width, height) => format!(
+                "Image of {width}×{height} pixels is too large, lower the resolution"
+            ),
+            GraphEditorError::InvalidPaste(errors) => {
+                format!("Pasted graph is invalid: {}", errors.join("; "))
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,1030 @@
+//! Graphviz DOT import and export.
+//!
+//! Positions are written as `pos` attributes in points with y axis pointing up,
+//! they are read back unchanged. Node size is written as `width`/`height` in inches,
+//! square with other width than height is written as `box`.
+//! Edge weight is written as `cost`, because Graphviz `weight` is a layout
+//! attribute, it is read as custom attribute.
+//! Custom attributes are written as DOT attributes, DOT attributes unknown to editor
+//! are read as custom attributes.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use eframe::egui::Pos2;
+
+use crate::{
+    consts::{
+        DEFAULT_EDGE_WEIGHT, MAX_EDGE_WIDTH, MAX_NODE_ASPECT_RATIO, MAX_NODE_SIZE, MIN_EDGE_WIDTH,
+        MIN_NODE_ASPECT_RATIO, MIN_NODE_SIZE, POINTS_PER_INCH,
+    },
+    error::GraphEditorError,
+    format::{color_from_str, color_to_hex},
+    graph::{
+        attribute::{self, AttributeValue},
+        ArrowHead, Edge, Graph, LineStyle, Node, NodeShape,
+    },
+};
+
+type Attributes = BTreeMap<String, String>;
+
+/// DOT attributes of nodes read and written by editor.
+const NODE_KEYS: [&str; 9] = [
+    "label",
+    "shape",
+    "style",
+    "fillcolor",
+    "color",
+    "width",
+    "height",
+    "pos",
+    "fixedsize",
+];
+/// DOT attributes of edges read and written by editor.
+const EDGE_KEYS: [&str; 8] = [
+    "color",
+    "penwidth",
+    "style",
+    "label",
+    "cost",
+    "dir",
+    "arrowtail",
+    "arrowhead",
+];
+
+// export
+pub fn export(graph: &Graph) -> String {
+    // unoriented graph only if there is no oriented edges at all (loops included),
+    // otherwise unoriented edges are marked with `dir=none`
+    let directed = graph.edges().values().any(|e| e.oriented) || graph.edges().is_empty();
+    let (graph_keyword, edge_op) = if directed {
+        ("digraph", "->")
+    } else {
+        ("graph", "--")
+    };
+
+    let mut nodes: Vec<_> = graph.nodes().iter().collect();
+    nodes.sort_by_key(|(id, _)| id.0);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{graph_keyword} G {{");
+
+    for (id, node) in nodes {
+        let half_size = node.half_size();
+        let mut attributes = vec![
+            ("label", quote(&node.label)),
+            ("shape", shape_name(node).to_owned()),
+            ("style", quote(shape_style(node.shape))),
+            ("fillcolor", quote(&color_to_hex(node.color))),
+            ("width", format_inches(2.0 * half_size.x)),
+            ("height", format_inches(2.0 * half_size.y)),
+            (
+                "pos",
+                quote(&format!(
+                    "{},{}!",
+                    format_number(node.position.x),
+                    format_number(-node.position.y)
+                )),
+            ),
+        ];
+        if node.shape == NodeShape::Circle {
+            attributes.push(("fixedsize", "true".to_owned()));
+        }
+        let custom = custom_attributes(&node.attributes, &NODE_KEYS);
+        attributes.extend(
+            custom
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.clone())),
+        );
+
+        let _ = writeln!(out, "    n{} [{}];", id.0, format_attributes(&attributes));
+    }
+
+    for edge in graph.edges().values() {
+        let color = color_to_hex(edge.color);
+        let mut attributes = vec![
+            // double line is drawn by DOT as parallel lines of color list
+            match edge.line_style {
+                LineStyle::Double => ("color", quote(&format!("{color}:invis:{color}"))),
+                _ => ("color", quote(&color)),
+            },
+            ("penwidth", format_number(edge.width)),
+        ];
+        match edge.line_style {
+            LineStyle::Dashed => attributes.push(("style", "dashed".to_owned())),
+            LineStyle::Dotted => attributes.push(("style", "dotted".to_owned())),
+            LineStyle::Solid | LineStyle::Double => {}
+        }
+        if !edge.label.is_empty() {
+            attributes.push(("label", quote(&edge.label)));
+        }
+        if edge.weight != DEFAULT_EDGE_WEIGHT {
+            attributes.push(("cost", format_number(edge.weight)));
+        }
+        let (source_arrow, target_arrow) = edge.arrow_heads();
+        if directed && !edge.oriented {
+            attributes.push(("dir", "none".to_owned()));
+        } else if source_arrow != ArrowHead::None {
+            attributes.push(("dir", "both".to_owned()));
+            attributes.push(("arrowtail", arrow_name(source_arrow).to_owned()));
+            attributes.push(("arrowhead", arrow_name(target_arrow).to_owned()));
+        } else if target_arrow != ArrowHead::Filled {
+            attributes.push(("arrowhead", arrow_name(target_arrow).to_owned()));
+        }
+        let custom = custom_attributes(&edge.attributes, &EDGE_KEYS);
+        attributes.extend(
+            custom
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.clone())),
+        );
+
+        let _ = writeln!(
+            out,
+            "    n{} {edge_op} n{} [{}];",
+            edge.start_id.0,
+            edge.end_id.0,
+            format_attributes(&attributes)
+        );
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn shape_name(node: &Node) -> &'static str {
+    match node.shape {
+        NodeShape::Circle => "circle",
+        // Graphviz square always has equal sides
+        NodeShape::Square if node.aspect_ratio != 1.0 => "box",
+        NodeShape::Square => "square",
+        NodeShape::Ellipse => "ellipse",
+        NodeShape::Diamond => "diamond",
+        NodeShape::Triangle => "triangle",
+        NodeShape::Hexagon => "hexagon",
+        NodeShape::RoundedRect => "box",
+    }
+}
+
+fn arrow_name(head: ArrowHead) -> &'static str {
+    match head {
+        ArrowHead::None => "none",
+        ArrowHead::Filled => "normal",
+        ArrowHead::Open => "vee",
+        ArrowHead::Diamond => "diamond",
+        ArrowHead::Circle => "odot",
+    }
+}
+
+/// DOT has no rounded rectangle shape, it's a box with rounded style.
+fn shape_style(shape: NodeShape) -> &'static str {
+    match shape {
+        NodeShape::RoundedRect => "rounded,filled",
+        _ => "filled",
+    }
+}
+
+/// Custom attributes as DOT attributes, attributes named as editor's own are skipped.
+fn custom_attributes(
+    attributes: &attribute::Attributes,
+    own_keys: &[&str],
+) -> Vec<(String, String)> {
+    attributes
+        .iter()
+        .filter(|attribute| !own_keys.contains(&attribute.name.as_str()))
+        .map(|attribute| {
+            let value = match &attribute.value {
+                AttributeValue::String(text) => quote(text),
+                value => value.to_string(),
+            };
+            (quote_id(&attribute.name), value)
+        })
+        .collect()
+}
+
+/// Name is quoted unless it is plain DOT identifier.
+fn quote_id(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if plain {
+        name.to_owned()
+    } else {
+        quote(name)
+    }
+}
+
+/// DOT attributes unknown to editor as custom attributes, sorted by name.
+fn read_custom_attributes(attributes: &Attributes, own_keys: &[&str]) -> attribute::Attributes {
+    let mut custom = attribute::Attributes::default();
+
+    for (key, value) in attributes {
+        if !own_keys.contains(&key.as_str()) {
+            custom.set(key, AttributeValue::infer(value));
+        }
+    }
+
+    custom
+}
+
+fn format_attributes(attributes: &[(&str, String)]) -> String {
+    attributes
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Length in points as inches, precise enough to keep size within 0.01 point.
+fn format_inches(points: f32) -> String {
+    let inches = (points / POINTS_PER_INCH * 10_000.0).round() / 10_000.0;
+    format!("{inches}")
+}
+
+fn format_number(value: f32) -> String {
+    let value = (value * 100.0).round() / 100.0;
+    format!("{value}")
+}
+
+fn quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+
+    format!("\"{escaped}\"")
+}
+
+// import
+pub fn import(text: &str) -> Result<Graph, GraphEditorError> {
+    let tokens = tokenize(text).map_err(GraphEditorError::FailedParseFile)?;
+    let dot_graph = Parser::new(tokens)
+        .parse()
+        .map_err(GraphEditorError::FailedParseFile)?;
+
+    Ok(dot_graph.into_graph())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Id { value: String, quoted: bool },
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Semicolon,
+    Comma,
+    Equal,
+    Colon,
+    Plus,
+    EdgeOp,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Id { value, .. } => format!("'{value}'"),
+            Token::LBrace => "'{'".to_owned(),
+            Token::RBrace => "'}'".to_owned(),
+            Token::LBracket => "'['".to_owned(),
+            Token::RBracket => "']'".to_owned(),
+            Token::Semicolon => "';'".to_owned(),
+            Token::Comma => "','".to_owned(),
+            Token::Equal => "'='".to_owned(),
+            Token::Colon => "':'".to_owned(),
+            Token::Plus => "'+'".to_owned(),
+            Token::EdgeOp => "edge operator".to_owned(),
+        }
+    }
+}
+
+/// Split DOT text into tokens paired with their line numbers.
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut i = 0;
+    let mut line_start = true;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\n' {
+            line += 1;
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        // preprocessor output lines are ignored
+        if c == '#' && line_start {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        line_start = false;
+
+        match c {
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' | '+' => {
+                let token = match c {
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    ';' => Token::Semicolon,
+                    ',' => Token::Comma,
+                    '=' => Token::Equal,
+                    ':' => Token::Colon,
+                    _ => Token::Plus,
+                };
+                tokens.push((token, line));
+                i += 1;
+            }
+            '-' if matches!(chars.get(i + 1), Some('>') | Some('-')) => {
+                tokens.push((Token::EdgeOp, line));
+                i += 2;
+            }
+            '"' => {
+                let start_line = line;
+                let mut value = String::new();
+                i += 1;
+
+                loop {
+                    match chars.get(i) {
+                        None => return Err(format!("line {start_line}: unterminated string")),
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some('"') => value.push('"'),
+                                Some('\\') => value.push('\\'),
+                                Some('n') | Some('l') | Some('r') => value.push('\n'),
+                                // line continuation
+                                Some('\n') => line += 1,
+                                Some(&other) => {
+                                    value.push('\\');
+                                    value.push(other);
+                                }
+                                None => {}
+                            }
+                            i += 2;
+                            continue;
+                        }
+                        Some(&other) => {
+                            if other == '\n' {
+                                line += 1;
+                            }
+                            value.push(other);
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+
+                tokens.push((
+                    Token::Id {
+                        value,
+                        quoted: true,
+                    },
+                    start_line,
+                ));
+            }
+            '<' => {
+                // HTML string, kept as is
+                let start_line = line;
+                let mut depth = 0;
+                let mut value = String::new();
+
+                loop {
+                    match chars.get(i) {
+                        None => return Err(format!("line {start_line}: unterminated HTML string")),
+                        Some('<') => depth += 1,
+                        Some('>') => depth -= 1,
+                        Some('\n') => line += 1,
+                        _ => {}
+                    }
+                    value.push(chars[i]);
+                    i += 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                }
+
+                let value = value[1..value.len() - 1].to_owned();
+                tokens.push((
+                    Token::Id {
+                        value,
+                        quoted: true,
+                    },
+                    start_line,
+                ));
+            }
+            c if c == '-' || c == '.' || c.is_ascii_digit() => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let value: String = chars[start..i].iter().collect();
+                tokens.push((
+                    Token::Id {
+                        value,
+                        quoted: false,
+                    },
+                    line,
+                ));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let value: String = chars[start..i].iter().collect();
+                tokens.push((
+                    Token::Id {
+                        value,
+                        quoted: false,
+                    },
+                    line,
+                ));
+            }
+            other => return Err(format!("line {line}: unexpected character '{other}'")),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Graph as it is described in DOT, before conversion to `Graph`.
+#[derive(Default)]
+struct DotGraph {
+    directed: bool,
+    node_indices: HashMap<String, usize>,
+    nodes: Vec<(String, Attributes)>,
+    edges: Vec<(usize, usize, Attributes)>,
+}
+
+/// Default attributes of current graph or subgraph.
+#[derive(Clone, Default)]
+struct Scope {
+    node: Attributes,
+    edge: Attributes,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    graph: DotGraph,
+}
+
+impl Parser {
+    fn new(tokens: Vec<(Token, usize)>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            graph: DotGraph::default(),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.peek(),
+            Some(Token::Id { value, quoted: false }) if value.eq_ignore_ascii_case(keyword)
+        )
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn error(&self, expected: &str) -> String {
+        match self.tokens.get(self.pos) {
+            Some((token, line)) => {
+                format!(
+                    "line {line}: expected {expected}, found {}",
+                    token.describe()
+                )
+            }
+            None => format!("expected {expected}, found end of file"),
+        }
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> Result<(), String> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    /// Parse ID, including concatenated quoted strings (`"a" + "b"`).
+    fn parse_id(&mut self) -> Result<String, String> {
+        let mut id = match self.peek() {
+            Some(Token::Id { value, .. }) => value.clone(),
+            _ => return Err(self.error("identifier")),
+        };
+        self.pos += 1;
+
+        while self.peek() == Some(&Token::Plus) {
+            self.pos += 1;
+            match self.next() {
+                Some(Token::Id { value, .. }) => id.push_str(&value),
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("string after '+'"));
+                }
+            }
+        }
+
+        Ok(id)
+    }
+
+    fn parse(mut self) -> Result<DotGraph, String> {
+        if self.peek_keyword("strict") {
+            self.pos += 1;
+        }
+
+        self.graph.directed = if self.peek_keyword("digraph") {
+            true
+        } else if self.peek_keyword("graph") {
+            false
+        } else {
+            return Err(self.error("'graph' or 'digraph'"));
+        };
+        self.pos += 1;
+
+        if matches!(self.peek(), Some(Token::Id { .. })) {
+            self.parse_id()?;
+        }
+
+        self.expect(Token::LBrace, "'{'")?;
+        self.parse_stmt_list(&mut Scope::default())?;
+        self.expect(Token::RBrace, "'}'")?;
+
+        if self.peek().is_some() {
+            return Err(self.error("end of file"));
+        }
+
+        Ok(self.graph)
+    }
+
+    /// Parse statements until closing brace, return all nodes mentioned in them.
+    fn parse_stmt_list(&mut self, scope: &mut Scope) -> Result<Vec<usize>, String> {
+        let mut mentioned = vec![];
+
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            self.parse_stmt(scope, &mut mentioned)?;
+
+            if self.peek() == Some(&Token::Semicolon) {
+                self.pos += 1;
+            }
+        }
+
+        Ok(mentioned)
+    }
+
+    fn parse_stmt(&mut self, scope: &mut Scope, mentioned: &mut Vec<usize>) -> Result<(), String> {
+        let next_is_attr_list = matches!(self.tokens.get(self.pos + 1), Some((Token::LBracket, _)));
+
+        if next_is_attr_list && self.peek_keyword("graph") {
+            self.pos += 1;
+            self.parse_attr_lists()?;
+            return Ok(());
+        }
+        if next_is_attr_list && self.peek_keyword("node") {
+            self.pos += 1;
+            let attributes = self.parse_attr_lists()?;
+            scope.node.extend(attributes);
+            return Ok(());
+        }
+        if next_is_attr_list && self.peek_keyword("edge") {
+            self.pos += 1;
+            let attributes = self.parse_attr_lists()?;
+            scope.edge.extend(attributes);
+            return Ok(());
+        }
+
+        // graph attribute: ID '=' ID
+        if matches!(self.tokens.get(self.pos + 1), Some((Token::Equal, _)))
+            && matches!(self.peek(), Some(Token::Id { .. }))
+        {
+            self.parse_id()?;
+            self.pos += 1;
+            self.parse_id()?;
+            return Ok(());
+        }
+
+        let first = self.parse_edge_operand(scope)?;
+        mentioned.extend(&first);
+
+        if self.peek() != Some(&Token::EdgeOp) {
+            // node statement (or standalone subgraph)
+            if self.peek() == Some(&Token::LBracket) {
+                let attributes = self.parse_attr_lists()?;
+                for &index in &first {
+                    self.graph.nodes[index].1.extend(attributes.clone());
+                }
+            }
+            return Ok(());
+        }
+
+        let mut operands = vec![first];
+        while self.peek() == Some(&Token::EdgeOp) {
+            self.pos += 1;
+            let operand = self.parse_edge_operand(scope)?;
+            mentioned.extend(&operand);
+            operands.push(operand);
+        }
+
+        let mut attributes = scope.edge.clone();
+        if self.peek() == Some(&Token::LBracket) {
+            attributes.extend(self.parse_attr_lists()?);
+        }
+
+        for pair in operands.windows(2) {
+            for &start in &pair[0] {
+                for &end in &pair[1] {
+                    self.graph.edges.push((start, end, attributes.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse node id or subgraph, return indices of nodes it stands for.
+    fn parse_edge_operand(&mut self, scope: &Scope) -> Result<Vec<usize>, String> {
+        if self.peek_keyword("subgraph") || self.peek() == Some(&Token::LBrace) {
+            if self.peek_keyword("subgraph") {
+                self.pos += 1;
+                if matches!(self.peek(), Some(Token::Id { .. })) {
+                    self.parse_id()?;
+                }
+            }
+
+            self.expect(Token::LBrace, "'{'")?;
+            let nodes = self.parse_stmt_list(&mut scope.clone())?;
+            self.expect(Token::RBrace, "'}'")?;
+
+            return Ok(nodes);
+        }
+
+        let name = self.parse_id()?;
+
+        // port is not supported by editor and is skipped
+        while self.peek() == Some(&Token::Colon) {
+            self.pos += 1;
+            self.parse_id()?;
+        }
+
+        Ok(vec![self.node_index(name, scope)])
+    }
+
+    fn node_index(&mut self, name: String, scope: &Scope) -> usize {
+        if let Some(&index) = self.graph.node_indices.get(&name) {
+            return index;
+        }
+
+        let index = self.graph.nodes.len();
+        self.graph.node_indices.insert(name.clone(), index);
+        self.graph.nodes.push((name, scope.node.clone()));
+
+        index
+    }
+
+    fn parse_attr_lists(&mut self) -> Result<Attributes, String> {
+        let mut attributes = Attributes::new();
+
+        while self.peek() == Some(&Token::LBracket) {
+            self.pos += 1;
+
+            while self.peek() != Some(&Token::RBracket) {
+                let key = self.parse_id()?;
+                self.expect(Token::Equal, "'='")?;
+                let value = self.parse_id()?;
+                attributes.insert(key, value);
+
+                if matches!(self.peek(), Some(Token::Comma) | Some(Token::Semicolon)) {
+                    self.pos += 1;
+                }
+            }
+
+            self.pos += 1;
+        }
+
+        Ok(attributes)
+    }
+}
+
+impl DotGraph {
+    fn into_graph(self) -> Graph {
+        let mut graph = Graph::new();
+
+        let mut node_ids = Vec::with_capacity(self.nodes.len());
+
+        for (name, attributes) in &self.nodes {
+            let label = match attributes.get("label") {
+                Some(label) if label != "\\N" => label.clone(),
+                _ => name.clone(),
+            };
+            let position = attributes
+                .get("pos")
+                .and_then(|pos| parse_position(pos))
+                .unwrap_or_else(|| graph.random_node_position());
+
+            let mut node = Node::new(label, position);
+
+            if let Some(shape) = attributes.get("shape").and_then(|s| parse_shape(s)) {
+                let rounded = attributes
+                    .get("style")
+                    .is_some_and(|style| style.contains("rounded"));
+                node.shape = match shape {
+                    NodeShape::Square if rounded => NodeShape::RoundedRect,
+                    shape => shape,
+                };
+            }
+            if let Some(color) = attributes
+                .get("fillcolor")
+                .or(attributes.get("color"))
+                .and_then(|c| color_from_str(c))
+            {
+                node.color = color;
+            }
+            let dimension = |key: &str| {
+                attributes
+                    .get(key)
+                    .and_then(|d| d.parse::<f32>().ok())
+                    .filter(|d| d.is_finite() && *d > 0.0)
+            };
+            let (width, height) = (dimension("width"), dimension("height"));
+            if let Some(height) = height.or(width) {
+                node.size = (height * POINTS_PER_INCH / 2.0).clamp(MIN_NODE_SIZE, MAX_NODE_SIZE);
+            }
+            let square = attributes.get("shape").is_some_and(|s| s == "square");
+            if let (Some(width), Some(height)) = (width, height) {
+                if node.shape.has_aspect_ratio() && !square {
+                    node.aspect_ratio =
+                        (width / height).clamp(MIN_NODE_ASPECT_RATIO, MAX_NODE_ASPECT_RATIO);
+                }
+            }
+
+            node.attributes = read_custom_attributes(attributes, &NODE_KEYS);
+
+            node_ids.push(graph.insert_node(node));
+        }
+
+        for (start, end, attributes) in self.edges {
+            let mut edge = Edge::new(node_ids[start], node_ids[end]);
+
+            edge.oriented = match attributes.get("dir").map(String::as_str) {
+                Some("none") => false,
+                Some("forward") | Some("back") | Some("both") => true,
+                _ => self.directed,
+            };
+            let arrow = |key: &str| attributes.get(key).and_then(|a| parse_arrow(a));
+            match attributes.get("dir").map(String::as_str) {
+                Some("back") => {
+                    std::mem::swap(&mut edge.start_id, &mut edge.end_id);
+                    edge.target_arrow = arrow("arrowtail").unwrap_or(ArrowHead::Filled);
+                }
+                Some("both") => {
+                    edge.source_arrow = arrow("arrowtail").unwrap_or(ArrowHead::Filled);
+                    edge.target_arrow = arrow("arrowhead").unwrap_or(ArrowHead::Filled);
+                }
+                _ => edge.target_arrow = arrow("arrowhead").unwrap_or(ArrowHead::Filled),
+            }
+            if let Some(style) = attributes.get("style") {
+                if style.contains("dashed") {
+                    edge.line_style = LineStyle::Dashed;
+                } else if style.contains("dotted") {
+                    edge.line_style = LineStyle::Dotted;
+                }
+            }
+            if let Some(label) = attributes.get("label") {
+                edge.label = label.clone();
+            }
+            if let Some(colors) = attributes.get("color") {
+                // color list is drawn as parallel lines
+                let mut colors = colors.split(':');
+                if let Some(color) = colors.next().and_then(color_from_str) {
+                    edge.color = color;
+                }
+                if colors.next().is_some() {
+                    edge.line_style = LineStyle::Double;
+                }
+            }
+            if let Some(width) = attributes
+                .get("penwidth")
+                .and_then(|w| w.parse::<f32>().ok())
+            {
+                edge.width = width.clamp(MIN_EDGE_WIDTH, MAX_EDGE_WIDTH);
+            }
+            if let Some(weight) = attributes
+                .get("cost")
+                .and_then(|w| w.parse::<f32>().ok())
+                .filter(|w| w.is_finite())
+            {
+                edge.weight = weight;
+            }
+            edge.attributes = read_custom_attributes(&attributes, &EDGE_KEYS);
+
+            graph.insert_edge(edge);
+        }
+
+        graph
+    }
+}
+
+/// Parse DOT position `"x,y"` (optionally with trailing `!`), flipping y axis.
+fn parse_position(value: &str) -> Option<Pos2> {
+    let value = value.trim().trim_end_matches('!');
+    let (x, y) = value.split_once(',')?;
+    let x = x.trim().parse::<f32>().ok()?;
+    // 3D position has extra coordinate
+    let y = y.split(',').next()?.trim().parse::<f32>().ok()?;
+
+    Some(Pos2::new(x, -y))
+}
+
+fn parse_arrow(value: &str) -> Option<ArrowHead> {
+    match value.to_lowercase().as_str() {
+        "none" => Some(ArrowHead::None),
+        "normal" | "inv" => Some(ArrowHead::Filled),
+        "vee" | "open" | "onormal" | "empty" => Some(ArrowHead::Open),
+        "diamond" | "odiamond" | "ediamond" => Some(ArrowHead::Diamond),
+        "dot" | "odot" => Some(ArrowHead::Circle),
+        _ => None,
+    }
+}
+
+fn parse_shape(value: &str) -> Option<NodeShape> {
+    match value.to_lowercase().as_str() {
+        "circle" | "point" | "doublecircle" => Some(NodeShape::Circle),
+        "square" | "box" | "rect" | "rectangle" => Some(NodeShape::Square),
+        "ellipse" | "oval" => Some(NodeShape::Ellipse),
+        "diamond" => Some(NodeShape::Diamond),
+        "triangle" => Some(NodeShape::Triangle),
+        "hexagon" => Some(NodeShape::Hexagon),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::{pos2, vec2};
+
+    use super::*;
+    use crate::graph::NodeId;
+
+    fn sample_graph() -> Graph {
+        let mut graph = Graph::new();
+
+        let mut a = Node::new("a \"quoted\"".to_owned(), pos2(-40.0, 25.5));
+        a.shape = NodeShape::Square;
+        a.aspect_ratio = 2.0;
+        a.size = 30.0;
+        let a = graph.insert_node(a);
+
+        let mut b = Node::new("b".to_owned(), pos2(120.0, -60.0));
+        b.shape = NodeShape::Hexagon;
+        b.attributes
+            .set("owner", AttributeValue::String("me".to_owned()));
+        let b = graph.insert_node(b);
+
+        let mut edge = Edge::new(a, b);
+        edge.weight = 2.5;
+        edge.line_style = LineStyle::Dashed;
+        edge.label = "ab".to_owned();
+        graph.insert_edge(edge);
+
+        let mut unoriented = Edge::new(b, a);
+        unoriented.oriented = false;
+        graph.insert_edge(unoriented);
+
+        graph
+    }
+
+    #[test]
+    fn round_trip_keeps_graph() {
+        let graph = sample_graph();
+        let imported = import(&export(&graph)).unwrap();
+
+        assert_eq!(imported.nodes().len(), 2);
+        for id in [NodeId(1), NodeId(2)] {
+            let (node, imported) = (graph.node(&id).unwrap(), imported.node(&id).unwrap());
+            assert_eq!(imported.label, node.label);
+            assert_eq!(imported.position, node.position);
+            assert_eq!(imported.shape, node.shape);
+            assert!((imported.size - node.size).abs() < 0.01);
+            assert!((imported.aspect_ratio - node.aspect_ratio).abs() < 0.001);
+            assert_eq!(imported.attributes, node.attributes);
+        }
+
+        let edges: Vec<_> = imported.edges().values().collect();
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].weight, 2.5);
+        assert_eq!(edges[0].line_style, LineStyle::Dashed);
+        assert_eq!(edges[0].label, "ab");
+        assert!(edges[0].oriented);
+        assert!(!edges[1].oriented);
+    }
+
+    #[test]
+    fn unoriented_loop_in_digraph_stays_unoriented() {
+        let mut graph = sample_graph();
+        let mut unoriented_loop = Edge::new(NodeId(1), NodeId(1));
+        unoriented_loop.oriented = false;
+        graph.insert_edge(unoriented_loop);
+
+        let text = export(&graph);
+        assert!(text.starts_with("digraph"));
+
+        let imported = import(&text).unwrap();
+        let edges: Vec<_> = imported.edges().values().collect();
+        assert!(edges[2].is_loop());
+        assert!(!edges[2].oriented);
+    }
+
+    #[test]
+    fn oriented_loop_makes_digraph() {
+        let mut graph = Graph::new();
+        let node = graph.insert_node(Node::new("a".to_owned(), pos2(0.0, 0.0)));
+        graph.insert_edge(Edge::new(node, node));
+
+        let text = export(&graph);
+        assert!(text.starts_with("digraph"));
+
+        let imported = import(&text).unwrap();
+        let edge = imported.edges().values().next().unwrap();
+        assert!(edge.is_loop());
+        assert!(edge.oriented);
+    }
+
+    #[test]
+    fn cost_is_not_graphviz_weight() {
+        let text = export(&sample_graph());
+        assert!(text.contains("cost=2.5"));
+        assert!(!text.contains("weight"));
+
+        let graph = import("digraph { a -> b [weight=7] }").unwrap();
+        let edge = graph.edges().values().next().unwrap();
+        assert_eq!(edge.weight, DEFAULT_EDGE_WEIGHT);
+        assert_eq!(
+            edge.attributes.get("weight"),
+            Some(&AttributeValue::Number(7.0))
+        );
+    }
+
+    #[test]
+    fn box_keeps_width_and_height() {
+        let graph = import("graph { a [shape=box, width=2, height=1] }").unwrap();
+        let node = graph.nodes().values().next().unwrap();
+
+        assert_eq!(node.shape, NodeShape::Square);
+        assert_eq!(node.aspect_ratio, 2.0);
+        assert_eq!(node.half_size(), vec2(2.0, 1.0) * POINTS_PER_INCH / 2.0);
+    }
+
+    #[test]
+    fn malformed_input_is_error() {
+        for text in [
+            "",
+            "digraph {",
+            "digraph { a -> }",
+            "graph { a [label=] }",
+            "graph { a [label=\"b] }",
+            "node { a }",
+        ] {
+            assert!(import(text).is_err(), "{text:?} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,917 @@
+//! GraphML import and export.
+//!
+//! Node and edge fields are declared as GraphML keys,
+//! keys unknown to editor are kept in `foreign_data` of nodes, edges and graph
+//! and written back on export.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use eframe::egui::Pos2;
+use quick_xml::{
+    escape::escape,
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::{
+    consts::{
+        MAX_EDGE_WIDTH, MAX_LOOP_EDGE_ANGLE, MAX_NODE_ASPECT_RATIO, MAX_NODE_SIZE, MIN_EDGE_WIDTH,
+        MIN_LOOP_EDGE_ANGLE, MIN_NODE_ASPECT_RATIO, MIN_NODE_SIZE,
+    },
+    error::GraphEditorError,
+    format::{color_from_str, color_to_hex},
+    graph::{
+        attribute::{AttributeKind, AttributeValue, Attributes},
+        ArrowHead, Edge, EdgeRouting, ForeignKey, ForeignKeyDomain, Graph, LineStyle, Node, NodeId,
+        NodeShape,
+    },
+};
+
+/// Keys of node fields: (id, attr.name, attr.type).
+const NODE_KEYS: [(&str, &str, &str); 7] = [
+    ("n_label", "label", "string"),
+    ("n_size", "size", "double"),
+    ("n_shape", "shape", "string"),
+    ("n_aspect_ratio", "aspect_ratio", "double"),
+    ("n_color", "color", "string"),
+    ("n_x", "x", "double"),
+    ("n_y", "y", "double"),
+];
+
+/// Keys of edge fields: (id, attr.name, attr.type).
+const EDGE_KEYS: [(&str, &str, &str); 11] = [
+    ("e_oriented", "oriented", "boolean"),
+    ("e_label", "label", "string"),
+    ("e_width", "width", "double"),
+    ("e_weight", "weight", "double"),
+    ("e_color", "color", "string"),
+    ("e_loop_rotation_angle", "loop_rotation_angle", "double"),
+    ("e_line_style", "line_style", "string"),
+    ("e_source_arrow", "source_arrow", "string"),
+    ("e_target_arrow", "target_arrow", "string"),
+    ("e_routing", "routing", "string"),
+    ("e_bend_points", "bend_points", "string"),
+];
+
+// export
+pub fn export(graph: &Graph) -> String {
+    let mut out = String::new();
+
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+
+    for (id, name, value_type) in NODE_KEYS {
+        write_key(&mut out, id, "node", name, value_type, None);
+    }
+    for (id, name, value_type) in EDGE_KEYS {
+        write_key(&mut out, id, "edge", name, value_type, None);
+    }
+    let node_attributes = graph.node_attribute_columns();
+    let edge_attributes = graph.edge_attribute_columns();
+    for (domain, columns) in [("node", &node_attributes), ("edge", &edge_attributes)] {
+        for (index, (name, kind)) in columns.iter().enumerate() {
+            write_key(
+                &mut out,
+                &attribute_key_id(domain, index),
+                domain,
+                name,
+                attribute_type(*kind),
+                None,
+            );
+        }
+    }
+    for (index, key) in graph.foreign_keys().iter().enumerate() {
+        write_key(
+            &mut out,
+            &foreign_key_id(index),
+            domain_name(key.domain),
+            &key.name,
+            &key.value_type,
+            key.default.as_deref(),
+        );
+    }
+
+    out.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+    write_foreign_data(
+        &mut out,
+        graph,
+        ForeignKeyDomain::Graph,
+        graph.foreign_data(),
+    );
+
+    let mut nodes: Vec<_> = graph.nodes().iter().collect();
+    nodes.sort_by_key(|(id, _)| id.0);
+
+    for (id, node) in nodes {
+        let _ = writeln!(out, "    <node id=\"n{}\">", id.0);
+
+        let values = [
+            node.label.clone(),
+            node.size.to_string(),
+            shape_name(node.shape).to_owned(),
+            node.aspect_ratio.to_string(),
+            color_to_hex(node.color),
+            node.position.x.to_string(),
+            node.position.y.to_string(),
+        ];
+        for ((key, _, _), value) in NODE_KEYS.iter().zip(values) {
+            write_data(&mut out, key, &value);
+        }
+        write_attributes(&mut out, "node", &node_attributes, &node.attributes);
+        write_foreign_data(&mut out, graph, ForeignKeyDomain::Node, &node.foreign_data);
+
+        out.push_str("    </node>\n");
+    }
+
+    for (id, edge) in graph.edges() {
+        let _ = writeln!(
+            out,
+            "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\" directed=\"{}\">",
+            id.0, edge.start_id.0, edge.end_id.0, edge.oriented
+        );
+
+        let values = [
+            edge.oriented.to_string(),
+            edge.label.clone(),
+            edge.width.to_string(),
+            edge.weight.to_string(),
+            color_to_hex(edge.color),
+            edge.loop_rotation_angle.to_string(),
+            edge.line_style.name().to_lowercase(),
+            edge.source_arrow.name().to_lowercase(),
+            edge.target_arrow.name().to_lowercase(),
+            edge.routing.name().to_lowercase(),
+            write_points(&edge.bend_points),
+        ];
+        for ((key, _, _), value) in EDGE_KEYS.iter().zip(values) {
+            write_data(&mut out, key, &value);
+        }
+        write_attributes(&mut out, "edge", &edge_attributes, &edge.attributes);
+        write_foreign_data(&mut out, graph, ForeignKeyDomain::Edge, &edge.foreign_data);
+
+        out.push_str("    </edge>\n");
+    }
+
+    out.push_str("  </graph>\n");
+    out.push_str("</graphml>\n");
+    out
+}
+
+fn write_key(
+    out: &mut String,
+    id: &str,
+    domain: &str,
+    name: &str,
+    value_type: &str,
+    default: Option<&str>,
+) {
+    let _ = write!(
+        out,
+        "  <key id=\"{}\" for=\"{domain}\" attr.name=\"{}\"",
+        escape(id),
+        escape(name)
+    );
+    if !value_type.is_empty() {
+        let _ = write!(out, " attr.type=\"{}\"", escape(value_type));
+    }
+
+    match default {
+        Some(default) => {
+            let _ = writeln!(
+                out,
+                ">\n    <default>{}</default>\n  </key>",
+                escape(default)
+            );
+        }
+        None => out.push_str("/>\n"),
+    }
+}
+
+fn write_data(out: &mut String, key: &str, value: &str) {
+    let _ = writeln!(
+        out,
+        "      <data key=\"{}\">{}</data>",
+        escape(key),
+        escape(value)
+    );
+}
+
+fn write_attributes(
+    out: &mut String,
+    domain: &str,
+    columns: &[(String, AttributeKind)],
+    attributes: &Attributes,
+) {
+    for (index, (name, _)) in columns.iter().enumerate() {
+        if let Some(value) = attributes.get(name) {
+            write_data(out, &attribute_key_id(domain, index), &value.to_string());
+        }
+    }
+}
+
+fn attribute_key_id(domain: &str, index: usize) -> String {
+    format!("{}a{index}", &domain[..1])
+}
+
+fn attribute_type(kind: AttributeKind) -> &'static str {
+    match kind {
+        AttributeKind::String => "string",
+        AttributeKind::Number => "double",
+        AttributeKind::Bool => "boolean",
+    }
+}
+
+/// Kind of attribute for GraphML type, None for complex types.
+fn attribute_kind(value_type: &str) -> Option<AttributeKind> {
+    match value_type {
+        "string" => Some(AttributeKind::String),
+        "int" | "long" | "float" | "double" => Some(AttributeKind::Number),
+        "boolean" => Some(AttributeKind::Bool),
+        _ => None,
+    }
+}
+
+fn write_foreign_data(
+    out: &mut String,
+    graph: &Graph,
+    domain: ForeignKeyDomain,
+    data: &BTreeMap<String, String>,
+) {
+    for (index, key) in graph.foreign_keys().iter().enumerate() {
+        if key.domain != domain && key.domain != ForeignKeyDomain::All {
+            continue;
+        }
+        if let Some(value) = data.get(&key.name) {
+            write_data(out, &foreign_key_id(index), value);
+        }
+    }
+}
+
+fn foreign_key_id(index: usize) -> String {
+    format!("f{index}")
+}
+
+fn domain_name(domain: ForeignKeyDomain) -> &'static str {
+    match domain {
+        ForeignKeyDomain::Node => "node",
+        ForeignKeyDomain::Edge => "edge",
+        ForeignKeyDomain::Graph => "graph",
+        ForeignKeyDomain::All => "all",
+    }
+}
+
+fn shape_name(shape: NodeShape) -> &'static str {
+    match shape {
+        NodeShape::Circle => "circle",
+        NodeShape::Square => "square",
+        NodeShape::Ellipse => "ellipse",
+        NodeShape::Diamond => "diamond",
+        NodeShape::Triangle => "triangle",
+        NodeShape::Hexagon => "hexagon",
+        NodeShape::RoundedRect => "roundrectangle",
+    }
+}
+
+// import
+pub fn import(text: &str) -> Result<Graph, GraphEditorError> {
+    parse(text).map_err(GraphEditorError::FailedParseFile)
+}
+
+/// Key declared in file.
+struct KeyDeclaration {
+    domain: Option<ForeignKeyDomain>,
+    name: String,
+    /// Empty for keys without `attr.type` (e.g. yFiles graphics), they aren't attributes.
+    value_type: String,
+    default: Option<String>,
+}
+
+/// Node or edge being read: GraphML attributes and data by key id.
+#[derive(Default)]
+struct Element {
+    attributes: HashMap<String, String>,
+    data: Vec<(String, String)>,
+}
+
+fn parse(text: &str) -> Result<Graph, String> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    let mut keys: HashMap<String, KeyDeclaration> = HashMap::new();
+    let mut key_order: Vec<String> = vec![];
+    let mut edge_default_directed = true;
+    let mut nodes: Vec<Element> = vec![];
+    let mut edges: Vec<Element> = vec![];
+    let mut graph_data = Element::default();
+
+    // element which is read now and its kind
+    let mut current: Option<(&str, Element)> = None;
+    let mut current_key: Option<String> = None;
+    let mut current_data: Option<(String, String)> = None;
+    let mut in_default = false;
+    let mut found_root = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|err| format!("position {}: {err}", reader.error_position()))?;
+
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let is_empty = matches!(event, Event::Empty(_));
+                let attributes = read_attributes(e)?;
+
+                match e.local_name().as_ref() {
+                    b"graphml" => found_root = true,
+                    b"key" => {
+                        let id = attributes
+                            .get("id")
+                            .cloned()
+                            .ok_or("key without id".to_owned())?;
+                        // keys of ports, hyperedges and whole file are dropped
+                        let domain = match attributes.get("for").map(String::as_str) {
+                            Some("node") => Some(ForeignKeyDomain::Node),
+                            Some("edge") => Some(ForeignKeyDomain::Edge),
+                            Some("graph") => Some(ForeignKeyDomain::Graph),
+                            Some("all") | None => Some(ForeignKeyDomain::All),
+                            _ => None,
+                        };
+                        let declaration = KeyDeclaration {
+                            domain,
+                            name: attributes.get("attr.name").cloned().unwrap_or(id.clone()),
+                            value_type: attributes.get("attr.type").cloned().unwrap_or_default(),
+                            default: None,
+                        };
+
+                        key_order.push(id.clone());
+                        keys.insert(id.clone(), declaration);
+                        if !is_empty {
+                            current_key = Some(id);
+                        }
+                    }
+                    b"default" => in_default = !is_empty,
+                    b"graph"
+                        if attributes.get("edgedefault").map(String::as_str)
+                            == Some("undirected") =>
+                    {
+                        edge_default_directed = false;
+                    }
+                    b"node" | b"edge" => {
+                        let kind = if e.local_name().as_ref() == b"node" {
+                            "node"
+                        } else {
+                            "edge"
+                        };
+                        let element = Element {
+                            attributes,
+                            data: vec![],
+                        };
+
+                        if is_empty {
+                            push_element(kind, element, &mut nodes, &mut edges);
+                        } else {
+                            current = Some((kind, element));
+                        }
+                    }
+                    b"data" => {
+                        let key = attributes
+                            .get("key")
+                            .cloned()
+                            .ok_or("data without key".to_owned())?;
+                        if is_empty {
+                            match current.as_mut() {
+                                Some((_, element)) => element.data.push((key, String::new())),
+                                None => graph_data.data.push((key, String::new())),
+                            }
+                        } else {
+                            current_data = Some((key, String::new()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(e) => {
+                let value = e.unescape().map_err(|err| err.to_string())?;
+
+                if let Some((_, data)) = current_data.as_mut() {
+                    data.push_str(&value);
+                } else if in_default {
+                    if let Some(key) = current_key.as_ref().and_then(|id| keys.get_mut(id)) {
+                        key.default = Some(value.into_owned());
+                    }
+                }
+            }
+            Event::CData(e) => {
+                if let Some((_, data)) = current_data.as_mut() {
+                    data.push_str(&String::from_utf8_lossy(&e));
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"key" => current_key = None,
+                b"default" => in_default = false,
+                b"data" => {
+                    // data outside of nodes and edges belongs to graph
+                    if let Some(data) = current_data.take() {
+                        match current.as_mut() {
+                            Some((_, element)) => element.data.push(data),
+                            None => graph_data.data.push(data),
+                        }
+                    }
+                }
+                b"node" | b"edge" => {
+                    if let Some((kind, element)) = current.take() {
+                        push_element(kind, element, &mut nodes, &mut edges);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !found_root {
+        return Err("'graphml' element not found".to_owned());
+    }
+
+    build_graph(
+        keys,
+        key_order,
+        edge_default_directed,
+        nodes,
+        edges,
+        graph_data,
+    )
+}
+
+fn push_element(kind: &str, element: Element, nodes: &mut Vec<Element>, edges: &mut Vec<Element>) {
+    if kind == "node" {
+        nodes.push(element);
+    } else {
+        edges.push(element);
+    }
+}
+
+fn read_attributes(e: &BytesStart) -> Result<HashMap<String, String>, String> {
+    let mut attributes = HashMap::new();
+
+    for attribute in e.attributes() {
+        let attribute = attribute.map_err(|err| err.to_string())?;
+        let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+        let value = attribute
+            .unescape_value()
+            .map_err(|err| err.to_string())?
+            .into_owned();
+
+        attributes.insert(key, value);
+    }
+
+    Ok(attributes)
+}
+
+fn build_graph(
+    keys: HashMap<String, KeyDeclaration>,
+    key_order: Vec<String>,
+    edge_default_directed: bool,
+    nodes: Vec<Element>,
+    edges: Vec<Element>,
+    graph_data: Element,
+) -> Result<Graph, String> {
+    let mut graph = Graph::new();
+    let mut node_ids: HashMap<String, NodeId> = HashMap::new();
+
+    // keys are matched to fields by their names, other keys are foreign;
+    // if file has editor's own key of field, other keys named as field are custom attributes
+    let is_known = |id: &str, key: &KeyDeclaration| {
+        let known_keys: &[(&str, &str, &str)] = match key.domain {
+            Some(ForeignKeyDomain::Node) => &NODE_KEYS,
+            Some(ForeignKeyDomain::Edge) => &EDGE_KEYS,
+            Some(ForeignKeyDomain::Graph | ForeignKeyDomain::All) | None => &[],
+        };
+        known_keys.iter().any(|(known_id, name, _)| {
+            *name == key.name && (id == *known_id || !keys.contains_key(*known_id))
+        })
+    };
+
+    // unknown node and edge keys of simple types are attributes
+    let attribute_kind = |id: &str, key: &KeyDeclaration| {
+        let element_key = matches!(
+            key.domain,
+            Some(ForeignKeyDomain::Node | ForeignKeyDomain::Edge)
+        );
+        attribute_kind(&key.value_type).filter(|_| element_key && !is_known(id, key))
+    };
+    let attribute_keys = |domain: ForeignKeyDomain| {
+        let mut columns: Vec<(String, AttributeKind)> = Vec::new();
+        for (id, key) in key_order.iter().filter_map(|id| Some((id, keys.get(id)?))) {
+            if let Some(kind) = attribute_kind(id, key).filter(|_| key.domain == Some(domain)) {
+                if !columns.iter().any(|(name, _)| *name == key.name) {
+                    columns.push((key.name.clone(), kind));
+                }
+            }
+        }
+        columns
+    };
+    let (node_attributes, edge_attributes) = (
+        attribute_keys(ForeignKeyDomain::Node),
+        attribute_keys(ForeignKeyDomain::Edge),
+    );
+
+    let foreign_keys = key_order
+        .iter()
+        .filter_map(|id| Some((id, keys.get(id)?)))
+        .filter(|(id, key)| {
+            key.domain.is_some() && !is_known(id, key) && attribute_kind(id, key).is_none()
+        })
+        .map(|(_, key)| ForeignKey {
+            domain: key.domain.unwrap(),
+            name: key.name.clone(),
+            value_type: key.value_type.clone(),
+            default: key.default.clone(),
+        })
+        .collect();
+    graph.set_foreign_keys(foreign_keys);
+    graph.set_foreign_data(foreign_values(&graph_data, &keys, |_, _| false, &[]));
+
+    for element in nodes {
+        let id = element
+            .attributes
+            .get("id")
+            .cloned()
+            .ok_or("node without id".to_owned())?;
+        let (values, other_values) =
+            element_values(&element, &keys, ForeignKeyDomain::Node, is_known);
+
+        let label = values.get("label").cloned().unwrap_or(id.clone());
+        let position = match (
+            values.get("x").and_then(|x| x.parse::<f32>().ok()),
+            values.get("y").and_then(|y| y.parse::<f32>().ok()),
+        ) {
+            (Some(x), Some(y)) => Pos2::new(x, y),
+            _ => graph.random_node_position(),
+        };
+
+        let mut node = Node::new(label, position);
+
+        if let Some(size) = values.get("size").and_then(|s| s.parse::<f32>().ok()) {
+            node.size = size.clamp(MIN_NODE_SIZE, MAX_NODE_SIZE);
+        }
+        if let Some(shape) = values.get("shape").and_then(|s| parse_shape(s)) {
+            node.shape = shape;
+        }
+        if let Some(ratio) = values
+            .get("aspect_ratio")
+            .and_then(|r| r.parse::<f32>().ok())
+        {
+            node.aspect_ratio = ratio.clamp(MIN_NODE_ASPECT_RATIO, MAX_NODE_ASPECT_RATIO);
+        }
+        if let Some(color) = values.get("color").and_then(|c| color_from_str(c)) {
+            node.color = color;
+        }
+        node.attributes = element_attributes(&other_values, &node_attributes);
+        node.foreign_data = foreign_values(&element, &keys, is_known, &node_attributes);
+
+        node_ids.insert(id, graph.insert_node(node));
+    }
+
+    for element in edges {
+        let node_id = |attribute: &str| -> Result<NodeId, String> {
+            let name = element
+                .attributes
+                .get(attribute)
+                .ok_or(format!("edge without {attribute}"))?;
+            node_ids
+                .get(name)
+                .copied()
+                .ok_or(format!("edge refers to unknown node '{name}'"))
+        };
+
+        let mut edge = Edge::new(node_id("source")?, node_id("target")?);
+        let (values, other_values) =
+            element_values(&element, &keys, ForeignKeyDomain::Edge, is_known);
+
+        edge.oriented = values
+            .get("oriented")
+            .or(element.attributes.get("directed"))
+            .and_then(|o| o.parse::<bool>().ok())
+            .unwrap_or(edge_default_directed);
+        if let Some(label) = values.get("label") {
+            edge.label = label.clone();
+        }
+        if let Some(width) = values.get("width").and_then(|w| w.parse::<f32>().ok()) {
+            edge.width = width.clamp(MIN_EDGE_WIDTH, MAX_EDGE_WIDTH);
+        }
+        if let Some(weight) = values
+            .get("weight")
+            .and_then(|w| w.parse::<f32>().ok())
+            .filter(|w| w.is_finite())
+        {
+            edge.weight = weight;
+        }
+        if let Some(color) = values.get("color").and_then(|c| color_from_str(c)) {
+            edge.color = color;
+        }
+        if let Some(angle) = values
+            .get("loop_rotation_angle")
+            .and_then(|a| a.parse::<f32>().ok())
+        {
+            edge.loop_rotation_angle = angle.clamp(MIN_LOOP_EDGE_ANGLE, MAX_LOOP_EDGE_ANGLE);
+        }
+        if let Some(style) = values.get("line_style").and_then(|s| parse_line_style(s)) {
+            edge.line_style = style;
+        }
+        if let Some(head) = values.get("source_arrow").and_then(|a| parse_arrow(a)) {
+            edge.source_arrow = head;
+        }
+        if let Some(head) = values.get("target_arrow").and_then(|a| parse_arrow(a)) {
+            edge.target_arrow = head;
+        }
+        if let Some(routing) = values.get("routing").and_then(|r| parse_routing(r)) {
+            edge.routing = routing;
+        }
+        if let Some(points) = values.get("bend_points").and_then(|p| parse_points(p)) {
+            edge.bend_points = points;
+        }
+        edge.attributes = element_attributes(&other_values, &edge_attributes);
+        edge.foreign_data = foreign_values(&element, &keys, is_known, &edge_attributes);
+
+        graph.insert_edge(edge);
+    }
+
+    Ok(graph)
+}
+
+/// Values of element by key names, including defaults of keys:
+/// values of editor's fields and values of other keys.
+fn element_values(
+    element: &Element,
+    keys: &HashMap<String, KeyDeclaration>,
+    domain: ForeignKeyDomain,
+    is_known: impl Fn(&str, &KeyDeclaration) -> bool,
+) -> (HashMap<String, String>, HashMap<String, String>) {
+    let (mut known, mut other) = (HashMap::new(), HashMap::new());
+    let mut insert = |id: &str, key: &KeyDeclaration, value: &String| {
+        let values = if is_known(id, key) {
+            &mut known
+        } else {
+            &mut other
+        };
+        values.insert(key.name.clone(), value.clone());
+    };
+
+    for (id, key) in keys.iter().filter(|(_, key)| key.domain == Some(domain)) {
+        if let Some(default) = &key.default {
+            insert(id, key, default);
+        }
+    }
+    for (key_id, value) in &element.data {
+        if let Some(key) = keys.get(key_id) {
+            insert(key_id, key, value);
+        }
+    }
+
+    (known, other)
+}
+
+/// Attributes of element in order of their keys, value which doesn't match
+/// type of its key is kept as string.
+fn element_attributes(
+    values: &HashMap<String, String>,
+    columns: &[(String, AttributeKind)],
+) -> Attributes {
+    let mut attributes = Attributes::default();
+
+    for (name, kind) in columns {
+        if let Some(text) = values.get(name) {
+            let value = AttributeValue::parse(*kind, text)
+                .unwrap_or_else(|| AttributeValue::String(text.clone()));
+            attributes.set(name, value);
+        }
+    }
+
+    attributes
+}
+
+/// Values of element given explicitly for keys unknown to editor, which aren't attributes.
+fn foreign_values(
+    element: &Element,
+    keys: &HashMap<String, KeyDeclaration>,
+    is_known: impl Fn(&str, &KeyDeclaration) -> bool,
+    attribute_keys: &[(String, AttributeKind)],
+) -> BTreeMap<String, String> {
+    element
+        .data
+        .iter()
+        .filter_map(|(key_id, value)| Some((key_id, keys.get(key_id)?, value)))
+        .filter(|(key_id, key, _)| !is_known(key_id, key))
+        .map(|(_, key, value)| (&key.name, value))
+        .filter(|(name, _)| {
+            !attribute_keys
+                .iter()
+                .any(|(attribute, _)| attribute == *name)
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+fn parse_line_style(value: &str) -> Option<LineStyle> {
+    LineStyle::ALL
+        .into_iter()
+        .find(|style| style.name().eq_ignore_ascii_case(value))
+}
+
+fn parse_arrow(value: &str) -> Option<ArrowHead> {
+    ArrowHead::ALL
+        .into_iter()
+        .find(|head| head.name().eq_ignore_ascii_case(value))
+}
+
+fn parse_routing(value: &str) -> Option<EdgeRouting> {
+    EdgeRouting::ALL
+        .into_iter()
+        .find(|routing| routing.name().eq_ignore_ascii_case(value))
+}
+
+/// Points written as `x,y x,y`.
+fn write_points(points: &[Pos2]) -> String {
+    points
+        .iter()
+        .map(|p| format!("{},{}", p.x, p.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Points written as `x,y x,y`, None if some of them isn't finite point.
+fn parse_points(value: &str) -> Option<Vec<Pos2>> {
+    value
+        .split_whitespace()
+        .map(|point| {
+            let (x, y) = point.split_once(',')?;
+            let (x, y) = (x.trim().parse::<f32>().ok()?, y.trim().parse::<f32>().ok()?);
+            (x.is_finite() && y.is_finite()).then_some(Pos2::new(x, y))
+        })
+        .collect()
+}
+
+fn parse_shape(value: &str) -> Option<NodeShape> {
+    match value.to_lowercase().as_str() {
+        "circle" => Some(NodeShape::Circle),
+        "square" | "rectangle" | "box" => Some(NodeShape::Square),
+        "ellipse" => Some(NodeShape::Ellipse),
+        "diamond" => Some(NodeShape::Diamond),
+        "triangle" => Some(NodeShape::Triangle),
+        "hexagon" => Some(NodeShape::Hexagon),
+        "roundrectangle" | "roundrect" => Some(NodeShape::RoundedRect),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::pos2;
+
+    use super::*;
+
+    fn sample_graph() -> Graph {
+        let mut graph = Graph::new();
+
+        let mut a = Node::new("a & <b>".to_owned(), pos2(-40.0, 25.5));
+        a.shape = NodeShape::Ellipse;
+        a.aspect_ratio = 1.5;
+        a.attributes.set("capacity", AttributeValue::Number(3.0));
+        let a = graph.insert_node(a);
+        let b = graph.insert_node(Node::new("b".to_owned(), pos2(120.0, -60.0)));
+
+        let mut edge = Edge::new(a, b);
+        edge.weight = -2.5;
+        edge.oriented = false;
+        edge.line_style = LineStyle::Double;
+        edge.routing = EdgeRouting::Polyline;
+        edge.bend_points = vec![pos2(10.0, 20.0), pos2(30.5, -4.0)];
+        edge.attributes
+            .set("owner", AttributeValue::String("me".to_owned()));
+        graph.insert_edge(edge);
+        graph.add_edge(b, b);
+
+        graph
+    }
+
+    #[test]
+    fn round_trip_keeps_graph() {
+        let graph = sample_graph();
+        let imported = import(&export(&graph)).unwrap();
+
+        assert_eq!(imported.nodes().len(), 2);
+        for (id, node) in graph.nodes() {
+            let imported = imported.node(id).unwrap();
+            assert_eq!(imported.label, node.label);
+            assert_eq!(imported.position, node.position);
+            assert_eq!(imported.shape, node.shape);
+            assert_eq!(imported.aspect_ratio, node.aspect_ratio);
+            assert_eq!(imported.attributes, node.attributes);
+        }
+
+        assert_eq!(imported.edges().len(), 2);
+        for (edge, imported) in graph.edges().values().zip(imported.edges().values()) {
+            assert_eq!(
+                (imported.start_id, imported.end_id),
+                (edge.start_id, edge.end_id)
+            );
+            assert_eq!(imported.oriented, edge.oriented);
+            assert_eq!(imported.weight, edge.weight);
+            assert_eq!(imported.line_style, edge.line_style);
+            assert_eq!(imported.routing, edge.routing);
+            assert_eq!(imported.bend_points, edge.bend_points);
+            assert_eq!(imported.attributes, edge.attributes);
+        }
+    }
+
+    #[test]
+    fn attributes_named_as_fields_are_kept_apart() {
+        let mut graph = Graph::new();
+        let mut node = Node::new("real".to_owned(), pos2(0.0, 0.0));
+        node.attributes
+            .set("label", AttributeValue::String("fake".to_owned()));
+        let a = graph.insert_node(node);
+        let b = graph.insert_node(Node::new("b".to_owned(), pos2(50.0, 0.0)));
+        let mut edge = Edge::new(a, b);
+        edge.weight = 2.0;
+        edge.attributes.set("weight", AttributeValue::Number(7.0));
+        edge.attributes
+            .set("color", AttributeValue::String("blue".to_owned()));
+        let color = edge.color;
+        graph.insert_edge(edge);
+
+        let imported = import(&export(&graph)).unwrap();
+
+        let node = imported.node(&a).unwrap();
+        assert_eq!(node.label, "real");
+        assert_eq!(node.attributes, graph.node(&a).unwrap().attributes);
+        assert!(node.foreign_data.is_empty());
+
+        let imported_edge = imported.edges().values().next().unwrap();
+        assert_eq!(imported_edge.weight, 2.0);
+        assert_eq!(imported_edge.color, color);
+        assert_eq!(
+            imported_edge.attributes,
+            graph.edges().values().next().unwrap().attributes
+        );
+        assert!(imported_edge.foreign_data.is_empty());
+    }
+
+    #[test]
+    fn unknown_keys_and_data_are_kept() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+              <key id="g0" for="graph" attr.name="author" attr.type="string"/>
+              <key id="k0" for="all" attr.name="note" attr.type="string"/>
+              <key id="y0" for="node" yfiles.type="nodegraphics"/>
+              <graph id="G" edgedefault="undirected">
+                <data key="g0">Ada</data>
+                <data key="k0">whole graph</data>
+                <node id="a">
+                  <data key="k0">first</data>
+                  <data key="y0"><![CDATA[<shape/>]]></data>
+                </node>
+                <node id="b"/>
+                <edge source="a" target="b">
+                  <data key="k0">link</data>
+                </edge>
+              </graph>
+            </graphml>"#;
+
+        let check = |graph: &Graph| {
+            assert_eq!(graph.foreign_keys().len(), 3);
+            assert_eq!(graph.foreign_data()["author"], "Ada");
+            assert_eq!(graph.foreign_data()["note"], "whole graph");
+
+            let a = graph.nodes().values().find(|n| n.label == "a").unwrap();
+            assert_eq!(a.foreign_data["note"], "first");
+            assert_eq!(a.foreign_data["y0"], "<shape/>");
+
+            let edge = graph.edges().values().next().unwrap();
+            assert!(!edge.oriented);
+            assert_eq!(edge.foreign_data["note"], "link");
+        };
+
+        let graph = import(text).unwrap();
+        check(&graph);
+        check(&import(&export(&graph)).unwrap());
+    }
+
+    #[test]
+    fn malformed_input_is_error() {
+        for text in [
+            "",
+            "not xml at all",
+            "<graph><node id=\"a\"/></graph>",
+            "<graphml><graph><node id=\"a\"></graph></graphml>",
+            "<graphml><key for=\"node\"/></graphml>",
+            "<graphml><graph><node/></graph></graphml>",
+            "<graphml><graph><edge source=\"a\" target=\"b\"/></graph></graphml>",
+        ] {
+            assert!(import(text).is_err(), "{text:?} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+pub mod dot;
+pub mod graphml;
+pub mod svg;
+
+use std::path::Path;
+
+use eframe::{
+    egui::{Color32, Rgba},
+    epaint::ecolor::HexColor,
+};
+
+use crate::{error::GraphEditorError, graph::Graph};
+
+/// Formats used to exchange graphs with other tools.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExchangeFormat {
+    Dot,
+    GraphMl,
+}
+
+impl ExchangeFormat {
+    pub const ALL: [ExchangeFormat; 2] = [ExchangeFormat::Dot, ExchangeFormat::GraphMl];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExchangeFormat::Dot => "DOT",
+            ExchangeFormat::GraphMl => "GraphML",
+        }
+    }
+
+    /// File extensions of format, the first one is preferred.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            ExchangeFormat::Dot => &["dot", "gv"],
+            ExchangeFormat::GraphMl => &["graphml"],
+        }
+    }
+
+    /// Format of file with given path, by its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extensions().contains(&extension.as_str()))
+    }
+
+    pub fn export(&self, graph: &Graph) -> String {
+        match self {
+            ExchangeFormat::Dot => dot::export(graph),
+            ExchangeFormat::GraphMl => graphml::export(graph),
+        }
+    }
+
+    pub fn import(&self, text: &str) -> Result<Graph, GraphEditorError> {
+        match self {
+            ExchangeFormat::Dot => dot::import(text),
+            ExchangeFormat::GraphMl => graphml::import(text),
+        }
+    }
+}
+
+/// Format color as `#rrggbb` (or `#rrggbbaa` for translucent colors).
+pub fn color_to_hex(color: Rgba) -> String {
+    let color = Color32::from(color);
+
+    if color.a() == u8::MAX {
+        HexColor::Hex6(color).to_string()
+    } else {
+        HexColor::Hex8(color).to_string()
+    }
+}
+
+/// Parse color given as hex string or as one of common color names.
+pub fn color_from_str(value: &str) -> Option<Rgba> {
+    let value = value.trim();
+
+    if value.starts_with('#') {
+        return Color32::from_hex(value).ok().map(Rgba::from);
+    }
+
+    let color = match value.to_lowercase().as_str() {
+        "black" => Color32::BLACK,
+        "white" => Color32::WHITE,
+        "red" => Color32::RED,
+        "green" => Color32::GREEN,
+        "blue" => Color32::BLUE,
+        "yellow" => Color32::YELLOW,
+        "orange" => Color32::ORANGE,
+        "brown" => Color32::BROWN,
+        "gold" => Color32::GOLD,
+        "gray" | "grey" => Color32::GRAY,
+        "lightgray" | "lightgrey" => Color32::LIGHT_GRAY,
+        "darkgray" | "darkgrey" => Color32::DARK_GRAY,
+        "lightblue" => Color32::LIGHT_BLUE,
+        "darkblue" => Color32::DARK_BLUE,
+        "lightgreen" => Color32::LIGHT_GREEN,
+        "darkgreen" => Color32::DARK_GREEN,
+        "lightred" => Color32::LIGHT_RED,
+        "darkred" => Color32::DARK_RED,
+        "lightyellow" => Color32::LIGHT_YELLOW,
+        "cyan" => Color32::from_rgb(0, 255, 255),
+        "magenta" => Color32::from_rgb(255, 0, 255),
+        "purple" => Color32::from_rgb(128, 0, 128),
+        "pink" => Color32::from_rgb(255, 192, 203),
+        "transparent" | "none" => Color32::TRANSPARENT,
+        _ => return None,
+    };
+
+    Some(Rgba::from(color))
+}
@@ -0,0 +1,355 @@
+//! SVG export of graph and comment lines, matching what canvas draws.
+
+use std::fmt::Write;
+
+use eframe::{
+    egui::{vec2, Color32, FontFamily, FontId, Pos2, Rect, Rgba, Vec2},
+    emath::Rot2,
+    epaint::text::{FontDefinitions, Fonts},
+};
+use quick_xml::escape::escape;
+
+use crate::{
+    comment_line::group::CommentsGroup,
+    consts::{
+        DASH_GAP_COEF, DASH_LENGTH_COEF, DOT_SPACING_COEF, DOUBLE_LINE_OFFSET_COEF, RENDER_MARGIN,
+    },
+    graph::{Edge, Graph, LineStyle, Node, NodeShape},
+    utils::geometry::edge::{
+        arrow_head_geometry, edges_geometry, label_background, label_text_position,
+        ArrowHeadGeometry, EdgeCurve, EdgeGeometry,
+    },
+};
+
+/// Font family of egui body text, used by canvas for edge labels.
+const EDGE_LABEL_FAMILY: FontFamily = FontFamily::Proportional;
+
+pub fn export(graph: &Graph, comment_lines: &CommentsGroup) -> String {
+    // text is measured with same fonts as on canvas to place label backgrounds
+    let fonts = Fonts::new(1.0, 2048, FontDefinitions::default());
+    let measure = |text: &str, font_id: FontId| {
+        fonts
+            .layout_no_wrap(text.to_owned(), font_id, Color32::BLACK)
+            .size()
+    };
+
+    let mut bounds = Rect::NOTHING;
+    let mut body = String::new();
+
+    let edges = edges_geometry(graph, None);
+
+    for (id, geometry) in &edges {
+        let edge = &graph.edges()[id];
+        write_edge(&mut body, edge, geometry, &mut bounds, &measure);
+    }
+
+    let mut nodes: Vec<_> = graph.nodes().iter().collect();
+    nodes.sort_by_key(|(id, _)| id.0);
+
+    for (_, node) in nodes {
+        write_node(&mut body, node);
+        bounds = bounds.union(Rect::from_center_size(
+            node.position,
+            2.0 * node.half_size(),
+        ));
+    }
+
+    for (_, line) in comment_lines.iter().filter(|(_, line)| line.len() >= 2) {
+        let points = line
+            .points
+            .iter()
+            .map(|p| format!("{},{}", num(p.x), num(p.y)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            body,
+            "  <polyline points=\"{points}\" fill=\"none\" {} stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>",
+            paint("stroke", line.stroke.color.into()),
+            num(line.stroke.width)
+        );
+
+        for point in &line.points {
+            bounds.extend_with(*point);
+        }
+    }
+
+    if !bounds.is_positive() {
+        bounds = Rect::from_min_size(Pos2::ZERO, Vec2::splat(1.0));
+    }
+    let bounds = bounds.expand(RENDER_MARGIN);
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">",
+        num(bounds.width()),
+        num(bounds.height()),
+        num(bounds.min.x),
+        num(bounds.min.y),
+        num(bounds.width()),
+        num(bounds.height())
+    );
+    let _ = writeln!(
+        out,
+        "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>",
+        num(bounds.min.x),
+        num(bounds.min.y),
+        num(bounds.width()),
+        num(bounds.height())
+    );
+    out.push_str(&body);
+    out.push_str("</svg>\n");
+    out
+}
+
+fn write_edge(
+    out: &mut String,
+    edge: &Edge,
+    geometry: &EdgeGeometry,
+    bounds: &mut Rect,
+    measure: &impl Fn(&str, FontId) -> Vec2,
+) {
+    let path = match &geometry.curve {
+        EdgeCurve::Quadratic([start, control, end]) => {
+            format!("M {} Q {} {}", point(*start), point(*control), point(*end))
+        }
+        EdgeCurve::Cubic([start, control1, control2, end]) => format!(
+            "M {} C {} {} {}",
+            point(*start),
+            point(*control1),
+            point(*control2),
+            point(*end)
+        ),
+        EdgeCurve::Polyline(points) => {
+            let points: Vec<_> = points.iter().map(|&p| point(p)).collect();
+            format!("M {}", points.join(" L "))
+        }
+    };
+    let stroke = paint("stroke", edge.color);
+    let width = edge.width;
+    match edge.line_style {
+        LineStyle::Solid => {
+            let _ = writeln!(
+                out,
+                "  <path d=\"{path}\" fill=\"none\" {stroke} stroke-width=\"{}\"/>",
+                num(width)
+            );
+        }
+        LineStyle::Dashed => {
+            let _ = writeln!(
+                out,
+                "  <path d=\"{path}\" fill=\"none\" {stroke} stroke-width=\"{}\" stroke-dasharray=\"{} {}\"/>",
+                num(width),
+                num(DASH_LENGTH_COEF * width),
+                num(DASH_GAP_COEF * width)
+            );
+        }
+        LineStyle::Dotted => {
+            // zero length dashes with round caps are dots
+            let _ = writeln!(
+                out,
+                "  <path d=\"{path}\" fill=\"none\" {stroke} stroke-width=\"{}\" stroke-linecap=\"round\" stroke-dasharray=\"0 {}\"/>",
+                num(width),
+                num(DOT_SPACING_COEF * width)
+            );
+        }
+        LineStyle::Double => {
+            let offset = DOUBLE_LINE_OFFSET_COEF * width;
+            for points in [
+                geometry.curve.offset_points(offset),
+                geometry.curve.offset_points(-offset),
+            ] {
+                let _ = writeln!(
+                    out,
+                    "  <polyline points=\"{}\" fill=\"none\" {stroke} stroke-width=\"{}\"/>",
+                    points.into_iter().map(point).collect::<Vec<_>>().join(" "),
+                    num(width / 2.0)
+                );
+            }
+        }
+    }
+
+    for point in geometry.curve.points() {
+        bounds.extend_with(point);
+    }
+
+    if !edge.label.is_empty() {
+        let center = geometry.label_center;
+        let font_id = FontId::new(edge.label_size, EDGE_LABEL_FAMILY);
+        let galley_size = measure(&edge.label, font_id);
+        let padding = vec2(edge.padding_x, edge.padding_y);
+        let angle = geometry.label_angle;
+
+        let background = label_background(center, galley_size, padding, angle);
+        let _ = writeln!(
+            out,
+            "  <polygon points=\"{}\" fill=\"#ffffff\"/>",
+            background.map(point).join(" ")
+        );
+
+        // text position is its left top corner, svg text is placed by its center
+        let text_position = label_text_position(center, galley_size, padding, angle);
+        let text_center = text_position + Rot2::from_angle(angle) * (galley_size / 2.0);
+        write_text(
+            out,
+            &edge.label,
+            text_center,
+            edge.label_size,
+            "sans-serif",
+            angle,
+        );
+
+        for corner in background {
+            bounds.extend_with(corner);
+        }
+    }
+
+    let (source_head, target_head) = edge.arrow_heads();
+    for (head, arrow) in [
+        (source_head, geometry.source_arrow),
+        (target_head, geometry.target_arrow),
+    ] {
+        let Some((from, to)) = arrow else {
+            continue;
+        };
+
+        match arrow_head_geometry(head, from, to, width) {
+            Some(ArrowHeadGeometry::Filled(points)) => {
+                let _ = writeln!(
+                    out,
+                    "  <polygon points=\"{}\" {} {stroke} stroke-width=\"{}\" stroke-linejoin=\"round\"/>",
+                    points.into_iter().map(point).collect::<Vec<_>>().join(" "),
+                    paint("fill", edge.color),
+                    num(width)
+                );
+            }
+            Some(ArrowHeadGeometry::Open(points)) => {
+                let _ = writeln!(
+                    out,
+                    "  <polyline points=\"{}\" fill=\"none\" {stroke} stroke-width=\"{}\" stroke-linejoin=\"round\"/>",
+                    points.map(point).join(" "),
+                    num(width)
+                );
+            }
+            Some(ArrowHeadGeometry::Circle { center, radius }) => {
+                let _ = writeln!(
+                    out,
+                    "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#ffffff\" {stroke} stroke-width=\"{}\"/>",
+                    num(center.x),
+                    num(center.y),
+                    num(radius),
+                    num(width)
+                );
+            }
+            None => {}
+        }
+    }
+}
+
+fn write_node(out: &mut String, node: &Node) {
+    let geometry = node.geometry();
+    let fill = paint("fill", node.color);
+
+    match node.shape {
+        NodeShape::Circle => {
+            let _ = writeln!(
+                out,
+                "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" {fill} stroke=\"#000000\" stroke-width=\"2\"/>",
+                num(node.position.x),
+                num(node.position.y),
+                num(node.size),
+            );
+        }
+        NodeShape::Ellipse => {
+            let _ = writeln!(
+                out,
+                "  <ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" {fill} stroke=\"#000000\" stroke-width=\"2\"/>",
+                num(node.position.x),
+                num(node.position.y),
+                num(geometry.half_size.x),
+                num(geometry.half_size.y),
+            );
+        }
+        NodeShape::Square | NodeShape::RoundedRect => {
+            // canvas draws stroke inside of rectangle
+            let rect = geometry.bounding_rect().shrink(1.0);
+            let _ = writeln!(
+                out,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" {fill} stroke=\"#000000\" stroke-width=\"2\"/>",
+                num(rect.min.x),
+                num(rect.min.y),
+                num(rect.width()),
+                num(rect.height()),
+                num(geometry.corner_radius()),
+            );
+        }
+        NodeShape::Diamond | NodeShape::Triangle | NodeShape::Hexagon => {
+            let points = geometry.polygon().unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "  <polygon points=\"{}\" {fill} stroke=\"#000000\" stroke-width=\"2\" stroke-linejoin=\"round\"/>",
+                points.into_iter().map(point).collect::<Vec<_>>().join(" "),
+            );
+        }
+    }
+
+    let label_size = if node.label_size_matches_node_size {
+        node.size
+    } else {
+        node.label_size
+    };
+    write_text(
+        out,
+        &node.label,
+        node.position,
+        label_size,
+        "monospace",
+        0.0,
+    );
+}
+
+fn write_text(out: &mut String, text: &str, center: Pos2, size: f32, family: &str, angle: f32) {
+    let transform = if angle != 0.0 {
+        format!(
+            " transform=\"rotate({} {} {})\"",
+            num(angle.to_degrees()),
+            num(center.x),
+            num(center.y)
+        )
+    } else {
+        String::new()
+    };
+
+    let _ = writeln!(
+        out,
+        "  <text x=\"{}\" y=\"{}\" font-family=\"{family}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"#000000\"{transform}>{}</text>",
+        num(center.x),
+        num(center.y),
+        num(size),
+        escape(text)
+    );
+}
+
+/// Color attribute with opacity, e.g. `fill="#ff0000" fill-opacity="0.5"`.
+fn paint(attribute: &str, color: Rgba) -> String {
+    let [r, g, b, a] = Color32::from(color).to_srgba_unmultiplied();
+    let hex = format!("#{r:02x}{g:02x}{b:02x}");
+
+    if a == u8::MAX {
+        format!("{attribute}=\"{hex}\"")
+    } else {
+        format!(
+            "{attribute}=\"{hex}\" {attribute}-opacity=\"{}\"",
+            num(a as f32 / 255.0)
+        )
+    }
+}
+
+fn point(p: Pos2) -> String {
+    format!("{},{}", num(p.x), num(p.y))
+}
+
+fn num(value: f32) -> String {
+    let value = (value * 100.0).round() / 100.0;
+    format!("{value}")
+}
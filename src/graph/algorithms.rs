@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::error::GraphEditorError;
+
+use super::{
+    edge::EdgeId,
+    trace::{Step, Trace},
+    Graph, NodeId,
+};
+
+/// Outgoing edges of every node: (edge, other node).
+/// Unoriented edges go both ways, nodes and edges are in creation order.
+type Adjacency = BTreeMap<NodeId, Vec<(EdgeId, NodeId)>>;
+
+/// Nodes in order of visiting with edges used to reach them.
+#[derive(Clone, Debug, Default)]
+pub struct Traversal {
+    pub nodes: Vec<NodeId>,
+    /// Depth of every visited node (number of edges from start).
+    pub depths: Vec<usize>,
+    pub tree_edges: Vec<EdgeId>,
+}
+
+/// Closed walk found by cycle detection.
+#[derive(Clone, Debug, Default)]
+pub struct Cycle {
+    pub nodes: Vec<NodeId>,
+    pub edges: Vec<EdgeId>,
+}
+
+impl Graph {
+    fn adjacency(&self, ignore_orientation: bool, reversed: bool) -> Adjacency {
+        let mut adjacency: Adjacency = self.nodes.keys().map(|&id| (id, Vec::new())).collect();
+
+        for (&id, edge) in &self.edges {
+            let (start, end) = if reversed {
+                (edge.end_id, edge.start_id)
+            } else {
+                (edge.start_id, edge.end_id)
+            };
+
+            if let Some(outgoing) = adjacency.get_mut(&start) {
+                outgoing.push((id, end));
+            }
+            if (ignore_orientation || !edge.oriented) && !edge.is_loop() {
+                if let Some(outgoing) = adjacency.get_mut(&end) {
+                    outgoing.push((id, start));
+                }
+            }
+        }
+
+        adjacency
+    }
+
+    /// Breadth-first search along edge orientation, steps are recorded to trace.
+    pub fn bfs(&self, start: NodeId, trace: &mut Trace) -> Traversal {
+        let adjacency = self.adjacency(false, false);
+        let mut traversal = Traversal::default();
+        if !adjacency.contains_key(&start) {
+            return traversal;
+        }
+
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        traversal.nodes.push(start);
+        traversal.depths.push(0);
+        trace.record(Step::Push(start));
+
+        while let Some((node, depth)) = queue.pop_front() {
+            trace.record(Step::Pop(node));
+            trace.record(Step::Visit(node));
+
+            for &(edge_id, next) in &adjacency[&node] {
+                if !visited.insert(next) {
+                    trace.record(Step::Examine(edge_id));
+                    continue;
+                }
+
+                traversal.nodes.push(next);
+                traversal.depths.push(depth + 1);
+                traversal.tree_edges.push(edge_id);
+                queue.push_back((next, depth + 1));
+                trace.record(Step::Relax {
+                    edge: edge_id,
+                    node: next,
+                    distance: (depth + 1) as f32,
+                });
+                trace.record(Step::Push(next));
+            }
+        }
+
+        traversal
+    }
+
+    /// Depth-first search along edge orientation, nodes are in preorder.
+    /// Steps are recorded to trace.
+    pub fn dfs(&self, start: NodeId, trace: &mut Trace) -> Traversal {
+        let adjacency = self.adjacency(false, false);
+        let mut traversal = Traversal::default();
+        if !adjacency.contains_key(&start) {
+            return traversal;
+        }
+
+        let mut visited = HashSet::from([start]);
+        // (node, depth, index of next outgoing edge)
+        let mut stack = vec![(start, 0, 0)];
+        traversal.nodes.push(start);
+        traversal.depths.push(0);
+        trace.record(Step::Push(start));
+        trace.record(Step::Visit(start));
+
+        while let Some((node, depth, next_index)) = stack.last_mut() {
+            let Some(&(edge_id, next)) = adjacency[node].get(*next_index) else {
+                trace.record(Step::Pop(*node));
+                stack.pop();
+                continue;
+            };
+            *next_index += 1;
+
+            if !visited.insert(next) {
+                trace.record(Step::Examine(edge_id));
+                continue;
+            }
+
+            let depth = *depth + 1;
+            traversal.nodes.push(next);
+            traversal.depths.push(depth);
+            traversal.tree_edges.push(edge_id);
+            stack.push((next, depth, 0));
+            trace.record(Step::Relax {
+                edge: edge_id,
+                node: next,
+                distance: depth as f32,
+            });
+            trace.record(Step::Push(next));
+            trace.record(Step::Visit(next));
+        }
+
+        traversal
+    }
+
+    /// Components connected by edges of any orientation.
+    pub fn weak_components(&self) -> Vec<Vec<NodeId>> {
+        let adjacency = self.adjacency(true, false);
+        let mut assigned = HashSet::with_capacity(adjacency.len());
+        let mut components: Vec<Vec<NodeId>> = Vec::new();
+
+        for &start in adjacency.keys() {
+            if !assigned.insert(start) {
+                continue;
+            }
+
+            let mut component = vec![start];
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for &(_, next) in &adjacency[&node] {
+                    if assigned.insert(next) {
+                        component.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+
+            component.sort_by_key(|id| id.0);
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Components where every node is reachable from every other one (Kosaraju).
+    pub fn strong_components(&self) -> Vec<Vec<NodeId>> {
+        let adjacency = self.adjacency(false, false);
+
+        // nodes in order of finishing depth-first search
+        let mut finished = Vec::with_capacity(adjacency.len());
+        let mut visited = HashSet::with_capacity(adjacency.len());
+        for &start in adjacency.keys() {
+            if !visited.insert(start) {
+                continue;
+            }
+
+            let mut stack = vec![(start, 0)];
+            while let Some((node, next_index)) = stack.last_mut() {
+                match adjacency[node].get(*next_index) {
+                    Some(&(_, next)) => {
+                        *next_index += 1;
+                        if visited.insert(next) {
+                            stack.push((next, 0));
+                        }
+                    }
+                    None => {
+                        finished.push(*node);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        // search in reversed graph collects exactly one component
+        let reversed = self.adjacency(false, true);
+        let mut assigned = HashSet::with_capacity(finished.len());
+        let mut components: Vec<Vec<NodeId>> = Vec::new();
+        for &start in finished.iter().rev() {
+            if !assigned.insert(start) {
+                continue;
+            }
+
+            let mut component = vec![start];
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for &(_, next) in &reversed[&node] {
+                    if assigned.insert(next) {
+                        component.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+
+            component.sort_by_key(|id| id.0);
+            components.push(component);
+        }
+
+        components.sort_by_key(|component| component[0].0);
+        components
+    }
+
+    /// Some cycle of graph, going back along the same unoriented edge doesn't count.
+    pub fn find_cycle(&self) -> Option<Cycle> {
+        let adjacency = self.adjacency(false, false);
+
+        // nodes of current search path are on stack, finished nodes are never entered again
+        let mut finished: HashSet<NodeId> = HashSet::with_capacity(adjacency.len());
+        let mut on_stack: HashSet<NodeId> = HashSet::new();
+
+        for &start in adjacency.keys() {
+            if finished.contains(&start) {
+                continue;
+            }
+            on_stack.insert(start);
+
+            // (node, edge used to enter it, index of next outgoing edge)
+            let mut stack: Vec<(NodeId, Option<EdgeId>, usize)> = vec![(start, None, 0)];
+            while let Some((node, entered_by, next_index)) = stack.last_mut() {
+                let Some(&(edge_id, next)) = adjacency[node].get(*next_index) else {
+                    finished.insert(*node);
+                    on_stack.remove(node);
+                    stack.pop();
+                    continue;
+                };
+                *next_index += 1;
+
+                if Some(edge_id) == *entered_by || finished.contains(&next) {
+                    continue;
+                }
+
+                if on_stack.contains(&next) {
+                    let index = stack.iter().position(|(id, _, _)| *id == next).unwrap();
+                    let mut cycle = Cycle::default();
+                    for (id, entered_by, _) in &stack[index..] {
+                        cycle.nodes.push(*id);
+                        cycle.edges.extend(entered_by.filter(|_| *id != next));
+                    }
+                    cycle.edges.push(edge_id);
+
+                    return Some(cycle);
+                }
+
+                on_stack.insert(next);
+                stack.push((next, Some(edge_id), 0));
+            }
+        }
+
+        None
+    }
+
+    /// Nodes ordered so that every edge goes forward, with layer of every node
+    /// (length of longest path ending in it).
+    pub fn topological_sort(&self) -> Result<Vec<(NodeId, usize)>, GraphEditorError> {
+        if self
+            .edges
+            .values()
+            .any(|edge| !edge.oriented && !edge.is_loop())
+        {
+            return Err(GraphEditorError::UnorientedEdges);
+        }
+
+        let adjacency = self.adjacency(false, false);
+        let mut in_degrees: BTreeMap<NodeId, usize> = adjacency.keys().map(|&id| (id, 0)).collect();
+        for outgoing in adjacency.values() {
+            for (_, next) in outgoing {
+                *in_degrees.get_mut(next).unwrap() += 1;
+            }
+        }
+
+        let mut layers: BTreeMap<NodeId, usize> = BTreeMap::new();
+        let mut queue: VecDeque<_> = in_degrees
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&id, _)| id)
+            .collect();
+        let mut order = Vec::with_capacity(adjacency.len());
+
+        while let Some(node) = queue.pop_front() {
+            let layer = layers.get(&node).copied().unwrap_or(0);
+            order.push((node, layer));
+
+            for (_, next) in &adjacency[&node] {
+                let next_layer = layers.entry(*next).or_insert(0);
+                *next_layer = (*next_layer).max(layer + 1);
+
+                let degree = in_degrees.get_mut(next).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        if order.len() < adjacency.len() {
+            let cycle = self.find_cycle().unwrap_or_default();
+            let labels = cycle
+                .nodes
+                .iter()
+                .map(|id| self.nodes[id].label.clone())
+                .collect();
+
+            return Err(GraphEditorError::NotAcyclic(labels));
+        }
+
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_utils::graph;
+
+    #[test]
+    fn topological_sort_orders_by_layers() {
+        let (graph, ids, _) = graph(4, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+
+        let order = graph.topological_sort().unwrap();
+
+        assert_eq!(order, [(ids[0], 0), (ids[1], 1), (ids[2], 1), (ids[3], 2)]);
+    }
+
+    #[test]
+    fn topological_sort_reports_cycle() {
+        let (graph, _, _) = graph(4, &[(0, 1), (1, 2), (2, 3), (3, 1)]);
+
+        let Err(GraphEditorError::NotAcyclic(labels)) = graph.topological_sort() else {
+            panic!("cyclic graph was sorted");
+        };
+
+        assert_eq!(labels, ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn strong_components_of_known_graph() {
+        // cycles a-b-c and d-e joined by single edge, f stands alone
+        let (graph, ids, _) = graph(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3)]);
+
+        let components = graph.strong_components();
+
+        assert_eq!(
+            components,
+            [
+                vec![ids[0], ids[1], ids[2]],
+                vec![ids[3], ids[4]],
+                vec![ids[5]]
+            ]
+        );
+    }
+
+    #[test]
+    fn weak_components_ignore_orientation() {
+        let (graph, ids, _) = graph(4, &[(1, 0), (2, 1)]);
+
+        let components = graph.weak_components();
+
+        assert_eq!(components, [vec![ids[0], ids[1], ids[2]], vec![ids[3]]]);
+    }
+}
@@ -0,0 +1,193 @@
+//! Custom typed attributes of nodes and edges, e.g. capacity, cost or owner.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeKind {
+    String,
+    Number,
+    Bool,
+}
+
+impl AttributeKind {
+    pub const ALL: [AttributeKind; 3] = [
+        AttributeKind::String,
+        AttributeKind::Number,
+        AttributeKind::Bool,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AttributeKind::String => "String",
+            AttributeKind::Number => "Number",
+            AttributeKind::Bool => "Bool",
+        }
+    }
+}
+
+/// Value of attribute, written to JSON as plain string, number or bool.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+impl AttributeValue {
+    pub fn kind(&self) -> AttributeKind {
+        match self {
+            AttributeValue::String(_) => AttributeKind::String,
+            AttributeValue::Number(_) => AttributeKind::Number,
+            AttributeValue::Bool(_) => AttributeKind::Bool,
+        }
+    }
+
+    /// Empty value of given kind.
+    pub fn default_of(kind: AttributeKind) -> Self {
+        match kind {
+            AttributeKind::String => AttributeValue::String(String::new()),
+            AttributeKind::Number => AttributeValue::Number(0.0),
+            AttributeKind::Bool => AttributeValue::Bool(false),
+        }
+    }
+
+    /// Value of text as given kind, None if text isn't value of this kind.
+    pub fn parse(kind: AttributeKind, text: &str) -> Option<Self> {
+        match kind {
+            AttributeKind::String => Some(AttributeValue::String(text.to_owned())),
+            AttributeKind::Number => text
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(AttributeValue::Number),
+            AttributeKind::Bool => match text.trim().to_lowercase().as_str() {
+                "true" => Some(AttributeValue::Bool(true)),
+                "false" => Some(AttributeValue::Bool(false)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Value of untyped text: number or bool if it looks like one, otherwise string.
+    pub fn infer(text: &str) -> Self {
+        Self::parse(AttributeKind::Number, text)
+            .or_else(|| Self::parse(AttributeKind::Bool, text))
+            .unwrap_or_else(|| AttributeValue::String(text.to_owned()))
+    }
+
+    /// Same value as another kind, or empty value if it can't be converted.
+    pub fn convert(&self, kind: AttributeKind) -> Self {
+        let converted = match (self, kind) {
+            (AttributeValue::Bool(value), AttributeKind::Number) => {
+                Some(AttributeValue::Number(if *value { 1.0 } else { 0.0 }))
+            }
+            (AttributeValue::Number(value), AttributeKind::Bool) => {
+                Some(AttributeValue::Bool(*value != 0.0))
+            }
+            _ => Self::parse(kind, &self.to_string()),
+        };
+
+        converted.unwrap_or_else(|| Self::default_of(kind))
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeValue::String(value) => write!(f, "{value}"),
+            AttributeValue::Number(value) => write!(f, "{value}"),
+            AttributeValue::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Attribute {
+    pub name: String,
+    pub value: AttributeValue,
+}
+
+/// Attributes in order given by user, names are unique.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Attributes(Vec<Attribute>);
+
+impl Attributes {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Attribute> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Attribute> {
+        self.0.iter_mut()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AttributeValue> {
+        self.0
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| &attribute.value)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut AttributeValue> {
+        self.0
+            .iter_mut()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| &mut attribute.value)
+    }
+
+    /// Replace value of attribute or add new attribute to the end.
+    pub fn set(&mut self, name: &str, value: AttributeValue) {
+        match self.get_mut(name) {
+            Some(old) => *old = value,
+            None => self.0.push(Attribute {
+                name: name.to_owned(),
+                value,
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<AttributeValue> {
+        let index = self.0.iter().position(|attribute| attribute.name == name)?;
+        Some(self.0.remove(index).value)
+    }
+
+    /// Swap attribute with previous one.
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 && index < self.0.len() {
+            self.0.swap(index - 1, index);
+        }
+    }
+}
+
+/// Names of attributes in order of first appearance with kind of their values,
+/// String if values of one name have different kinds.
+pub fn attribute_columns<'a>(
+    all: impl IntoIterator<Item = &'a Attributes>,
+) -> Vec<(String, AttributeKind)> {
+    let mut columns: Vec<(String, AttributeKind)> = Vec::new();
+
+    for attribute in all.into_iter().flat_map(Attributes::iter) {
+        let kind = attribute.value.kind();
+        match columns.iter_mut().find(|(name, _)| *name == attribute.name) {
+            Some((_, column_kind)) if *column_kind != kind => {
+                *column_kind = AttributeKind::String;
+            }
+            Some(_) => {}
+            None => columns.push((attribute.name.clone(), kind)),
+        }
+    }
+
+    columns
+}
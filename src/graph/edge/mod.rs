@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use eframe::egui::{Color32, Pos2, Rgba};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    consts::{
+        DEFAULT_EDGE_WEIGHT, MAX_EDGE_LABEL_PADDING, MIN_EDGE_LABEL_PADDING, MIN_EDGE_LABEL_SIZE,
+        MIN_EDGE_WIDTH, MIN_LOOP_EDGE_ANGLE,
+    },
+    utils::geometry::edge::segment_distance,
+};
+
+use super::{attribute::Attributes, NodeId};
+
+pub mod style;
+use style::{ArrowHead, EdgeRouting, LineStyle};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EdgeId(pub usize);
+
+/// One of two ends of edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeEnd {
+    Start,
+    End,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Edge {
+    pub start_id: NodeId,
+    pub end_id: NodeId,
+    pub oriented: bool,
+    pub color: Rgba,
+    pub label: String,
+    pub label_size: f32,
+    pub padding_x: f32,
+    pub padding_y: f32,
+    pub width: f32,
+    #[serde(default)]
+    pub line_style: LineStyle,
+    /// Arrowhead at start node.
+    #[serde(default)]
+    pub source_arrow: ArrowHead,
+    /// Arrowhead at end node.
+    #[serde(default = "default_target_arrow")]
+    pub target_arrow: ArrowHead,
+    #[serde(default)]
+    pub routing: EdgeRouting,
+    /// Points which polyline edge goes through, from start to end.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bend_points: Vec<Pos2>,
+    pub loop_rotation_angle: f32,
+    /// Cost of going along edge, used by shortest path search.
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    /// Custom data given by user.
+    #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes,
+    /// Data of keys unknown to editor, kept from imported files.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub foreign_data: BTreeMap<String, String>,
+}
+
+impl Edge {
+    pub fn new(start_id: NodeId, end_id: NodeId) -> Self {
+        Self {
+            start_id,
+            end_id,
+            oriented: true,
+            color: Rgba::from(Color32::BLACK),
+            label: String::new(),
+            label_size: MIN_EDGE_LABEL_SIZE,
+            padding_x: (MIN_EDGE_LABEL_PADDING + MAX_EDGE_LABEL_PADDING) / 2.0,
+            padding_y: (MIN_EDGE_LABEL_PADDING + MAX_EDGE_LABEL_PADDING) / 2.0,
+            width: MIN_EDGE_WIDTH,
+            line_style: LineStyle::Solid,
+            source_arrow: ArrowHead::None,
+            target_arrow: default_target_arrow(),
+            routing: EdgeRouting::Curved,
+            bend_points: Vec::new(),
+            loop_rotation_angle: MIN_LOOP_EDGE_ANGLE,
+            weight: DEFAULT_EDGE_WEIGHT,
+            attributes: Attributes::default(),
+            foreign_data: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_loop(&self) -> bool {
+        self.start_id == self.end_id
+    }
+
+    /// Node at given end of edge.
+    pub fn node_id(&self, end: EdgeEnd) -> NodeId {
+        match end {
+            EdgeEnd::Start => self.start_id,
+            EdgeEnd::End => self.end_id,
+        }
+    }
+
+    /// Move given end of edge to another node, edge may become a loop or stop being one.
+    pub fn reconnect(&mut self, end: EdgeEnd, node_id: NodeId) {
+        match end {
+            EdgeEnd::Start => self.start_id = node_id,
+            EdgeEnd::End => self.end_id = node_id,
+        }
+    }
+
+    /// Polyline edge between two different nodes, only it goes through bend points.
+    pub fn is_polyline(&self) -> bool {
+        self.routing == EdgeRouting::Polyline && !self.is_loop()
+    }
+
+    /// Arrowheads drawn at (start, end), only oriented edges have them.
+    pub fn arrow_heads(&self) -> (ArrowHead, ArrowHead) {
+        if self.oriented {
+            (self.source_arrow, self.target_arrow)
+        } else {
+            (ArrowHead::None, ArrowHead::None)
+        }
+    }
+
+    /// Insert bend point into segment closest to it,
+    /// segments go from `start` through bend points to `end`.
+    pub fn insert_bend_point(&mut self, start: Pos2, end: Pos2, point: Pos2) {
+        let mut points = vec![start];
+        points.extend(&self.bend_points);
+        points.push(end);
+
+        let segment = points
+            .windows(2)
+            .map(|segment| segment_distance(segment[0], segment[1], point))
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(segment, _)| segment);
+
+        self.bend_points.insert(segment, point);
+    }
+
+    /// Number written in label, if label is just a number.
+    pub fn label_weight(&self) -> Option<f32> {
+        self.label
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|weight| weight.is_finite())
+    }
+}
+
+fn default_weight() -> f32 {
+    DEFAULT_EDGE_WEIGHT
+}
+
+fn default_target_arrow() -> ArrowHead {
+    ArrowHead::Filled
+}
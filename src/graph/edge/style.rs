@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    /// Two parallel lines.
+    Double,
+}
+
+impl LineStyle {
+    pub const ALL: [LineStyle; 4] = [
+        LineStyle::Solid,
+        LineStyle::Dashed,
+        LineStyle::Dotted,
+        LineStyle::Double,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LineStyle::Solid => "Solid",
+            LineStyle::Dashed => "Dashed",
+            LineStyle::Dotted => "Dotted",
+            LineStyle::Double => "Double",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub enum ArrowHead {
+    #[default]
+    None,
+    Filled,
+    /// Two lines without fill.
+    Open,
+    Diamond,
+    /// Hollow circle.
+    Circle,
+}
+
+impl ArrowHead {
+    pub const ALL: [ArrowHead; 5] = [
+        ArrowHead::None,
+        ArrowHead::Filled,
+        ArrowHead::Open,
+        ArrowHead::Diamond,
+        ArrowHead::Circle,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ArrowHead::None => "None",
+            ArrowHead::Filled => "Filled",
+            ArrowHead::Open => "Open",
+            ArrowHead::Diamond => "Diamond",
+            ArrowHead::Circle => "Circle",
+        }
+    }
+}
+
+/// How edge between two different nodes is drawn, loops are always curved.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub enum EdgeRouting {
+    /// Bézier curve, parallel edges are bent apart.
+    #[default]
+    Curved,
+    Straight,
+    /// Straight segments through bend points.
+    Polyline,
+    /// Horizontal and vertical segments around other nodes.
+    Orthogonal,
+}
+
+impl EdgeRouting {
+    pub const ALL: [EdgeRouting; 4] = [
+        EdgeRouting::Curved,
+        EdgeRouting::Straight,
+        EdgeRouting::Polyline,
+        EdgeRouting::Orthogonal,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EdgeRouting::Curved => "Curved",
+            EdgeRouting::Straight => "Straight",
+            EdgeRouting::Polyline => "Polyline",
+            EdgeRouting::Orthogonal => "Orthogonal",
+        }
+    }
+}
@@ -1,38 +1,72 @@
+pub mod algorithms;
+pub mod attribute;
 pub mod edge;
 pub mod node;
+pub mod path;
+pub mod styling;
+pub mod subgraph;
+#[cfg(test)]
+pub mod test_utils;
+pub mod trace;
+pub mod validation;
 
 use std::collections::BTreeMap;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
-use std::io::Write;
-use std::path::PathBuf;
 use std::sync::LazyLock;
 use std::sync::Mutex;
 
+use attribute::{attribute_columns, AttributeKind};
+pub use edge::style::{ArrowHead, EdgeRouting, LineStyle};
 pub use edge::Edge;
 use edge::EdgeId;
-use eframe::egui::pos2;
+use eframe::egui::{pos2, Pos2};
 pub use node::shape::NodeShape;
 pub use node::Node;
 pub use node::NodeId;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::Deserialize;
 use serde::Serialize;
+use styling::StyleRule;
 
 use crate::consts::{
     DEFAULT_NODE_X_POSITION, DEFAULT_NODE_Y_POSITION, MAX_NODE_SIZE, MIN_NODE_SIZE,
 };
-use crate::error::GraphEditorError;
 
 static RNG: LazyLock<Mutex<StdRng>> = LazyLock::new(|| Mutex::new(StdRng::seed_from_u64(0)));
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ForeignKeyDomain {
+    Node,
+    Edge,
+    Graph,
+    /// Key of nodes, edges and graph at once.
+    All,
+}
+
+/// Declaration of data key unknown to editor, kept from imported files.
+/// Values are stored in `foreign_data` of nodes, edges and graph.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub domain: ForeignKeyDomain,
+    pub name: String,
+    /// GraphML `attr.type`, empty if key has no type.
+    pub value_type: String,
+    pub default: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Graph {
     nodes: HashMap<NodeId, Node>,
     edges: BTreeMap<EdgeId, Edge>,
     node_id_counter: usize,
     edge_id_counter: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    foreign_keys: Vec<ForeignKey>,
+    /// Values of foreign keys given for graph itself.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    foreign_data: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    style_rules: Vec<StyleRule>,
 }
 
 impl Graph {
@@ -60,17 +94,29 @@ impl Graph {
         self.edges.get_mut(id)
     }
 
-    pub fn add_node(&mut self) {
+    pub fn add_node(&mut self) -> NodeId {
+        let label = (self.node_id_counter + 1).to_string();
+        let new_node = Node::new(label, self.random_node_position());
+
+        self.insert_node(new_node)
+    }
+
+    /// Insert prepared node, return its id.
+    pub fn insert_node(&mut self, node: Node) -> NodeId {
         self.node_id_counter += 1;
-        let node_id = self.node_id_counter;
+        let node_id = NodeId(self.node_id_counter);
+
+        self.nodes.insert(node_id, node);
+
+        node_id
+    }
 
-        let position = pos2(
+    /// Position of new node: default position with random offset.
+    pub fn random_node_position(&self) -> Pos2 {
+        pos2(
             DEFAULT_NODE_X_POSITION + self.random_node_position_offset(),
             DEFAULT_NODE_Y_POSITION + self.random_node_position_offset(),
-        );
-        let new_node = Node::new(node_id.to_string(), position);
-
-        self.nodes.insert(NodeId(node_id), new_node);
+        )
     }
 
     fn random_node_position_offset(&self) -> f32 {
@@ -79,12 +125,17 @@ impl Graph {
     }
 
     pub fn add_edge(&mut self, start_id: NodeId, end_id: NodeId) {
+        self.insert_edge(Edge::new(start_id, end_id));
+    }
+
+    /// Insert prepared edge, return its id.
+    pub fn insert_edge(&mut self, edge: Edge) -> EdgeId {
         self.edge_id_counter += 1;
-        let edge_id = self.edge_id_counter;
+        let edge_id = EdgeId(self.edge_id_counter);
 
-        let new_edge = Edge::new(start_id, end_id);
+        self.edges.insert(edge_id, edge);
 
-        self.edges.insert(EdgeId(edge_id), new_edge);
+        edge_id
     }
 
     pub fn remove_node(&mut self, id: NodeId) {
@@ -96,39 +147,45 @@ impl Graph {
         self.edges.retain(|&edge_id, _| edge_id != id);
     }
 
-    pub fn save_to_file(&self, file_path: &PathBuf) -> Result<(), GraphEditorError> {
-        let graph_json = serde_json::to_string_pretty(&self);
+    pub fn foreign_keys(&self) -> &[ForeignKey] {
+        &self.foreign_keys
+    }
+
+    pub fn set_foreign_keys(&mut self, keys: Vec<ForeignKey>) {
+        self.foreign_keys = keys;
+    }
 
-        match graph_json {
-            Ok(value) => {
-                let mut file =
-                    File::create(file_path).map_err(|_| GraphEditorError::FailedSaveFile)?;
-                file.write_all(value.as_bytes())
-                    .map_err(|_| GraphEditorError::FailedSaveFile)?;
-            }
-            Err(_) => {
-                return Err(GraphEditorError::FailedSaveFile);
-            }
-        }
+    pub fn foreign_data(&self) -> &BTreeMap<String, String> {
+        &self.foreign_data
+    }
 
-        Ok(())
+    pub fn set_foreign_data(&mut self, data: BTreeMap<String, String>) {
+        self.foreign_data = data;
+    }
+
+    /// Styling rules in order of applying.
+    pub fn style_rules(&self) -> &[StyleRule] {
+        &self.style_rules
+    }
+
+    pub fn style_rules_mut(&mut self) -> &mut Vec<StyleRule> {
+        &mut self.style_rules
     }
 
     pub fn edge(&self, id: &EdgeId) -> Option<&Edge> {
         self.edges.get(id)
     }
-}
 
-impl TryFrom<&PathBuf> for Graph {
-    type Error = GraphEditorError;
+    /// Attribute names used by nodes with their kinds, in order of node creation.
+    pub fn node_attribute_columns(&self) -> Vec<(String, AttributeKind)> {
+        let mut nodes: Vec<_> = self.nodes.iter().collect();
+        nodes.sort_by_key(|(id, _)| id.0);
 
-    fn try_from(value: &PathBuf) -> Result<Self, Self::Error> {
-        let file: File = File::open(value).map_err(|_| GraphEditorError::FailedOpenFile)?;
-        let reader = BufReader::new(file);
+        attribute_columns(nodes.into_iter().map(|(_, node)| &node.attributes))
+    }
 
-        serde_json::from_reader(reader).map_err(|err| {
-            println!("{:?}", err);
-            GraphEditorError::FailedOpenFile
-        })
+    /// Attribute names used by edges with their kinds, in order of edge creation.
+    pub fn edge_attribute_columns(&self) -> Vec<(String, AttributeKind)> {
+        attribute_columns(self.edges.values().map(|edge| &edge.attributes))
     }
 }
@@ -1,4 +1,9 @@
-use eframe::egui::{Align2, Color32, FontFamily, FontId, Painter, Pos2, Rect, Rgba, Stroke, Vec2};
+use std::collections::BTreeMap;
+
+use eframe::{
+    egui::{Align2, Color32, FontFamily, FontId, Pos2, Rgba, Shape, Stroke, Vec2},
+    epaint::text::Fonts,
+};
 use serde::{Deserialize, Serialize};
 
 pub mod shape;
@@ -6,25 +11,39 @@ use crate::{
     consts::{
         DEFAULT_NODE_X_POSITION, DEFAULT_NODE_Y_POSITION, MIN_NODE_LABEL_SIZE, MIN_NODE_SIZE,
     },
-    graph::node::shape::NodeShape,
-    utils::geometry::{
-        circle::rotate_cirlce_border_point,
-        square::{intersect_rect_edge, rotate_square_border_point},
-    },
+    graph::{attribute::Attributes, node::shape::NodeShape},
+    utils::geometry::shape::ShapeGeometry,
 };
 
-#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct NodeId(pub usize);
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Node {
     pub position: Pos2,
+    /// Half of node height, also half of width for circle and square.
     pub size: f32,
     pub shape: NodeShape,
+    /// Width to height ratio of shapes which may be stretched.
+    #[serde(default = "default_aspect_ratio")]
+    pub aspect_ratio: f32,
     pub color: Rgba,
     pub label: String,
     pub label_size_matches_node_size: bool,
     pub label_size: f32,
+    /// Pinned node is not moved by automatic layout.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Custom data given by user.
+    #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes,
+    /// Data of keys unknown to editor, kept from imported files.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub foreign_data: BTreeMap<String, String>,
+}
+
+fn default_aspect_ratio() -> f32 {
+    1.0
 }
 
 impl Default for Node {
@@ -33,10 +52,14 @@ impl Default for Node {
             position: Pos2::new(DEFAULT_NODE_X_POSITION, DEFAULT_NODE_Y_POSITION),
             size: MIN_NODE_SIZE,
             shape: NodeShape::Circle,
+            aspect_ratio: default_aspect_ratio(),
             color: Rgba::from(Color32::RED),
             label: "1".to_string(),
             label_size_matches_node_size: true,
             label_size: MIN_NODE_LABEL_SIZE,
+            pinned: false,
+            attributes: Attributes::default(),
+            foreign_data: BTreeMap::new(),
         }
     }
 }
@@ -50,67 +73,54 @@ impl Node {
         }
     }
 
-    fn draw_shape(&self, painter: &Painter) {
-        match self.shape {
-            NodeShape::Circle => painter.circle(
-                self.position,
-                self.size,
-                self.color,
-                Stroke::new(2.0, Color32::BLACK),
-            ),
-            NodeShape::Square => painter.rect(
-                Rect::from_center_size(self.position, Vec2::splat(2.0 * self.size)),
-                2.0,
-                self.color,
-                Stroke::new(2.0, Color32::BLACK),
-                eframe::egui::StrokeKind::Inside,
-            ),
-        };
+    /// Half of width and height of node.
+    pub fn half_size(&self) -> Vec2 {
+        if self.shape.has_aspect_ratio() {
+            Vec2::new(self.size * self.aspect_ratio, self.size)
+        } else {
+            Vec2::splat(self.size)
+        }
     }
 
-    pub fn draw(&self, painter: &Painter) {
-        self.draw_shape(painter);
+    pub fn geometry(&self) -> ShapeGeometry {
+        ShapeGeometry::new(self.shape, self.position, self.half_size())
+    }
 
+    /// Shapes of node and its label.
+    pub fn shapes(&self, fonts: &Fonts) -> [Shape; 2] {
         let label_size = if self.label_size_matches_node_size {
             self.size
         } else {
             self.label_size
         };
 
-        painter.text(
+        let label = Shape::text(
+            fonts,
             self.position,
             Align2::CENTER_CENTER,
             &self.label,
             FontId::new(label_size, FontFamily::Monospace),
             Color32::BLACK,
         );
+
+        let shape = self
+            .geometry()
+            .shape(self.color.into(), Stroke::new(2.0, Color32::BLACK));
+
+        [shape, label]
     }
 
     pub fn is_clicked(&self, pointer_pos: Pos2) -> bool {
-        match self.shape {
-            NodeShape::Circle => self.position.distance(pointer_pos) < self.size,
-            NodeShape::Square => {
-                let rect = Rect::from_center_size(self.position, Vec2::splat(2.0 * self.size));
-                rect.contains(pointer_pos)
-            }
-        }
+        self.geometry().contains(pointer_pos)
     }
 
     pub fn border_point_in_direction(&self, direction: Vec2) -> Pos2 {
-        match self.shape {
-            NodeShape::Circle => self.position + direction.normalized() * self.size,
-            NodeShape::Square => intersect_rect_edge(self.position, self.size, direction),
-        }
+        self.geometry().border_point(direction)
     }
 
     /// Rotate a point on the border of a node around its center by a given angle (alpha, in radians).
     /// The resulting point is snapped back to the node's perimeter.
     pub fn rotate_border_point(&self, border_pos: Pos2, alpha: f32) -> Pos2 {
-        match self.shape {
-            NodeShape::Circle => rotate_cirlce_border_point(border_pos, self.position, alpha),
-            NodeShape::Square => {
-                rotate_square_border_point(border_pos, self.position, alpha, self.size)
-            }
-        }
+        self.geometry().rotate_border_point(border_pos, alpha)
     }
 }
@@ -1,7 +1,42 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub enum NodeShape {
     Circle,
-    Square
-}
\ No newline at end of file
+    Square,
+    Ellipse,
+    Diamond,
+    Triangle,
+    Hexagon,
+    RoundedRect,
+}
+
+impl NodeShape {
+    pub const ALL: [NodeShape; 7] = [
+        NodeShape::Circle,
+        NodeShape::Square,
+        NodeShape::Ellipse,
+        NodeShape::Diamond,
+        NodeShape::Triangle,
+        NodeShape::Hexagon,
+        NodeShape::RoundedRect,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NodeShape::Circle => "Circle",
+            NodeShape::Square => "Square",
+            NodeShape::Ellipse => "Ellipse",
+            NodeShape::Diamond => "Diamond",
+            NodeShape::Triangle => "Triangle",
+            NodeShape::Hexagon => "Hexagon",
+            NodeShape::RoundedRect => "Rounded rectangle",
+        }
+    }
+
+    /// Circle always has equal width and height,
+    /// square with width other than height is drawn as rectangle.
+    pub fn has_aspect_ratio(self) -> bool {
+        self != NodeShape::Circle
+    }
+}
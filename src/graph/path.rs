@@ -0,0 +1,358 @@
+use crate::error::GraphEditorError;
+
+use super::{
+    edge::EdgeId,
+    trace::{Step, Trace},
+    Edge, Graph, NodeId,
+};
+
+/// Result of shortest path search.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphPath {
+    /// Nodes from start to end, both included.
+    pub nodes: Vec<NodeId>,
+    /// Edges between consecutive `nodes`.
+    pub edges: Vec<EdgeId>,
+    pub cost: f32,
+}
+
+/// Where cost of edge is taken from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EdgeCost {
+    #[default]
+    Weight,
+    /// Number written in label, weight for edges without numeric label.
+    Label,
+}
+
+impl EdgeCost {
+    pub fn of(self, edge: &Edge) -> f32 {
+        match self {
+            EdgeCost::Weight => edge.weight,
+            EdgeCost::Label => edge.label_weight().unwrap_or(edge.weight),
+        }
+    }
+}
+
+/// Edge walked in one direction, nodes are indices in sorted node ids.
+struct Arc {
+    from: usize,
+    to: usize,
+    edge_id: EdgeId,
+    cost: f32,
+}
+
+/// Last arc of cheapest known path to node: (previous node, edge).
+type Predecessors = Vec<Option<(usize, EdgeId)>>;
+
+impl Graph {
+    /// Cheapest path from start to end node. Unoriented edges are walked both ways.
+    /// Dijkstra is used when there are no negative costs, Bellman-Ford otherwise.
+    /// Steps of search are recorded to trace.
+    pub fn shortest_path(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        edge_cost: EdgeCost,
+        trace: &mut Trace,
+    ) -> Result<GraphPath, GraphEditorError> {
+        let mut ids: Vec<_> = self.nodes.keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+
+        let index = |id: NodeId| ids.binary_search_by_key(&id.0, |id| id.0).ok();
+        let (Some(start), Some(end)) = (index(start), index(end)) else {
+            return Err(GraphEditorError::NoPath);
+        };
+
+        let mut arcs = Vec::new();
+        for (&edge_id, edge) in &self.edges {
+            let (Some(from), Some(to)) = (index(edge.start_id), index(edge.end_id)) else {
+                continue;
+            };
+            let cost = edge_cost.of(edge);
+
+            arcs.push(Arc {
+                from,
+                to,
+                edge_id,
+                cost,
+            });
+            if !edge.oriented && !edge.is_loop() {
+                arcs.push(Arc {
+                    from: to,
+                    to: from,
+                    edge_id,
+                    cost,
+                });
+            }
+        }
+
+        let (distances, predecessors) = if arcs.iter().any(|arc| arc.cost < 0.0) {
+            bellman_ford(&ids, &arcs, start, end, trace)
+                .map_err(|cycle| GraphEditorError::NegativeCycle(self.labels(&ids, &cycle)))?
+        } else {
+            dijkstra(&ids, &arcs, start, trace)
+        };
+
+        if !distances[end].is_finite() {
+            return Err(GraphEditorError::NoPath);
+        }
+
+        let mut nodes = vec![ids[end]];
+        let mut edges = Vec::new();
+        let mut current = end;
+        while let Some((previous, edge_id)) = predecessors[current] {
+            nodes.push(ids[previous]);
+            edges.push(edge_id);
+            current = previous;
+        }
+        nodes.reverse();
+        edges.reverse();
+
+        Ok(GraphPath {
+            nodes,
+            edges,
+            cost: distances[end],
+        })
+    }
+
+    fn labels(&self, ids: &[NodeId], indices: &[usize]) -> Vec<String> {
+        indices
+            .iter()
+            .map(|&index| self.nodes[&ids[index]].label.clone())
+            .collect()
+    }
+}
+
+/// Simple O(V^2) Dijkstra, graphs drawn by hand are small.
+fn dijkstra(
+    ids: &[NodeId],
+    arcs: &[Arc],
+    start: usize,
+    trace: &mut Trace,
+) -> (Vec<f32>, Predecessors) {
+    let node_count = ids.len();
+    let mut distances = vec![f32::INFINITY; node_count];
+    let mut predecessors = vec![None; node_count];
+    let mut visited = vec![false; node_count];
+    distances[start] = 0.0;
+    trace.record(Step::Push(ids[start]));
+
+    while let Some(current) = (0..node_count)
+        .filter(|&node| !visited[node] && distances[node].is_finite())
+        .min_by(|&a, &b| distances[a].total_cmp(&distances[b]))
+    {
+        visited[current] = true;
+        trace.record(Step::Pop(ids[current]));
+        trace.record(Step::Visit(ids[current]));
+
+        for arc in arcs.iter().filter(|arc| arc.from == current) {
+            let distance = distances[current] + arc.cost;
+            if distance < distances[arc.to] {
+                if !distances[arc.to].is_finite() {
+                    trace.record(Step::Push(ids[arc.to]));
+                }
+                distances[arc.to] = distance;
+                predecessors[arc.to] = Some((current, arc.edge_id));
+                trace.record(Step::Relax {
+                    edge: arc.edge_id,
+                    node: ids[arc.to],
+                    distance,
+                });
+            } else {
+                trace.record(Step::Examine(arc.edge_id));
+            }
+        }
+    }
+
+    (distances, predecessors)
+}
+
+/// Bellman-Ford, fails with nodes of negative cycle if it makes cost of path to end unbounded.
+fn bellman_ford(
+    ids: &[NodeId],
+    arcs: &[Arc],
+    start: usize,
+    end: usize,
+    trace: &mut Trace,
+) -> Result<(Vec<f32>, Predecessors), Vec<usize>> {
+    let node_count = ids.len();
+    let mut distances = vec![f32::INFINITY; node_count];
+    let mut predecessors = vec![None; node_count];
+    distances[start] = 0.0;
+    trace.record(Step::Visit(ids[start]));
+
+    for _ in 1..node_count {
+        if !relax(ids, arcs, &mut distances, &mut predecessors, trace) {
+            break;
+        }
+    }
+
+    // nodes which still can be improved are reachable from negative cycle
+    let mut unbounded = vec![false; node_count];
+    for arc in arcs {
+        if distances[arc.from] + arc.cost < distances[arc.to] {
+            distances[arc.to] = distances[arc.from] + arc.cost;
+            predecessors[arc.to] = Some((arc.from, arc.edge_id));
+            unbounded[arc.to] = true;
+        }
+    }
+    let Some(cycle_node) = unbounded.iter().position(|&unbounded| unbounded) else {
+        return Ok((distances, predecessors));
+    };
+
+    // everything reachable from those nodes is unbounded too
+    let mut stack: Vec<_> = (0..node_count).filter(|&node| unbounded[node]).collect();
+    while let Some(node) = stack.pop() {
+        for arc in arcs.iter().filter(|arc| arc.from == node) {
+            if !unbounded[arc.to] {
+                unbounded[arc.to] = true;
+                stack.push(arc.to);
+            }
+        }
+    }
+    if !unbounded[end] {
+        return Ok((distances, predecessors));
+    }
+
+    Err(negative_cycle(&predecessors, cycle_node, node_count))
+}
+
+/// Relax all arcs once, return true if some distance decreased.
+fn relax(
+    ids: &[NodeId],
+    arcs: &[Arc],
+    distances: &mut [f32],
+    predecessors: &mut Predecessors,
+    trace: &mut Trace,
+) -> bool {
+    let mut relaxed = false;
+
+    // arcs from unreached nodes are skipped, they can't give anything
+    for arc in arcs {
+        if !distances[arc.from].is_finite() {
+            continue;
+        }
+
+        let distance = distances[arc.from] + arc.cost;
+        if distance < distances[arc.to] {
+            distances[arc.to] = distance;
+            predecessors[arc.to] = Some((arc.from, arc.edge_id));
+            relaxed = true;
+            trace.record(Step::Relax {
+                edge: arc.edge_id,
+                node: ids[arc.to],
+                distance,
+            });
+        } else {
+            trace.record(Step::Examine(arc.edge_id));
+        }
+    }
+
+    relaxed
+}
+
+/// Nodes of cycle found by following predecessors of node relaxed after last iteration.
+fn negative_cycle(predecessors: &Predecessors, node: usize, node_count: usize) -> Vec<usize> {
+    // after node_count steps we are surely inside of cycle
+    let mut node = node;
+    for _ in 0..node_count {
+        node = predecessors[node].map_or(node, |(previous, _)| previous);
+    }
+
+    let mut cycle = vec![node];
+    let mut current = predecessors[node].map_or(node, |(previous, _)| previous);
+    while current != node {
+        cycle.push(current);
+        current = predecessors[current].map_or(node, |(previous, _)| previous);
+    }
+    cycle.reverse();
+
+    cycle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_utils;
+
+    /// Test graph with oriented edges `(from, to, weight)`.
+    fn graph(node_count: usize, edges: &[(usize, usize, f32)]) -> (Graph, Vec<NodeId>) {
+        let ends: Vec<_> = edges.iter().map(|&(from, to, _)| (from, to)).collect();
+        let (mut graph, node_ids, edge_ids) = test_utils::graph(node_count, &ends);
+        for (id, &(_, _, weight)) in edge_ids.iter().zip(edges) {
+            graph.edge_mut(id).unwrap().weight = weight;
+        }
+
+        (graph, node_ids)
+    }
+
+    fn path(graph: &Graph, start: NodeId, end: NodeId) -> Result<GraphPath, GraphEditorError> {
+        graph.shortest_path(start, end, EdgeCost::Weight, &mut Trace::default())
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_path() {
+        let (graph, ids) = graph(
+            5,
+            &[
+                (0, 1, 4.0),
+                (0, 2, 2.0),
+                (2, 1, 1.0),
+                (1, 3, 5.0),
+                (2, 3, 8.0),
+                (3, 4, 3.0),
+            ],
+        );
+
+        let path = path(&graph, ids[0], ids[4]).unwrap();
+
+        assert_eq!(path.nodes, [ids[0], ids[2], ids[1], ids[3], ids[4]]);
+        assert_eq!(path.edges, [EdgeId(2), EdgeId(3), EdgeId(4), EdgeId(6)]);
+        assert_eq!(path.cost, 11.0);
+    }
+
+    #[test]
+    fn unoriented_edge_is_walked_back() {
+        let (mut graph, ids) = graph(2, &[(1, 0, 3.0)]);
+        assert!(matches!(
+            path(&graph, ids[0], ids[1]),
+            Err(GraphEditorError::NoPath)
+        ));
+
+        graph.edge_mut(&EdgeId(1)).unwrap().oriented = false;
+        assert_eq!(path(&graph, ids[0], ids[1]).unwrap().cost, 3.0);
+    }
+
+    #[test]
+    fn bellman_ford_handles_negative_edges() {
+        let (graph, ids) = graph(3, &[(0, 1, 5.0), (0, 2, 2.0), (2, 1, -4.0)]);
+
+        let path = path(&graph, ids[0], ids[1]).unwrap();
+
+        assert_eq!(path.nodes, [ids[0], ids[2], ids[1]]);
+        assert_eq!(path.cost, -2.0);
+    }
+
+    #[test]
+    fn bellman_ford_detects_negative_cycle() {
+        let (graph, ids) = graph(4, &[(0, 1, 1.0), (1, 2, -3.0), (2, 1, 1.0), (2, 3, 1.0)]);
+
+        match path(&graph, ids[0], ids[3]) {
+            Err(GraphEditorError::NegativeCycle(labels)) => {
+                let mut labels = labels;
+                labels.sort();
+                labels.dedup();
+                assert_eq!(labels, ["1", "2"]);
+            }
+            other => panic!("expected negative cycle, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn negative_cycle_off_the_way_is_ignored() {
+        let (graph, ids) = graph(4, &[(0, 1, 2.0), (0, 2, 1.0), (2, 3, -3.0), (3, 2, 1.0)]);
+
+        assert_eq!(path(&graph, ids[0], ids[1]).unwrap().cost, 2.0);
+    }
+}
@@ -0,0 +1,432 @@
+//! Rules which style nodes and edges by their data, e.g. color nodes of high degree.
+//! Rules are applied to a copy of graph when it is drawn or exported.
+
+use std::collections::{HashMap, HashSet};
+
+use eframe::egui::Rgba;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    edge::{Edge, EdgeId},
+    Graph, LineStyle, Node, NodeId, NodeShape,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleTarget {
+    Nodes,
+    Edges,
+}
+
+impl RuleTarget {
+    pub const ALL: [RuleTarget; 2] = [RuleTarget::Nodes, RuleTarget::Edges];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleTarget::Nodes => "Nodes",
+            RuleTarget::Edges => "Edges",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    pub const ALL: [Comparison; 6] = [
+        Comparison::Less,
+        Comparison::LessOrEqual,
+        Comparison::Equal,
+        Comparison::NotEqual,
+        Comparison::GreaterOrEqual,
+        Comparison::Greater,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "≤",
+            Comparison::Equal => "=",
+            Comparison::NotEqual => "≠",
+            Comparison::GreaterOrEqual => "≥",
+            Comparison::Greater => ">",
+        }
+    }
+
+    pub fn holds(self, value: usize, bound: usize) -> bool {
+        match self {
+            Comparison::Less => value < bound,
+            Comparison::LessOrEqual => value <= bound,
+            Comparison::Equal => value == bound,
+            Comparison::NotEqual => value != bound,
+            Comparison::GreaterOrEqual => value >= bound,
+            Comparison::Greater => value > bound,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    Always,
+    /// Number of edge ends at node, loop counts twice.
+    Degree(Comparison, usize),
+    /// Label contains match of regular expression.
+    LabelMatches(String),
+    Loop,
+    /// Edge without orientation, loops included.
+    Unoriented,
+    /// Node or edge belongs to weak component of given node.
+    Component(Option<NodeId>),
+}
+
+impl Condition {
+    /// Conditions which make sense for target, with default parameters.
+    pub fn all(target: RuleTarget) -> Vec<Condition> {
+        let mut conditions = vec![Condition::Always];
+        match target {
+            RuleTarget::Nodes => conditions.push(Condition::Degree(Comparison::Greater, 2)),
+            RuleTarget::Edges => conditions.extend([Condition::Loop, Condition::Unoriented]),
+        }
+        conditions.extend([
+            Condition::LabelMatches(String::new()),
+            Condition::Component(None),
+        ]);
+        conditions
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Condition::Always => "Always",
+            Condition::Degree(..) => "Degree",
+            Condition::LabelMatches(_) => "Label matches",
+            Condition::Loop => "Is loop",
+            Condition::Unoriented => "Is unoriented",
+            Condition::Component(_) => "In component of",
+        }
+    }
+
+    pub fn fits(&self, target: RuleTarget) -> bool {
+        match self {
+            Condition::Degree(..) => target == RuleTarget::Nodes,
+            Condition::Loop | Condition::Unoriented => target == RuleTarget::Edges,
+            _ => true,
+        }
+    }
+
+    /// Error of label pattern, if it isn't valid regular expression,
+    /// or of component node, if it was removed from graph.
+    pub fn error(&self, graph: &Graph) -> Option<String> {
+        match self {
+            // parse errors quote the pattern, the last line tells what is wrong
+            Condition::LabelMatches(pattern) => Regex::new(pattern).err().map(|err| {
+                let message = err.to_string();
+                let last_line = message.lines().last().unwrap_or_default();
+                last_line.trim_start_matches("error: ").to_owned()
+            }),
+            Condition::Component(Some(node)) if graph.node(node).is_none() => {
+                Some("node was removed, pick another one".to_owned())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Node properties set by rule, None keeps property as is.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeStyle {
+    pub color: Option<Rgba>,
+    pub shape: Option<NodeShape>,
+    pub size: Option<f32>,
+}
+
+/// Edge properties set by rule, None keeps property as is.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EdgeStyle {
+    pub color: Option<Rgba>,
+    pub width: Option<f32>,
+    pub line_style: Option<LineStyle>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StyleRule {
+    pub enabled: bool,
+    pub target: RuleTarget,
+    pub condition: Condition,
+    #[serde(default)]
+    pub node_style: NodeStyle,
+    #[serde(default)]
+    pub edge_style: EdgeStyle,
+    /// Also restyle properties which differ from defaults. Editor doesn't remember
+    /// which values were set by hand, so any value other than default counts as such.
+    #[serde(default, alias = "overwrite")]
+    pub restyle_non_default: bool,
+}
+
+impl StyleRule {
+    pub fn new(target: RuleTarget) -> Self {
+        Self {
+            enabled: true,
+            target,
+            condition: Condition::Always,
+            node_style: NodeStyle::default(),
+            edge_style: EdgeStyle::default(),
+            restyle_non_default: false,
+        }
+    }
+}
+
+/// Condition prepared for checking many elements.
+enum Matcher {
+    Always,
+    Never,
+    /// Comparison, bound and degrees of all nodes.
+    Degree(Comparison, usize, HashMap<NodeId, usize>),
+    Label(Regex),
+    Loop,
+    Unoriented,
+    Component(HashSet<NodeId>),
+}
+
+impl Matcher {
+    fn new(graph: &Graph, condition: &Condition) -> Self {
+        match condition {
+            Condition::Always => Matcher::Always,
+            Condition::Degree(comparison, bound) => {
+                Matcher::Degree(*comparison, *bound, degrees(graph))
+            }
+            // invalid pattern matches nothing, editor shows the error
+            Condition::LabelMatches(pattern) => {
+                Regex::new(pattern).map_or(Matcher::Never, Matcher::Label)
+            }
+            Condition::Loop => Matcher::Loop,
+            Condition::Unoriented => Matcher::Unoriented,
+            Condition::Component(Some(node)) => graph
+                .weak_components()
+                .into_iter()
+                .find(|component| component.contains(node))
+                .map_or(Matcher::Never, |component| {
+                    Matcher::Component(component.into_iter().collect())
+                }),
+            Condition::Component(None) => Matcher::Never,
+        }
+    }
+
+    fn node_matches(&self, id: NodeId, label: &str) -> bool {
+        match self {
+            Matcher::Always => true,
+            Matcher::Degree(comparison, bound, degrees) => {
+                comparison.holds(degrees.get(&id).copied().unwrap_or(0), *bound)
+            }
+            Matcher::Label(regex) => regex.is_match(label),
+            Matcher::Component(component) => component.contains(&id),
+            Matcher::Never | Matcher::Loop | Matcher::Unoriented => false,
+        }
+    }
+
+    fn edge_matches(&self, edge: &Edge) -> bool {
+        match self {
+            Matcher::Always => true,
+            Matcher::Label(regex) => regex.is_match(&edge.label),
+            Matcher::Loop => edge.is_loop(),
+            Matcher::Unoriented => !edge.oriented,
+            Matcher::Component(component) => component.contains(&edge.start_id),
+            Matcher::Never | Matcher::Degree(..) => false,
+        }
+    }
+}
+
+/// Number of edge ends at every node with edges.
+fn degrees(graph: &Graph) -> HashMap<NodeId, usize> {
+    let mut degrees = HashMap::new();
+    for edge in graph.edges().values() {
+        *degrees.entry(edge.start_id).or_insert(0) += 1;
+        *degrees.entry(edge.end_id).or_insert(0) += 1;
+    }
+    degrees
+}
+
+/// Properties which rules set to nodes and edges of graph.
+/// They depend on labels, edges and rules only, so they are resolved once
+/// and applied to graph while nodes move.
+#[derive(Clone, Debug, Default)]
+pub struct Styles {
+    nodes: HashMap<NodeId, NodeStyle>,
+    edges: HashMap<EdgeId, EdgeStyle>,
+}
+
+impl Styles {
+    /// Styles of enabled rules of graph.
+    ///
+    /// Rules are applied in order, so later rules win. Unless rule restyles
+    /// non-default values, it changes only properties which are left at their defaults.
+    pub fn resolve(graph: &Graph) -> Self {
+        let mut styles = Self::default();
+        let default_node = Node::default();
+
+        let rules = graph
+            .style_rules()
+            .iter()
+            .filter(|rule| rule.enabled && rule.condition.fits(rule.target));
+        for rule in rules {
+            let matcher = Matcher::new(graph, &rule.condition);
+            let restyle = rule.restyle_non_default;
+
+            match rule.target {
+                RuleTarget::Nodes => {
+                    for (id, node) in graph.nodes() {
+                        if !matcher.node_matches(*id, &node.label) {
+                            continue;
+                        }
+                        let styled = styles.nodes.entry(*id).or_default();
+                        let style = &rule.node_style;
+
+                        if restyle || node.color == default_node.color {
+                            styled.color = style.color.or(styled.color);
+                        }
+                        if restyle || node.shape == default_node.shape {
+                            styled.shape = style.shape.or(styled.shape);
+                        }
+                        if restyle || node.size == default_node.size {
+                            styled.size = style.size.or(styled.size);
+                        }
+                    }
+                }
+                RuleTarget::Edges => {
+                    for (id, edge) in graph.edges() {
+                        if !matcher.edge_matches(edge) {
+                            continue;
+                        }
+                        let styled = styles.edges.entry(*id).or_default();
+                        let style = &rule.edge_style;
+                        let default_edge = Edge::new(edge.start_id, edge.end_id);
+
+                        if restyle || edge.color == default_edge.color {
+                            styled.color = style.color.or(styled.color);
+                        }
+                        if restyle || edge.width == default_edge.width {
+                            styled.width = style.width.or(styled.width);
+                        }
+                        if restyle || edge.line_style == default_edge.line_style {
+                            styled.line_style = style.line_style.or(styled.line_style);
+                        }
+                    }
+                }
+            }
+        }
+
+        styles
+            .nodes
+            .retain(|_, style| *style != NodeStyle::default());
+        styles
+            .edges
+            .retain(|_, style| *style != EdgeStyle::default());
+        styles
+    }
+
+    /// Copy of graph with styles applied, None if no rule matched anything.
+    pub fn apply(&self, graph: &Graph) -> Option<Graph> {
+        if self.nodes.is_empty() && self.edges.is_empty() {
+            return None;
+        }
+
+        let mut styled = graph.clone();
+        for (id, style) in &self.nodes {
+            let Some(node) = styled.node_mut(id) else {
+                continue;
+            };
+            node.color = style.color.unwrap_or(node.color);
+            node.shape = style.shape.unwrap_or(node.shape);
+            node.size = style.size.unwrap_or(node.size);
+        }
+        for (id, style) in &self.edges {
+            let Some(edge) = styled.edge_mut(id) else {
+                continue;
+            };
+            edge.color = style.color.unwrap_or(edge.color);
+            edge.width = style.width.unwrap_or(edge.width);
+            edge.line_style = style.line_style.unwrap_or(edge.line_style);
+        }
+
+        Some(styled)
+    }
+}
+
+/// Copy of graph styled by its enabled rules, None if they don't change anything.
+pub fn apply_rules(graph: &Graph) -> Option<Graph> {
+    Styles::resolve(graph).apply(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::Color32;
+
+    use super::*;
+
+    fn edge_rule(condition: Condition, color: Color32) -> StyleRule {
+        let mut rule = StyleRule::new(RuleTarget::Edges);
+        rule.condition = condition;
+        rule.edge_style.color = Some(color.into());
+        rule
+    }
+
+    #[test]
+    fn unoriented_condition_matches_loops() {
+        let mut graph = Graph::new();
+        let (a, b) = (graph.add_node(), graph.add_node());
+        let mut edges = [Edge::new(a, b), Edge::new(a, a), Edge::new(a, b)];
+        edges[0].oriented = false;
+        edges[1].oriented = false;
+        let ids = edges.map(|edge| graph.insert_edge(edge));
+        graph
+            .style_rules_mut()
+            .push(edge_rule(Condition::Unoriented, Color32::RED));
+
+        let styled = apply_rules(&graph).unwrap();
+
+        let red = Rgba::from(Color32::RED);
+        assert_eq!(styled.edges()[&ids[0]].color, red);
+        assert_eq!(styled.edges()[&ids[1]].color, red);
+        assert_ne!(styled.edges()[&ids[2]].color, red);
+    }
+
+    #[test]
+    fn non_default_values_are_kept_unless_restyled() {
+        let mut graph = Graph::new();
+        let (a, b) = (graph.add_node(), graph.add_node());
+        let mut edge = Edge::new(a, b);
+        edge.color = Color32::GREEN.into();
+        let id = graph.insert_edge(edge);
+        graph
+            .style_rules_mut()
+            .push(edge_rule(Condition::Always, Color32::RED));
+
+        assert!(apply_rules(&graph).is_none());
+
+        graph.style_rules_mut()[0].restyle_non_default = true;
+        graph
+            .style_rules_mut()
+            .push(edge_rule(Condition::Always, Color32::BLUE));
+        graph.style_rules_mut()[1].restyle_non_default = true;
+        let styled = apply_rules(&graph).unwrap();
+        assert_eq!(styled.edges()[&id].color, Color32::BLUE.into());
+    }
+
+    #[test]
+    fn removed_component_node_is_error() {
+        let mut graph = Graph::new();
+        let node = graph.add_node();
+        let condition = Condition::Component(Some(node));
+        assert_eq!(condition.error(&graph), None);
+
+        graph.remove_node(node);
+
+        assert!(condition.error(&graph).is_some());
+    }
+}
@@ -0,0 +1,190 @@
+use eframe::egui::{Pos2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use super::{edge::EdgeId, Edge, Graph, Node, NodeId};
+
+/// Version of subgraph text, it also marks clipboard text as subgraph.
+const SUBGRAPH_VERSION: u32 = 1;
+
+/// Part of graph: some nodes with edges between them.
+/// Used to copy nodes inside of editor and between editors via clipboard.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Subgraph {
+    graph_editor_subgraph: u32,
+    nodes: Vec<(NodeId, Node)>,
+    /// Edges referencing ids of `nodes`.
+    edges: Vec<Edge>,
+}
+
+impl Subgraph {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos2> + '_ {
+        self.nodes.iter().map(|(_, node)| node.position)
+    }
+
+    pub fn to_text(&self) -> String {
+        serde_json::to_string(self).expect("Subgraph is always serializable")
+    }
+
+    /// Problems of nodes and edges which editor wouldn't produce itself,
+    /// e.g. non-finite positions or sizes out of range in hand-edited text.
+    pub fn validate(&self) -> Vec<String> {
+        let mut graph = Graph::new();
+        graph.insert_subgraph(self, Vec2::ZERO);
+        graph.validate()
+    }
+
+    /// Parse subgraph from clipboard text, None if text is something else.
+    pub fn from_text(text: &str) -> Option<Self> {
+        serde_json::from_str::<Self>(text.trim())
+            .ok()
+            .filter(|subgraph| subgraph.graph_editor_subgraph == SUBGRAPH_VERSION)
+    }
+}
+
+impl Graph {
+    /// Copy of given nodes with edges between them.
+    pub fn subgraph<'a>(&self, node_ids: impl IntoIterator<Item = &'a NodeId>) -> Subgraph {
+        let mut nodes: Vec<_> = node_ids
+            .into_iter()
+            .filter_map(|id| self.node(id).map(|node| (*id, node.clone())))
+            .collect();
+        nodes.sort_by_key(|(id, _)| id.0);
+
+        let contains = |id: &NodeId| nodes.iter().any(|(node_id, _)| node_id == id);
+        let edges = self
+            .edges
+            .values()
+            .filter(|edge| contains(&edge.start_id) && contains(&edge.end_id))
+            .cloned()
+            .collect();
+
+        Subgraph {
+            graph_editor_subgraph: SUBGRAPH_VERSION,
+            nodes,
+            edges,
+        }
+    }
+
+    /// Insert copy of subgraph with new ids, moved by offset.
+    /// Return ids of inserted nodes and edges.
+    pub fn insert_subgraph(
+        &mut self,
+        subgraph: &Subgraph,
+        offset: Vec2,
+    ) -> (Vec<NodeId>, Vec<EdgeId>) {
+        let mut node_ids = Vec::with_capacity(subgraph.nodes.len());
+
+        for (_, node) in &subgraph.nodes {
+            let mut node = node.clone();
+            node.position += offset;
+            node_ids.push(self.insert_node(node));
+        }
+
+        let new_id = |old_id: NodeId| {
+            subgraph
+                .nodes
+                .iter()
+                .position(|(id, _)| *id == old_id)
+                .map(|index| node_ids[index])
+        };
+
+        let mut edge_ids = Vec::with_capacity(subgraph.edges.len());
+
+        for edge in &subgraph.edges {
+            // edges of hand-edited text may reference missing nodes
+            if let (Some(start_id), Some(end_id)) = (new_id(edge.start_id), new_id(edge.end_id)) {
+                let mut edge = edge.clone();
+                edge.start_id = start_id;
+                edge.end_id = end_id;
+                for point in &mut edge.bend_points {
+                    *point += offset;
+                }
+                edge_ids.push(self.insert_edge(edge));
+            }
+        }
+
+        (node_ids, edge_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Nodes 1, 2, 3 and edges 1-2, 2-3, 1-1.
+    fn sample_graph() -> Graph {
+        let mut graph = Graph::new();
+        let ids: Vec<_> = (0..3)
+            .map(|i| graph.insert_node(Node::new(i.to_string(), Pos2::new(i as f32, 0.0))))
+            .collect();
+        graph.insert_edge(Edge::new(ids[0], ids[1]));
+        graph.insert_edge(Edge::new(ids[1], ids[2]));
+        graph.insert_edge(Edge::new(ids[0], ids[0]));
+        graph
+    }
+
+    #[test]
+    fn subgraph_drops_edges_leaving_selection() {
+        let graph = sample_graph();
+
+        let subgraph = graph.subgraph(&[NodeId(2), NodeId(1), NodeId(7)]);
+
+        let ids: Vec<_> = subgraph.nodes.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, [NodeId(1), NodeId(2)]);
+        let ends: Vec<_> = subgraph
+            .edges
+            .iter()
+            .map(|edge| (edge.start_id, edge.end_id))
+            .collect();
+        assert_eq!(ends, [(NodeId(1), NodeId(2)), (NodeId(1), NodeId(1))]);
+    }
+
+    #[test]
+    fn inserted_subgraph_gets_new_ids() {
+        let mut graph = sample_graph();
+        let subgraph = graph.subgraph(&[NodeId(2), NodeId(3)]);
+
+        let (node_ids, edge_ids) = graph.insert_subgraph(&subgraph, Vec2::new(10.0, 5.0));
+
+        assert_eq!(node_ids, [NodeId(4), NodeId(5)]);
+        assert_eq!(edge_ids, [EdgeId(4)]);
+        let edge = graph.edge(&EdgeId(4)).unwrap();
+        assert_eq!((edge.start_id, edge.end_id), (NodeId(4), NodeId(5)));
+        assert_eq!(
+            graph.node(&NodeId(4)).unwrap().position,
+            Pos2::new(11.0, 5.0)
+        );
+        assert_eq!(graph.node(&NodeId(4)).unwrap().label, "1");
+    }
+
+    #[test]
+    fn edges_to_missing_nodes_are_not_inserted() {
+        let mut subgraph = sample_graph().subgraph(&[NodeId(1), NodeId(2)]);
+        subgraph.edges[0].end_id = NodeId(9);
+
+        let mut graph = Graph::new();
+        let (node_ids, edge_ids) = graph.insert_subgraph(&subgraph, Vec2::ZERO);
+
+        assert_eq!(node_ids.len(), 2);
+        assert_eq!(edge_ids.len(), 1);
+        assert!(graph.validate().is_empty());
+    }
+
+    #[test]
+    fn text_round_trip_and_validation() {
+        let subgraph = sample_graph().subgraph(&[NodeId(1), NodeId(2)]);
+        let parsed = Subgraph::from_text(&subgraph.to_text()).unwrap();
+        assert_eq!(parsed.nodes.len(), 2);
+        assert!(parsed.validate().is_empty());
+        assert!(Subgraph::from_text("just text").is_none());
+
+        let mut invalid = parsed;
+        invalid.nodes[0].1.position.x = f32::INFINITY;
+        invalid.edges[0].width = 1000.0;
+        assert_eq!(invalid.validate().len(), 2);
+    }
+}
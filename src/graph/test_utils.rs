@@ -0,0 +1,18 @@
+//! Graphs built for unit tests.
+
+use super::{edge::EdgeId, Edge, Graph, Node, NodeId};
+
+/// Graph of nodes labeled by their index with oriented edges `(from, to)`.
+/// Return ids of nodes and edges in the given order.
+pub fn graph(node_count: usize, edges: &[(usize, usize)]) -> (Graph, Vec<NodeId>, Vec<EdgeId>) {
+    let mut graph = Graph::new();
+    let node_ids: Vec<_> = (0..node_count)
+        .map(|index| graph.insert_node(Node::new(index.to_string(), Default::default())))
+        .collect();
+    let edge_ids = edges
+        .iter()
+        .map(|&(from, to)| graph.insert_edge(Edge::new(node_ids[from], node_ids[to])))
+        .collect();
+
+    (graph, node_ids, edge_ids)
+}
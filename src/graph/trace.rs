@@ -0,0 +1,40 @@
+use super::{edge::EdgeId, NodeId};
+
+/// One action of algorithm, recorded to replay it step by step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    /// Node is added to frontier (queue, stack or priority queue).
+    Push(NodeId),
+    /// Node is taken from frontier.
+    Pop(NodeId),
+    /// Node is processed.
+    Visit(NodeId),
+    /// Edge is looked at, but gives nothing new.
+    Examine(EdgeId),
+    /// Edge gives better (or first) way to node.
+    Relax {
+        edge: EdgeId,
+        node: NodeId,
+        distance: f32,
+    },
+}
+
+/// Steps of algorithm run in order of happening.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+}
+
+impl Trace {
+    pub fn record(&mut self, step: Step) {
+        self.steps.push(step);
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
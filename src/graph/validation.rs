@@ -0,0 +1,187 @@
+use std::ops::RangeInclusive;
+
+use crate::consts::{
+    MAX_EDGE_LABEL_PADDING, MAX_EDGE_LABEL_SIZE, MAX_EDGE_WIDTH, MAX_LOOP_EDGE_ANGLE,
+    MAX_NODE_ASPECT_RATIO, MAX_NODE_LABEL_SIZE, MAX_NODE_SIZE, MIN_EDGE_LABEL_PADDING,
+    MIN_EDGE_LABEL_SIZE, MIN_EDGE_WIDTH, MIN_LOOP_EDGE_ANGLE, MIN_NODE_ASPECT_RATIO,
+    MIN_NODE_LABEL_SIZE, MIN_NODE_SIZE,
+};
+
+use super::{
+    attribute::{AttributeValue, Attributes},
+    Graph,
+};
+
+impl Graph {
+    /// Find problems which editor can't handle or wouldn't produce itself:
+    /// edges to missing nodes, ids not covered by counters, values out of editor ranges.
+    /// Return human readable description of every problem.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        let mut nodes: Vec<_> = self.nodes.iter().collect();
+        nodes.sort_by_key(|(id, _)| id.0);
+
+        for (id, node) in nodes {
+            let name = format!("node {}", id.0);
+
+            if id.0 > self.node_id_counter {
+                errors.push(format!(
+                    "{name}: id is greater than node id counter ({})",
+                    self.node_id_counter
+                ));
+            }
+            if !node.position.x.is_finite() || !node.position.y.is_finite() {
+                errors.push(format!("{name}: position is not finite"));
+            }
+            check_range(
+                &mut errors,
+                &name,
+                "size",
+                node.size,
+                MIN_NODE_SIZE..=MAX_NODE_SIZE,
+            );
+            check_range(
+                &mut errors,
+                &name,
+                "aspect ratio",
+                node.aspect_ratio,
+                MIN_NODE_ASPECT_RATIO..=MAX_NODE_ASPECT_RATIO,
+            );
+            check_range(
+                &mut errors,
+                &name,
+                "label size",
+                node.label_size,
+                MIN_NODE_LABEL_SIZE..=MAX_NODE_LABEL_SIZE,
+            );
+            check_attributes(&mut errors, &name, &node.attributes);
+        }
+
+        for (id, edge) in &self.edges {
+            let name = format!("edge {}", id.0);
+
+            if id.0 > self.edge_id_counter {
+                errors.push(format!(
+                    "{name}: id is greater than edge id counter ({})",
+                    self.edge_id_counter
+                ));
+            }
+            for (end, node_id) in [("start", edge.start_id), ("end", edge.end_id)] {
+                if !self.nodes.contains_key(&node_id) {
+                    errors.push(format!("{name}: {end} node {} doesn't exist", node_id.0));
+                }
+            }
+            check_range(
+                &mut errors,
+                &name,
+                "width",
+                edge.width,
+                MIN_EDGE_WIDTH..=MAX_EDGE_WIDTH,
+            );
+            check_range(
+                &mut errors,
+                &name,
+                "label size",
+                edge.label_size,
+                MIN_EDGE_LABEL_SIZE..=MAX_EDGE_LABEL_SIZE,
+            );
+            for (axis, padding) in [("x", edge.padding_x), ("y", edge.padding_y)] {
+                check_range(
+                    &mut errors,
+                    &name,
+                    &format!("label padding {axis}"),
+                    padding,
+                    MIN_EDGE_LABEL_PADDING..=MAX_EDGE_LABEL_PADDING,
+                );
+            }
+            if !edge.weight.is_finite() {
+                errors.push(format!("{name}: weight is not finite"));
+            }
+            if edge
+                .bend_points
+                .iter()
+                .any(|point| !point.x.is_finite() || !point.y.is_finite())
+            {
+                errors.push(format!("{name}: bend point is not finite"));
+            }
+            check_range(
+                &mut errors,
+                &name,
+                "loop rotation angle",
+                edge.loop_rotation_angle,
+                MIN_LOOP_EDGE_ANGLE..=MAX_LOOP_EDGE_ANGLE,
+            );
+            check_attributes(&mut errors, &name, &edge.attributes);
+        }
+
+        for (index, rule) in self.style_rules.iter().enumerate() {
+            let name = format!("styling rule {}", index + 1);
+
+            if !rule.condition.fits(rule.target) {
+                errors.push(format!(
+                    "{name}: condition '{}' doesn't apply to {}",
+                    rule.condition.name(),
+                    rule.target.name().to_lowercase()
+                ));
+            }
+            if let Some(size) = rule.node_style.size {
+                check_range(
+                    &mut errors,
+                    &name,
+                    "size",
+                    size,
+                    MIN_NODE_SIZE..=MAX_NODE_SIZE,
+                );
+            }
+            if let Some(width) = rule.edge_style.width {
+                check_range(
+                    &mut errors,
+                    &name,
+                    "width",
+                    width,
+                    MIN_EDGE_WIDTH..=MAX_EDGE_WIDTH,
+                );
+            }
+        }
+
+        errors
+    }
+}
+
+fn check_attributes(errors: &mut Vec<String>, name: &str, attributes: &Attributes) {
+    for (index, attribute) in attributes.iter().enumerate() {
+        if attributes
+            .iter()
+            .take(index)
+            .any(|other| other.name == attribute.name)
+        {
+            errors.push(format!(
+                "{name}: attribute '{}' is repeated",
+                attribute.name
+            ));
+        }
+        if matches!(attribute.value, AttributeValue::Number(number) if !number.is_finite()) {
+            errors.push(format!(
+                "{name}: attribute '{}' is not finite number",
+                attribute.name
+            ));
+        }
+    }
+}
+
+fn check_range(
+    errors: &mut Vec<String>,
+    name: &str,
+    property: &str,
+    value: f32,
+    range: RangeInclusive<f32>,
+) {
+    if !range.contains(&value) {
+        errors.push(format!(
+            "{name}: {property} {value} is out of range [{}; {}]",
+            range.start(),
+            range.end()
+        ));
+    }
+}
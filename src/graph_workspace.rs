@@ -1,20 +1,73 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
-use eframe::egui::{self, Context, Rect, Stroke, Ui};
+use eframe::egui::{self, Context, Key, Pos2, Rect, Stroke, Ui, Vec2};
 
 use crate::{
-    canvas::Canvas,
-    comment_line::group::CommentsGroup,
+    canvas::{Canvas, SelectionHighlight},
+    comment_line::{group::CommentsGroup, CommentLine},
+    consts::{EDGE_HANDLE_RADIUS, FIT_VIEW_MARGIN, GUIDE_SNAP_DISTANCE, PASTE_OFFSET},
+    document::Document,
     error::GraphEditorError,
-    graph::{edge::EdgeId, Edge, Graph, Node, NodeId},
+    format::{svg, ExchangeFormat},
+    graph::{
+        edge::{EdgeEnd, EdgeId},
+        path::{EdgeCost, GraphPath},
+        styling::{self, StyleRule, Styles},
+        subgraph::Subgraph,
+        trace::Trace,
+        Edge, Graph, Node, NodeId,
+    },
+    history::{EditKind, History},
+    layout::{
+        align::{self, Alignment, Axis, Guides, Snapping},
+        force::{ForceLayout, COOLING, SETTLED_DISPLACEMENT},
+        layered::{LayeredDrawing, LayeredLayout},
+        LayoutAnimation,
+    },
+    render::{self, RenderOptions},
+    scene::ColorMap,
+    selection::Selection,
+    utils::geometry::{edge::edges_geometry, route::EdgeRoutes},
 };
 
 #[derive(Default)]
 struct InteractionState {
-    selected_node_id: Option<NodeId>,
+    selected_nodes: Selection<NodeId>,
     dragging_node_id: Option<NodeId>,
-    selected_edge_id: Option<EdgeId>,
+    /// Offset from pointer to dragged node, kept during the whole drag.
+    drag_offset: Option<Vec2>,
+    /// Guides which dragged node sticks to.
+    guides: Guides,
+    selected_edges: Selection<EdgeId>,
+    /// Edge under pointer, highlighted to show it can be clicked.
+    hovered_edge: Option<EdgeId>,
+    /// Edge which end is dragged to another node.
+    reconnecting_edge: Option<(EdgeId, EdgeEnd)>,
+    /// Dragged bend point: its edge, index and offset from pointer.
+    dragging_bend: Option<(EdgeId, usize, Vec2)>,
     new_edge_start: Option<NodeId>,
+    /// Start and end of rectangle selection.
+    selection_rect: Option<(Pos2, Pos2)>,
+    /// Shortest path shown on canvas, set by path editor every frame.
+    highlighted_path: Option<GraphPath>,
+    /// Colors of algorithm result, set by algorithm editor every frame.
+    recolor: Option<ColorMap>,
+    /// Highlight of replayed algorithm steps, set by player every frame.
+    overlay: Option<ColorMap>,
+}
+
+#[derive(Default)]
+struct LayoutState {
+    force: ForceLayout,
+    /// Nodes moving to positions found by layout run once.
+    animation: Option<LayoutAnimation>,
+    continuous: bool,
+    /// Limit of node move in next continuous step, raised by every edit.
+    temperature: f32,
 }
 
 #[derive(Default)]
@@ -22,7 +75,16 @@ pub struct GraphWorkspace {
     canvas: Canvas,
     comment_lines: CommentsGroup,
     graph: Graph,
+    /// Styles of rules resolved for current graph, None after edits which may change them.
+    styles: Option<Styles>,
+    /// Graph with styling rules applied, as it was drawn last frame.
+    styled_graph: Option<Graph>,
+    /// Routes of orthogonal edges of styled graph, None after edits which may move them.
+    routes: Option<EdgeRoutes>,
     interactions: InteractionState,
+    layout: LayoutState,
+    snapping: Snapping,
+    history: History<Document>,
 }
 
 impl GraphWorkspace {
@@ -34,18 +96,61 @@ impl GraphWorkspace {
         self.canvas.setup(ctx, ui);
     }
 
-    pub fn draw_components(&mut self, ui: &mut Ui) {
+    pub fn draw_components(&mut self) {
+        let styles = self
+            .styles
+            .get_or_insert_with(|| Styles::resolve(&self.graph));
+        self.styled_graph = styles.apply(&self.graph);
+        let styled = self.styled_graph.as_ref().unwrap_or(&self.graph);
+        let selection_rect = self.selection_rect();
+        let possible_edge_start = self
+            .interactions
+            .new_edge_start
+            .or(self.reconnection_anchor());
+        let routes = self
+            .routes
+            .get_or_insert_with(|| EdgeRoutes::resolve(styled));
+        let recolored = self
+            .interactions
+            .recolor
+            .as_ref()
+            .map(|colors| colors.recolor(styled));
+
+        if self.snapping.show_grid {
+            self.canvas.draw_grid(self.snapping.grid_spacing);
+        }
+
         self.canvas.draw_components(
-            &self.graph,
-            self.interactions.new_edge_start,
+            recolored.as_ref().unwrap_or(styled),
+            routes,
+            possible_edge_start,
             &self.comment_lines,
-            ui,
+            &SelectionHighlight {
+                nodes: &self.interactions.selected_nodes,
+                edges: &self.interactions.selected_edges,
+                hovered_edge: self.interactions.hovered_edge,
+                dragging_bend: self
+                    .interactions
+                    .dragging_bend
+                    .map(|(id, index, _)| (id, index)),
+                path: self.interactions.highlighted_path.as_ref(),
+                overlay: self.interactions.overlay.as_ref(),
+                rect: selection_rect,
+            },
         );
+
+        self.canvas.draw_guides(&self.interactions.guides);
     }
 
     pub fn handle_graph_interactions(&mut self) {
+        self.handle_edge_hover();
+        // pointer on edge handles doesn't change selection
+        if !self.handle_edge_reconnection() && !self.handle_bend_editing() {
+            self.handle_node_selection();
+        }
         self.handle_node_draging();
-        self.handle_node_selection();
+        self.handle_rect_selection();
+        self.handle_selection_removal();
 
         let edge_created = self.handle_edge_creation();
         // if edge_created is true => we clicked on edge's end => ignore this
@@ -55,12 +160,65 @@ impl GraphWorkspace {
     }
 
     pub fn graph_from_file(&mut self, file_path: &PathBuf) -> Result<(), GraphEditorError> {
-        self.graph = Graph::try_from(file_path)?;
+        let document = Document::try_from(file_path)?;
+
+        self.interactions = InteractionState::default();
+        self.history.reset(document.clone());
+        self.restore(document);
+        self.reset_view();
         Ok(())
     }
 
-    pub fn save_graph_to_file(&mut self, file_path: &PathBuf) -> Result<(), GraphEditorError> {
-        self.graph.save_to_file(file_path)
+    pub fn save_graph_to_file(&mut self, file_path: &Path) -> Result<(), GraphEditorError> {
+        self.snapshot().save_to_file(file_path)
+    }
+
+    /// Replace current document with graph imported from file of given format.
+    pub fn import_graph(
+        &mut self,
+        file_path: &Path,
+        format: ExchangeFormat,
+    ) -> Result<(), GraphEditorError> {
+        let document = Document::import(file_path, format)?;
+
+        self.interactions = InteractionState::default();
+        self.history.reset(document.clone());
+        self.restore(document);
+        self.reset_view();
+        Ok(())
+    }
+
+    pub fn export_graph(
+        &self,
+        file_path: &PathBuf,
+        format: ExchangeFormat,
+    ) -> Result<(), GraphEditorError> {
+        fs::write(file_path, format.export(&self.graph))
+            .map_err(|_| GraphEditorError::FailedSaveFile)
+    }
+
+    pub fn export_svg(&self, file_path: &PathBuf) -> Result<(), GraphEditorError> {
+        let styled = styling::apply_rules(&self.graph);
+        let graph = styled.as_ref().unwrap_or(&self.graph);
+
+        fs::write(file_path, svg::export(graph, &self.comment_lines))
+            .map_err(|_| GraphEditorError::FailedSaveFile)
+    }
+
+    pub fn export_png(
+        &self,
+        file_path: &Path,
+        options: &RenderOptions,
+    ) -> Result<(), GraphEditorError> {
+        let styled = styling::apply_rules(&self.graph);
+        let graph = styled.as_ref().unwrap_or(&self.graph);
+
+        render::save_png(file_path, graph, &self.comment_lines, options)
+    }
+
+    /// Graph as it is drawn: styled by rules, if there are any.
+    fn shown_graph(&self) -> &Graph {
+        self.styled_graph.as_ref().unwrap_or(&self.graph)
     }
 
     pub fn canvas_rect(&self) -> Rect {
@@ -72,104 +230,561 @@ impl GraphWorkspace {
     }
 }
 
+// history
+impl GraphWorkspace {
+    fn snapshot(&self) -> Document {
+        Document::new(self.graph.clone(), self.comment_lines.clone())
+    }
+
+    /// Record current state in history after an edit.
+    pub fn record(&mut self, kind: EditKind) {
+        let snapshot = self.snapshot();
+        self.history.record(kind, &snapshot);
+        if kind.affects_styling() {
+            self.styles = None;
+        }
+        if kind.affects_routing() {
+            self.routes = None;
+        }
+
+        if kind != EditKind::Layout {
+            self.layout.temperature = self.layout.force.continuous_temperature();
+        }
+    }
+
+    /// Finish continuous edits (drag, scrub, stroke) when pointer is released.
+    pub fn seal_history_on_release(&mut self, ctx: &Context) {
+        if ctx.input(|i| i.pointer.any_released()) {
+            self.history.seal();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.history.undo().cloned() {
+            self.restore(snapshot);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(snapshot) = self.history.redo().cloned() {
+            self.restore(snapshot);
+        }
+    }
+
+    fn restore(&mut self, snapshot: Document) {
+        self.layout.animation = None;
+        self.styles = None;
+        self.routes = None;
+        self.graph = snapshot.graph;
+        self.comment_lines = snapshot.comment_lines;
+
+        // start next comment line from scratch instead of extending restored one
+        if self
+            .comment_lines
+            .last_added_mut()
+            .is_some_and(|line| !line.is_empty())
+        {
+            self.comment_lines.insert(CommentLine::new());
+        }
+
+        self.forget_removed();
+    }
+
+    /// Drop references to nodes and edges which don't exist anymore.
+    fn forget_removed(&mut self) {
+        let interactions = &mut self.interactions;
+        let node_exists = |id: &NodeId| self.graph.node(id).is_some();
+
+        interactions.selected_nodes.retain(node_exists);
+        interactions.dragging_node_id = interactions.dragging_node_id.filter(node_exists);
+        interactions.new_edge_start = interactions.new_edge_start.filter(node_exists);
+        interactions
+            .selected_edges
+            .retain(|id| self.graph.edge(id).is_some());
+        interactions.hovered_edge = interactions
+            .hovered_edge
+            .filter(|id| self.graph.edge(id).is_some());
+        interactions.reconnecting_edge = interactions
+            .reconnecting_edge
+            .filter(|(id, _)| self.graph.edge(id).is_some());
+        interactions.dragging_bend = interactions.dragging_bend.filter(|(id, index, _)| {
+            self.graph
+                .edge(id)
+                .is_some_and(|edge| *index < edge.bend_points.len())
+        });
+    }
+}
+
+// view
+impl GraphWorkspace {
+    pub fn is_panning(&self) -> bool {
+        self.canvas.is_panning()
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.canvas.view().zoom()
+    }
+
+    /// Zoom and pan view to show whole graph with comment lines.
+    pub fn fit_view(&mut self) {
+        let mut bounds = Rect::NOTHING;
+
+        for node in self.graph.nodes().values() {
+            bounds = bounds.union(Rect::from_center_size(
+                node.position,
+                2.0 * node.half_size(),
+            ));
+        }
+        for (_, line) in self.comment_lines.iter() {
+            for point in &line.points {
+                bounds.extend_with(*point);
+            }
+        }
+
+        if bounds.is_positive() {
+            let screen_rect = self.canvas_rect().shrink(FIT_VIEW_MARGIN);
+            self.canvas.view_mut().fit(bounds, screen_rect);
+        } else {
+            self.reset_view();
+        }
+    }
+
+    pub fn reset_view(&mut self) {
+        self.canvas.view_mut().reset();
+    }
+}
+
 // node
 impl GraphWorkspace {
     pub fn selected_node_mut(&mut self) -> Option<&mut Node> {
         self.interactions
-            .selected_node_id
+            .selected_nodes
+            .primary()
             .map(|id| self.graph.node_mut(&id).unwrap())
     }
 
+    /// Primary selected node, shown in node editor.
+    pub fn selected_node_id(&self) -> Option<NodeId> {
+        self.interactions.selected_nodes.primary()
+    }
+
     pub fn set_selected_node_id(&mut self, node_id: Option<NodeId>) {
-        self.interactions.selected_node_id = node_id;
+        self.interactions.selected_nodes.set(node_id);
+    }
+
+    pub fn selected_nodes(&self) -> &Selection<NodeId> {
+        &self.interactions.selected_nodes
+    }
+
+    /// Apply change to every selected node.
+    pub fn update_selected_nodes(&mut self, mut update: impl FnMut(&mut Node)) {
+        for id in self.interactions.selected_nodes.iter() {
+            update(self.graph.node_mut(id).unwrap());
+        }
     }
 
-    pub fn remove_selected_node(&mut self) {
-        if let Some(selected_id) = self.interactions.selected_node_id {
-            self.remove_node(selected_id);
+    /// Remove all selected nodes as one edit.
+    pub fn remove_selected_nodes(&mut self) {
+        if self.interactions.selected_nodes.is_empty() {
+            return;
+        }
+
+        for &id in self.interactions.selected_nodes.iter() {
+            self.graph.remove_node(id);
         }
+        self.forget_removed();
+        self.record(EditKind::RemoveNode);
     }
 
-    /// Mark one node selected if pointer position same as this node position.
+    /// Remove all selected nodes and edges as one edit.
+    pub fn remove_selection(&mut self) {
+        if self.interactions.selected_nodes.is_empty()
+            && self.interactions.selected_edges.is_empty()
+        {
+            return;
+        }
+
+        for &id in self.interactions.selected_edges.iter() {
+            self.graph.remove_edge(id);
+        }
+        for &id in self.interactions.selected_nodes.iter() {
+            self.graph.remove_node(id);
+        }
+        self.forget_removed();
+        self.record(EditKind::RemoveSelection);
+    }
+
+    /// Remove selection when Delete is pressed, unless some text field is focused.
+    pub fn handle_selection_removal(&mut self) {
+        let ctx = &self.canvas.response().ctx;
+
+        if ctx.memory(|m| m.focused().is_none()) && ctx.input(|i| i.key_pressed(Key::Delete)) {
+            self.remove_selection();
+        }
+    }
+
+    /// Topmost node under pointer, as node is drawn.
+    fn node_at(&self, pointer_pos: Pos2) -> Option<NodeId> {
+        self.shown_graph()
+            .nodes()
+            .iter()
+            .filter(|(_, node)| node.is_clicked(pointer_pos))
+            .map(|(&id, _)| id)
+            .max_by_key(|id| id.0)
+    }
+
+    /// Update selection when primary button is pressed on canvas.
+    /// Shift/Ctrl-click toggles node or edge, press on empty canvas starts rectangle selection.
     pub fn handle_node_selection(&mut self) {
-        if let Some(pointer_pos) = self.canvas.response().interact_pointer_pos() {
-            for (id, node) in self.graph.nodes() {
-                if node.is_clicked(pointer_pos) {
-                    self.set_selected_node_id(Some(*id));
-                    break;
+        let response = self.canvas.response();
+        let (pressed, toggle) = response.ctx.input(|i| {
+            (
+                i.pointer.primary_pressed(),
+                i.modifiers.shift || i.modifiers.command,
+            )
+        });
+
+        if !pressed || !response.hovered() {
+            return;
+        }
+        let Some(pointer_pos) = self.canvas.hover_pos() else {
+            return;
+        };
+
+        let node_under_pointer = self.node_at(pointer_pos);
+        let interactions = &mut self.interactions;
+        match (node_under_pointer, interactions.hovered_edge) {
+            (Some(id), _) => {
+                if toggle {
+                    interactions.selected_nodes.toggle(id);
+                } else if interactions.selected_nodes.contains(&id) {
+                    // keep selected group to drag it
+                    interactions.selected_nodes.insert(id);
+                } else {
+                    interactions.selected_nodes.set(Some(id));
+                }
+
+                if interactions.selected_nodes.contains(&id) {
+                    interactions.dragging_node_id = Some(id);
+                    interactions.drag_offset = Some(self.graph.nodes()[&id].position - pointer_pos);
                 }
             }
+            (None, Some(id)) => {
+                if toggle {
+                    interactions.selected_edges.toggle(id);
+                } else {
+                    interactions.selected_nodes.clear();
+                    interactions.selected_edges.set(Some(id));
+                }
+            }
+            (None, None) => {
+                if !toggle {
+                    interactions.selected_nodes.clear();
+                    interactions.selected_edges.clear();
+                }
+                interactions.selection_rect = Some((pointer_pos, pointer_pos));
+            }
         }
     }
 
+    /// Extend rectangle selection while pointer is dragged,
+    /// select nodes inside of it (and edges between them) on release.
+    pub fn handle_rect_selection(&mut self) {
+        let Some((start, _)) = self.interactions.selection_rect else {
+            return;
+        };
+
+        if let Some(pointer_pos) = self.canvas.interact_pos() {
+            self.interactions.selection_rect = Some((start, pointer_pos));
+            return;
+        }
+
+        let rect = self.selection_rect().unwrap();
+        self.interactions.selection_rect = None;
+
+        let nodes: Vec<_> = self
+            .graph
+            .nodes()
+            .iter()
+            .filter(|(_, node)| rect.contains(node.position))
+            .map(|(&id, _)| id)
+            .collect();
+
+        for (&id, edge) in self.graph.edges() {
+            if nodes.contains(&edge.start_id) && nodes.contains(&edge.end_id) {
+                self.interactions.selected_edges.insert(id);
+            }
+        }
+        for id in nodes {
+            self.interactions.selected_nodes.insert(id);
+        }
+    }
+
+    fn selection_rect(&self) -> Option<Rect> {
+        self.interactions
+            .selection_rect
+            .map(|(start, end)| Rect::from_two_pos(start, end))
+    }
+
     pub fn dragging_node(&self) -> Option<NodeId> {
         self.interactions.dragging_node_id
     }
 
     pub fn set_dragging_node(&mut self, dragging: Option<NodeId>) {
         self.interactions.dragging_node_id = dragging;
+        self.interactions.drag_offset = None;
+        self.interactions.guides = Guides::default();
     }
 
-    /// Handle node dragging.
+    /// Move all selected nodes while one of them is dragged.
     pub fn handle_node_draging(&mut self) {
-        let pointer_pos = match self.canvas.response().interact_pointer_pos() {
-            Some(pos) => pos,
-            None => {
-                // any node is not node dragging
-                self.set_dragging_node(None);
-                return;
-            }
+        let (Some(id), Some(pointer_pos)) = (self.dragging_node(), self.canvas.interact_pos())
+        else {
+            // any node is not node dragging
+            self.set_dragging_node(None);
+            return;
         };
 
         self.canvas.set_cursor_icon(egui::CursorIcon::Grabbing);
 
-        // drag selected node to poiter pos
-        if let Some(id) = self.dragging_node() {
-            let node = self.graph.nodes().get(&id).unwrap();
-            self.graph.node_mut(&id).unwrap().position =
-                self.canvas.bounds_constraint_correction(node, pointer_pos);
+        let position = self.graph.nodes()[&id].position;
+        let offset = *self
+            .interactions
+            .drag_offset
+            .get_or_insert(position - pointer_pos);
+        let mut target = pointer_pos + offset;
 
-            return;
+        let snapping = self.snapping;
+        let mut guides = Guides::default();
+        if snapping.smart_guides {
+            let tolerance = GUIDE_SNAP_DISTANCE / self.canvas.view().zoom();
+            guides = Guides::find(&self.graph, &self.dragged_nodes(), target, tolerance);
+            target = guides.snap(target);
         }
+        if snapping.snap_to_grid {
+            // guides win over grid
+            target = guides.snap(snapping.grid_point(target));
+        }
+        self.interactions.guides = guides;
+
+        let delta = target - position;
+        if delta != Vec2::ZERO {
+            self.update_selected_nodes(|node| node.position += delta);
 
-        // if pointer pos is the same as some node => mark node as dragging node
-        for (id, node) in self.graph.nodes().iter() {
-            if node.is_clicked(pointer_pos) {
-                self.set_dragging_node(Some(*id));
-                break;
+            // bend points of edges inside of moved group go with it
+            let selected = &self.interactions.selected_nodes;
+            let edges: Vec<_> = self
+                .graph
+                .edges()
+                .iter()
+                .filter(|(_, edge)| {
+                    selected.contains(&edge.start_id) && selected.contains(&edge.end_id)
+                })
+                .map(|(&id, _)| id)
+                .collect();
+            for id in edges {
+                for point in &mut self.graph.edge_mut(&id).unwrap().bend_points {
+                    *point += delta;
+                }
             }
+
+            self.record(EditKind::MoveNode(id));
         }
     }
 
     pub fn add_node(&mut self) {
-        self.graph.add_node();
+        let id = self.graph.add_node();
+
+        // new node position is relative to visible part of canvas
+        let node = self.graph.node_mut(&id).unwrap();
+        node.position = self.canvas.view().to_world(node.position);
+
+        self.record(EditKind::AddNode);
     }
 
     pub fn remove_node(&mut self, id: NodeId) {
-        if let Some(selected_id) = self.interactions.selected_node_id {
-            if id == selected_id {
-                self.interactions.selected_node_id = None;
-            }
+        self.graph.remove_node(id);
+        self.forget_removed();
+        self.record(EditKind::RemoveNode);
+    }
+
+    pub fn node(&self, id: &NodeId) -> Option<&Node> {
+        self.graph.node(id)
+    }
+
+    /// Ids of all nodes in creation order.
+    pub fn node_ids(&self) -> Vec<NodeId> {
+        let mut ids: Vec<_> = self.graph.nodes().keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+        ids
+    }
+}
+
+// layout
+impl GraphWorkspace {
+    /// Nodes which are moved by user right now, layout doesn't touch them.
+    fn dragged_nodes(&self) -> Vec<NodeId> {
+        match self.dragging_node() {
+            Some(id) => self
+                .interactions
+                .selected_nodes
+                .iter()
+                .copied()
+                .chain([id])
+                .collect(),
+            None => Vec::new(),
         }
+    }
+
+    /// Animate nodes to positions found by layout, pinned nodes stay in place.
+    pub fn animate_layout(&mut self, mut targets: HashMap<NodeId, Pos2>) {
+        targets.retain(|id, _| self.graph.node(id).is_some_and(|node| !node.pinned));
+        self.layout.animation = Some(LayoutAnimation::new(&self.graph, targets));
+    }
+
+    /// Compute force-directed layout and animate nodes to it.
+    pub fn run_force_layout(&mut self) {
+        let targets = self.layout.force.run(&self.graph, &self.dragged_nodes());
+        self.animate_layout(targets);
+    }
+
+    /// Put nodes in layers along edge orientation and animate nodes to them,
+    /// edges crossing layers bend around nodes of layers between.
+    pub fn run_layered_layout(&mut self, layout: &LayeredLayout) {
+        let loop_rotation_angle = layout.direction.loop_rotation_angle();
+        let loops: Vec<_> = self
+            .graph
+            .edges()
+            .iter()
+            .filter(|(_, edge)| edge.is_loop())
+            .map(|(id, _)| *id)
+            .collect();
+        for id in loops {
+            self.graph.edge_mut(&id).unwrap().loop_rotation_angle = loop_rotation_angle;
+        }
+
+        let LayeredDrawing {
+            positions,
+            bend_points,
+        } = layout.run(&self.graph);
+        self.animate_layout(positions);
+        if let Some(animation) = self.layout.animation.take() {
+            self.layout.animation = Some(animation.with_bend_points(&self.graph, bend_points));
+        }
+    }
 
-        if let Some(dragging_id) = self.interactions.dragging_node_id {
-            if id == dragging_id {
-                self.interactions.dragging_node_id = None;
+    pub fn force_layout_mut(&mut self) -> &mut ForceLayout {
+        &mut self.layout.force
+    }
+
+    pub fn is_continuous_layout(&self) -> bool {
+        self.layout.continuous
+    }
+
+    pub fn set_continuous_layout(&mut self, continuous: bool) {
+        self.layout.continuous = continuous;
+        self.layout.temperature = self.layout.force.continuous_temperature();
+    }
+
+    pub fn snapping_mut(&mut self) -> &mut Snapping {
+        &mut self.snapping
+    }
+
+    pub fn align_selected_nodes(&mut self, alignment: Alignment) {
+        let ids: Vec<_> = self.interactions.selected_nodes.iter().copied().collect();
+        if align::align(&mut self.graph, &ids, alignment) {
+            self.record(EditKind::Align);
+        }
+    }
+
+    pub fn distribute_selected_nodes(&mut self, axis: Axis) {
+        let ids: Vec<_> = self.interactions.selected_nodes.iter().copied().collect();
+        if align::distribute(&mut self.graph, &ids, axis) {
+            self.record(EditKind::Align);
+        }
+    }
+
+    /// Advance layout animation or continuous layout by one frame.
+    pub fn update_layout(&mut self, ctx: &Context) {
+        if let Some(animation) = &mut self.layout.animation {
+            // animation frames are recorded only when it finishes
+            self.routes = None;
+            if animation.update(&mut self.graph, ctx.input(|i| i.stable_dt)) {
+                self.layout.animation = None;
+                self.record(EditKind::Layout);
+                self.history.seal();
             }
+            ctx.request_repaint();
+            return;
         }
 
-        self.graph.remove_node(id);
+        if !self.layout.continuous || self.layout.temperature < SETTLED_DISPLACEMENT {
+            return;
+        }
 
-        // TODO: check if need this, looks weird
-        // meaning we removed edge when deleting edges connected to node
-        // but must set selected id = None
-        if self.selected_edge().is_none() {
-            self.set_selected_edge_id(None);
+        let fixed = self.dragged_nodes();
+        let displacement = self
+            .layout
+            .force
+            .step(&mut self.graph, &fixed, self.layout.temperature);
+        self.layout.temperature *= COOLING;
+
+        if displacement > 0.0 {
+            // layout moves while node is dragged are part of the drag
+            match self.dragging_node() {
+                Some(id) => self.record(EditKind::MoveNode(id)),
+                None => self.record(EditKind::Layout),
+            }
         }
+        ctx.request_repaint();
     }
+}
 
-    pub fn node(&self, id: &NodeId) -> Option<&Node> {
-        self.graph.node(id)
+// algorithms
+impl GraphWorkspace {
+    pub fn shortest_path(
+        &self,
+        start: NodeId,
+        end: NodeId,
+        edge_cost: EdgeCost,
+        trace: &mut Trace,
+    ) -> Result<GraphPath, GraphEditorError> {
+        self.graph.shortest_path(start, end, edge_cost, trace)
+    }
+
+    pub fn set_highlighted_path(&mut self, path: Option<GraphPath>) {
+        self.interactions.highlighted_path = path;
+    }
+
+    pub fn set_recolor(&mut self, recolor: Option<ColorMap>) {
+        self.interactions.recolor = recolor;
+    }
+
+    pub fn set_overlay(&mut self, overlay: Option<ColorMap>) {
+        self.interactions.overlay = overlay;
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+}
+
+// styling
+impl GraphWorkspace {
+    pub fn style_rules(&self) -> &[StyleRule] {
+        self.graph.style_rules()
+    }
+
+    pub fn set_style_rules(&mut self, rules: Vec<StyleRule>) {
+        *self.graph.style_rules_mut() = rules;
     }
 }
 
@@ -194,17 +809,15 @@ impl GraphWorkspace {
         }
 
         if let Some(edge_start) = self.interactions.new_edge_start {
-            let pointer_pos = self.canvas.response().interact_pointer_pos().unwrap();
+            let pointer_pos = self.canvas.interact_pos().unwrap();
 
             // if some node has same pos as pointer
             // then creating edge (edge_start; node)
-            for (id, node) in self.graph.nodes() {
-                if node.is_clicked(pointer_pos) {
-                    self.add_edge(edge_start, *id);
-                    self.interactions.new_edge_start = None;
+            if let Some(id) = self.node_at(pointer_pos) {
+                self.add_edge(edge_start, id);
+                self.interactions.new_edge_start = None;
 
-                    return true;
-                }
+                return true;
             }
         }
 
@@ -215,54 +828,251 @@ impl GraphWorkspace {
     /// (Edge is not created at this moment)
     pub fn handle_setting_edge_start(&mut self) {
         if self.canvas.response().secondary_clicked() {
-            let pointer_pos = self.canvas.response().interact_pointer_pos().unwrap();
+            let pointer_pos = self.canvas.interact_pos().unwrap();
 
             // if some node has same pos as pointer
             // then set edge start as node id
-            for (id, node) in self.graph.nodes() {
-                if node.is_clicked(pointer_pos) {
-                    self.interactions.new_edge_start = Some(*id);
-                    break;
-                }
+            if let Some(id) = self.node_at(pointer_pos) {
+                self.interactions.new_edge_start = Some(id);
+            }
+        }
+    }
+
+    /// Remember edge under pointer, nodes are above edges, so they hide them.
+    fn handle_edge_hover(&mut self) {
+        let hovered_edge = self
+            .canvas
+            .hover_pos()
+            .filter(|&pos| self.node_at(pos).is_none())
+            .and_then(|pos| {
+                self.canvas
+                    .edge_at(self.shown_graph(), self.routes.as_ref(), pos)
+            });
+
+        if hovered_edge.is_some() && self.dragging_node().is_none() {
+            self.canvas.set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+        self.interactions.hovered_edge = hovered_edge;
+    }
+
+    /// End of selected edge which handle is under pointer.
+    fn edge_end_at(&self, pointer_pos: Pos2) -> Option<(EdgeId, EdgeEnd)> {
+        let selected = &self.interactions.selected_edges;
+        if selected.is_empty() {
+            return None;
+        }
+        let radius = EDGE_HANDLE_RADIUS / self.canvas.view().zoom();
+
+        edges_geometry(self.shown_graph(), self.routes.as_ref())
+            .into_iter()
+            .filter(|(id, _)| selected.contains(id))
+            .find_map(|(id, geometry)| {
+                [
+                    (EdgeEnd::Start, geometry.curve.start()),
+                    (EdgeEnd::End, geometry.curve.end()),
+                ]
+                .into_iter()
+                .find(|(_, point)| point.distance(pointer_pos) <= radius)
+                .map(|(end, _)| (id, end))
+            })
+    }
+
+    /// Node at the other end of edge being reconnected, preview is drawn from it.
+    fn reconnection_anchor(&self) -> Option<NodeId> {
+        let (id, end) = self.interactions.reconnecting_edge?;
+        let other_end = match end {
+            EdgeEnd::Start => EdgeEnd::End,
+            EdgeEnd::End => EdgeEnd::Start,
+        };
+        Some(self.graph.edge(&id)?.node_id(other_end))
+    }
+
+    /// Drag handle at end of selected edge onto another node to reconnect edge.
+    /// Escape cancels dragging.
+    /// Return true if pointer is busy with edge end.
+    pub fn handle_edge_reconnection(&mut self) -> bool {
+        let response = self.canvas.response();
+        let hovered = response.hovered();
+        let (pressed, down, escape) = response.ctx.input(|i| {
+            (
+                i.pointer.primary_pressed(),
+                i.pointer.primary_down(),
+                i.key_pressed(Key::Escape),
+            )
+        });
+
+        if pressed && hovered && self.interactions.reconnecting_edge.is_none() {
+            self.interactions.reconnecting_edge = self
+                .canvas
+                .hover_pos()
+                .and_then(|pointer_pos| self.edge_end_at(pointer_pos));
+        }
+        let Some((id, end)) = self.interactions.reconnecting_edge else {
+            return false;
+        };
+
+        if escape {
+            self.interactions.reconnecting_edge = None;
+            return true;
+        }
+        if down {
+            self.canvas.set_cursor_icon(egui::CursorIcon::Grabbing);
+            return true;
+        }
+
+        // released: end goes to node under pointer, or stays if there is none
+        self.interactions.reconnecting_edge = None;
+        let target = self
+            .canvas
+            .hover_pos()
+            .and_then(|pointer_pos| self.node_at(pointer_pos));
+        if let Some(node_id) = target {
+            let edge = self.graph.edge_mut(&id).unwrap();
+            if edge.node_id(end) != node_id {
+                edge.reconnect(end, node_id);
+                self.record(EditKind::ReconnectEdge);
             }
         }
+
+        true
+    }
+
+    /// Bend point of selected polyline edge under pointer.
+    fn bend_point_at(&self, pointer_pos: Pos2) -> Option<(EdgeId, usize)> {
+        let radius = EDGE_HANDLE_RADIUS / self.canvas.view().zoom();
+
+        self.interactions.selected_edges.iter().find_map(|&id| {
+            let edge = self.graph.edge(&id).filter(|edge| edge.is_polyline())?;
+            edge.bend_points
+                .iter()
+                .position(|point| point.distance(pointer_pos) <= radius)
+                .map(|index| (id, index))
+        })
+    }
+
+    /// Drag bend points of selected polyline edges, add them by double-click on edge
+    /// and remove by double-click on them.
+    /// Return true if pointer is busy with bend point.
+    pub fn handle_bend_editing(&mut self) -> bool {
+        let response = self.canvas.response();
+        let (pressed, dragged, double_clicked) = (
+            response.ctx.input(|i| i.pointer.primary_pressed()) && response.hovered(),
+            response.dragged(),
+            response.double_clicked(),
+        );
+        let Some(pointer_pos) = self.canvas.hover_pos() else {
+            self.interactions.dragging_bend = None;
+            return false;
+        };
+
+        if double_clicked {
+            self.interactions.dragging_bend = None;
+
+            if let Some((id, index)) = self.bend_point_at(pointer_pos) {
+                self.graph.edge_mut(&id).unwrap().bend_points.remove(index);
+                self.record(EditKind::EdgeProperty(id));
+                return true;
+            }
+
+            let hovered_polyline = self.interactions.hovered_edge.filter(|id| {
+                self.interactions.selected_edges.contains(id)
+                    && self.graph.edge(id).is_some_and(Edge::is_polyline)
+            });
+            if let Some(id) = hovered_polyline {
+                let (start, end) = self
+                    .edge_nodes(&id)
+                    .map(|(start, end)| (start.position, end.position))
+                    .unwrap();
+                self.graph
+                    .edge_mut(&id)
+                    .unwrap()
+                    .insert_bend_point(start, end, pointer_pos);
+                self.record(EditKind::EdgeProperty(id));
+                return true;
+            }
+
+            return false;
+        }
+
+        if pressed {
+            self.interactions.dragging_bend = self.bend_point_at(pointer_pos).map(|(id, index)| {
+                let point = self.graph.edges()[&id].bend_points[index];
+                (id, index, point - pointer_pos)
+            });
+        }
+
+        let Some((id, index, offset)) = self.interactions.dragging_bend else {
+            return false;
+        };
+        if !response.ctx.input(|i| i.pointer.primary_down()) {
+            self.interactions.dragging_bend = None;
+            return false;
+        }
+
+        self.canvas.set_cursor_icon(egui::CursorIcon::Grabbing);
+
+        // small pointer moves of click don't move point
+        if dragged {
+            let mut target = pointer_pos + offset;
+            if self.snapping.snap_to_grid {
+                target = self.snapping.grid_point(target);
+            }
+
+            let point = &mut self.graph.edge_mut(&id).unwrap().bend_points[index];
+            if *point != target {
+                *point = target;
+                self.record(EditKind::EdgeProperty(id));
+            }
+        }
+
+        true
+    }
+
+    pub fn clear_hovered_edge(&mut self) {
+        self.interactions.hovered_edge = None;
     }
 
     pub fn selected_edge_mut(&mut self) -> Option<&mut Edge> {
         self.interactions
-            .selected_edge_id
+            .selected_edges
+            .primary()
             .map(|id| self.graph.edge_mut(&id).unwrap())
     }
 
     pub fn selected_edge(&self) -> Option<&Edge> {
-        match self.interactions.selected_edge_id {
-            Some(id) => self.graph.edges().get(&id),
-            None => None,
-        }
+        self.interactions
+            .selected_edges
+            .primary()
+            .and_then(|id| self.graph.edges().get(&id))
     }
 
-    pub fn selected_edge_id(&self) -> &Option<EdgeId> {
-        &self.interactions.selected_edge_id
+    /// Primary selected edge, shown in edge editor.
+    pub fn selected_edge_id(&self) -> Option<EdgeId> {
+        self.interactions.selected_edges.primary()
     }
 
     pub fn set_selected_edge_id(&mut self, edge_id: Option<EdgeId>) {
-        self.interactions.selected_edge_id = edge_id;
+        self.interactions.selected_edges.set(edge_id);
+    }
+
+    pub fn selected_edges(&self) -> &Selection<EdgeId> {
+        &self.interactions.selected_edges
+    }
+
+    pub fn selected_edges_mut(&mut self) -> &mut Selection<EdgeId> {
+        &mut self.interactions.selected_edges
     }
 
     pub fn remove_selected_edge(&mut self) {
-        if let Some(selected_id) = self.interactions.selected_edge_id {
+        if let Some(selected_id) = self.selected_edge_id() {
             self.remove_edge(selected_id);
         }
     }
 
     pub fn remove_edge(&mut self, id: EdgeId) {
-        if let Some(selected_id) = self.interactions.selected_edge_id {
-            if id == selected_id {
-                self.interactions.selected_edge_id = None;
-            }
-        }
-
         self.graph.remove_edge(id);
+        self.forget_removed();
+        self.record(EditKind::RemoveEdge);
     }
 
     pub fn edges_ids(&self) -> Vec<EdgeId> {
@@ -290,18 +1100,88 @@ impl GraphWorkspace {
 
     pub fn add_edge(&mut self, start_id: NodeId, end_id: NodeId) {
         self.graph.add_edge(start_id, end_id);
+        self.record(EditKind::AddEdge);
+    }
+}
+
+// clipboard
+impl GraphWorkspace {
+    /// Text of selected nodes with edges between them, None if nothing is selected.
+    pub fn copy_selection(&self) -> Option<String> {
+        let subgraph = self.graph.subgraph(self.interactions.selected_nodes.iter());
+
+        (!subgraph.is_empty()).then(|| subgraph.to_text())
+    }
+
+    /// Copy selected nodes and remove them.
+    pub fn cut_selection(&mut self) -> Option<String> {
+        let text = self.copy_selection()?;
+        self.remove_selected_nodes();
+
+        Some(text)
+    }
+
+    /// Paste subgraph from text and select it.
+    /// Return false if text isn't subgraph, error if its values are invalid.
+    pub fn paste(&mut self, text: &str) -> Result<bool, GraphEditorError> {
+        let Some(subgraph) = Subgraph::from_text(text) else {
+            return Ok(false);
+        };
+        if subgraph.is_empty() {
+            return Ok(false);
+        }
+        // text may come from another program, so it is checked like opened file
+        let errors = subgraph.validate();
+        if !errors.is_empty() {
+            return Err(GraphEditorError::InvalidPaste(errors));
+        }
+
+        // shift pasted nodes until they don't cover existing ones (e.g. after repeated paste)
+        let positions: Vec<_> = self.graph.nodes().values().map(|n| n.position).collect();
+        let mut offset = Vec2::splat(PASTE_OFFSET);
+        while subgraph
+            .positions()
+            .any(|position| positions.contains(&(position + offset)))
+        {
+            offset += Vec2::splat(PASTE_OFFSET);
+        }
+
+        let (node_ids, edge_ids) = self.graph.insert_subgraph(&subgraph, offset);
+
+        self.interactions.selected_nodes.clear();
+        for id in node_ids {
+            self.interactions.selected_nodes.insert(id);
+        }
+        self.interactions.selected_edges.clear();
+        for id in edge_ids {
+            self.interactions.selected_edges.insert(id);
+        }
+
+        self.record(EditKind::Paste);
+        Ok(true)
     }
 }
 
 // comment lines
 impl GraphWorkspace {
     pub fn handle_comment_draw(&mut self, stroke: Stroke) {
-        self.canvas
-            .handle_comment_draw(stroke, &mut self.comment_lines);
+        if self
+            .canvas
+            .handle_comment_draw(stroke, &mut self.comment_lines)
+        {
+            self.record(EditKind::CommentDraw);
+        }
     }
 
     pub fn handle_comment_erase(&mut self) {
-        self.canvas.handle_comment_erase(&mut self.comment_lines);
+        if self.canvas.handle_comment_erase(&mut self.comment_lines) {
+            self.record(EditKind::CommentErase);
+        }
+    }
+
+    pub fn clear_comment_lines(&mut self) {
+        self.comment_lines.clear();
+        self.record(EditKind::CommentsClear);
     }
 
     pub fn comment_lines(&mut self) -> &mut CommentsGroup {
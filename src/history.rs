@@ -0,0 +1,227 @@
+use std::collections::VecDeque;
+
+use crate::{
+    consts::HISTORY_LIMIT,
+    graph::{edge::EdgeId, NodeId},
+};
+
+/// Kind of edit recorded in history.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EditKind {
+    AddNode,
+    RemoveNode,
+    AddEdge,
+    RemoveEdge,
+    /// Selected nodes and edges removed together.
+    RemoveSelection,
+    Paste,
+    MoveNode(NodeId),
+    NodeProperty(NodeId),
+    EdgeProperty(EdgeId),
+    ReconnectEdge,
+    CommentDraw,
+    CommentErase,
+    CommentsClear,
+    Layout,
+    Align,
+    /// Change of styling rule at index.
+    StyleRule(usize),
+    /// Styling rule added, removed or moved.
+    StyleRules,
+}
+
+impl EditKind {
+    /// Continuous edits (dragging, DragValue scrubbing, typing, drawing)
+    /// are merged into one history step.
+    fn is_mergeable(&self) -> bool {
+        matches!(
+            self,
+            EditKind::MoveNode(_)
+                | EditKind::NodeProperty(_)
+                | EditKind::EdgeProperty(_)
+                | EditKind::StyleRule(_)
+                | EditKind::CommentDraw
+                | EditKind::CommentErase
+                | EditKind::Layout
+        )
+    }
+
+    /// Edits which can change what styling rules match,
+    /// moving nodes and drawing comments can't.
+    pub fn affects_styling(&self) -> bool {
+        !matches!(
+            self,
+            EditKind::MoveNode(_)
+                | EditKind::CommentDraw
+                | EditKind::CommentErase
+                | EditKind::CommentsClear
+                | EditKind::Layout
+                | EditKind::Align
+        )
+    }
+
+    /// Edits which can move nodes or edges, or change their size,
+    /// only drawing comments can't.
+    pub fn affects_routing(&self) -> bool {
+        !matches!(
+            self,
+            EditKind::CommentDraw | EditKind::CommentErase | EditKind::CommentsClear
+        )
+    }
+}
+
+/// Bounded undo/redo history of snapshots.
+///
+/// `current` is the last recorded state, `undo_stack` keeps states before it
+/// and `redo_stack` keeps undone states.
+pub struct History<T: Clone> {
+    undo_stack: VecDeque<T>,
+    redo_stack: Vec<T>,
+    current: T,
+    last_edit: Option<EditKind>,
+    limit: usize,
+}
+
+impl<T: Clone + Default> Default for History<T> {
+    fn default() -> Self {
+        Self::new(T::default(), HISTORY_LIMIT)
+    }
+}
+
+impl<T: Clone> History<T> {
+    pub fn new(state: T, limit: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            current: state,
+            last_edit: None,
+            limit,
+        }
+    }
+
+    /// Record state after an edit.
+    /// Edit is merged with previous one if both are the same mergeable kind
+    /// and history was not sealed between them.
+    pub fn record(&mut self, kind: EditKind, state: &T) {
+        if self.last_edit == Some(kind) && kind.is_mergeable() {
+            self.current = state.clone();
+            return;
+        }
+
+        let previous = std::mem::replace(&mut self.current, state.clone());
+        self.undo_stack.push_back(previous);
+
+        if self.undo_stack.len() > self.limit {
+            self.undo_stack.pop_front();
+        }
+
+        self.redo_stack.clear();
+        self.last_edit = Some(kind);
+    }
+
+    /// Finish current continuous edit, so next edit starts a new step.
+    pub fn seal(&mut self) {
+        self.last_edit = None;
+    }
+
+    /// Drop all steps and start history from `state`.
+    pub fn reset(&mut self, state: T) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.current = state;
+        self.last_edit = None;
+    }
+
+    /// Step back, return state to restore.
+    pub fn undo(&mut self) -> Option<&T> {
+        let previous = self.undo_stack.pop_back()?;
+        let current = std::mem::replace(&mut self.current, previous);
+        self.redo_stack.push(current);
+        self.last_edit = None;
+
+        Some(&self.current)
+    }
+
+    /// Step forward, return state to restore.
+    pub fn redo(&mut self) -> Option<&T> {
+        let next = self.redo_stack.pop()?;
+        let current = std::mem::replace(&mut self.current, next);
+        self.undo_stack.push_back(current);
+        self.last_edit = None;
+
+        Some(&self.current)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Undo everything, return restored states from newest to oldest.
+    fn undo_all(history: &mut History<i32>) -> Vec<i32> {
+        let mut states = Vec::new();
+        while let Some(state) = history.undo() {
+            states.push(*state);
+        }
+        states
+    }
+
+    #[test]
+    fn same_mergeable_edits_are_merged() {
+        let mut history = History::new(0, HISTORY_LIMIT);
+        let node = NodeId(1);
+        history.record(EditKind::MoveNode(node), &1);
+        history.record(EditKind::MoveNode(node), &2);
+        history.record(EditKind::MoveNode(NodeId(2)), &3);
+        history.record(EditKind::AddNode, &4);
+        history.record(EditKind::AddNode, &5);
+
+        assert_eq!(undo_all(&mut history), [4, 3, 2, 0]);
+    }
+
+    #[test]
+    fn seal_breaks_merge() {
+        let mut history = History::new(0, HISTORY_LIMIT);
+        history.record(EditKind::Layout, &1);
+        history.seal();
+        history.record(EditKind::Layout, &2);
+
+        assert_eq!(undo_all(&mut history), [1, 0]);
+    }
+
+    #[test]
+    fn limit_drops_oldest_step() {
+        let mut history = History::new(0, HISTORY_LIMIT);
+        for state in 1..=HISTORY_LIMIT as i32 + 1 {
+            history.record(EditKind::AddNode, &state);
+        }
+
+        let states = undo_all(&mut history);
+        assert_eq!(states.len(), HISTORY_LIMIT);
+        assert_eq!(states.last(), Some(&1));
+    }
+
+    #[test]
+    fn record_clears_redo() {
+        let mut history = History::new(0, HISTORY_LIMIT);
+        history.record(EditKind::AddNode, &1);
+        history.record(EditKind::AddEdge, &2);
+
+        assert_eq!(history.undo(), Some(&1));
+        assert_eq!(history.redo(), Some(&2));
+        assert_eq!(history.undo(), Some(&1));
+        history.record(EditKind::RemoveNode, &3);
+
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert_eq!(undo_all(&mut history), [1, 0]);
+    }
+}
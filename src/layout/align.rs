@@ -0,0 +1,266 @@
+use eframe::egui::{Pos2, Vec2};
+
+use crate::{
+    consts::DEFAULT_GRID_SPACING,
+    graph::{Graph, NodeId},
+};
+
+/// Nodes closer than this to their new place are left as they are,
+/// so rounding errors don't count as change.
+const MOVE_TOLERANCE: f32 = 1e-3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Centers on one vertical line.
+    CenterX,
+    /// Centers on one horizontal line.
+    CenterY,
+}
+
+impl Alignment {
+    pub const ALL: [Alignment; 6] = [
+        Alignment::Left,
+        Alignment::CenterX,
+        Alignment::Right,
+        Alignment::Top,
+        Alignment::CenterY,
+        Alignment::Bottom,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Alignment::Left => "Left",
+            Alignment::Right => "Right",
+            Alignment::Top => "Top",
+            Alignment::Bottom => "Bottom",
+            Alignment::CenterX => "Center ↕",
+            Alignment::CenterY => "Center ↔",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Background grid and snapping of dragged nodes.
+#[derive(Clone, Copy, Debug)]
+pub struct Snapping {
+    pub show_grid: bool,
+    /// Dragged node is put on grid crossings.
+    pub snap_to_grid: bool,
+    pub grid_spacing: f32,
+    /// Dragged node sticks to lines through centers of other nodes.
+    pub smart_guides: bool,
+}
+
+impl Default for Snapping {
+    fn default() -> Self {
+        Self {
+            show_grid: false,
+            snap_to_grid: false,
+            grid_spacing: DEFAULT_GRID_SPACING,
+            smart_guides: true,
+        }
+    }
+}
+
+impl Snapping {
+    /// Closest grid crossing.
+    pub fn grid_point(&self, position: Pos2) -> Pos2 {
+        (position / self.grid_spacing).round() * self.grid_spacing
+    }
+}
+
+/// Lines through centers of nodes which dragged node is aligned with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Guides {
+    /// X of vertical line.
+    pub vertical: Option<f32>,
+    /// Y of horizontal line.
+    pub horizontal: Option<f32>,
+}
+
+impl Guides {
+    /// Guides of nodes (except `moving` ones) with center closer than `tolerance`
+    /// to position on some axis, closest node wins.
+    pub fn find(graph: &Graph, moving: &[NodeId], position: Pos2, tolerance: f32) -> Self {
+        let closest = |coordinate: fn(Pos2) -> f32| {
+            graph
+                .nodes()
+                .iter()
+                .filter(|(id, _)| !moving.contains(id))
+                .map(|(_, node)| coordinate(node.position))
+                .map(|line| (line, (line - coordinate(position)).abs()))
+                .filter(|(_, distance)| *distance <= tolerance)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(line, _)| line)
+        };
+
+        Self {
+            vertical: closest(|pos| pos.x),
+            horizontal: closest(|pos| pos.y),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertical.is_none() && self.horizontal.is_none()
+    }
+
+    /// Position moved onto guides.
+    pub fn snap(&self, position: Pos2) -> Pos2 {
+        Pos2::new(
+            self.vertical.unwrap_or(position.x),
+            self.horizontal.unwrap_or(position.y),
+        )
+    }
+}
+
+/// Move nodes to common border or center line of their bounding box.
+/// Return true if some node moved.
+pub fn align(graph: &mut Graph, ids: &[NodeId], alignment: Alignment) -> bool {
+    let nodes: Vec<_> = ids.iter().filter_map(|id| graph.node(id)).collect();
+    if nodes.len() < 2 {
+        return false;
+    }
+
+    let (mut left, mut right) = (f32::INFINITY, f32::NEG_INFINITY);
+    let (mut top, mut bottom) = (f32::INFINITY, f32::NEG_INFINITY);
+    for node in nodes {
+        let half_size = node.half_size();
+        left = left.min(node.position.x - half_size.x);
+        right = right.max(node.position.x + half_size.x);
+        top = top.min(node.position.y - half_size.y);
+        bottom = bottom.max(node.position.y + half_size.y);
+    }
+
+    let mut moved = false;
+    for id in ids {
+        let Some(node) = graph.node_mut(id) else {
+            continue;
+        };
+
+        let half_size = node.half_size();
+        let (coordinate, target) = match alignment {
+            Alignment::Left => (&mut node.position.x, left + half_size.x),
+            Alignment::Right => (&mut node.position.x, right - half_size.x),
+            Alignment::Top => (&mut node.position.y, top + half_size.y),
+            Alignment::Bottom => (&mut node.position.y, bottom - half_size.y),
+            Alignment::CenterX => (&mut node.position.x, (left + right) / 2.0),
+            Alignment::CenterY => (&mut node.position.y, (top + bottom) / 2.0),
+        };
+        moved |= move_to(coordinate, target);
+    }
+    moved
+}
+
+/// Make gaps between borders of neighbour nodes equal along axis,
+/// first and last nodes stay in place. Return true if some node moved.
+pub fn distribute(graph: &mut Graph, ids: &[NodeId], axis: Axis) -> bool {
+    let coordinate = |vector: Vec2| match axis {
+        Axis::Horizontal => vector.x,
+        Axis::Vertical => vector.y,
+    };
+
+    let mut nodes: Vec<_> = ids
+        .iter()
+        .filter_map(|id| {
+            graph.node(id).map(|node| {
+                (
+                    *id,
+                    coordinate(node.position.to_vec2()),
+                    coordinate(node.half_size()),
+                )
+            })
+        })
+        .collect();
+    if nodes.len() < 3 {
+        return false;
+    }
+    nodes.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let (_, first, first_size) = nodes[0];
+    let (_, last, last_size) = nodes[nodes.len() - 1];
+    let occupied: f32 = nodes.iter().map(|(_, _, size)| 2.0 * size).sum();
+    let gap = ((last + last_size) - (first - first_size) - occupied) / (nodes.len() - 1) as f32;
+
+    let mut moved = false;
+    let mut border = first - first_size;
+    for (id, _, size) in nodes {
+        let center = border + size;
+        border += 2.0 * size + gap;
+
+        let position = &mut graph.node_mut(&id).unwrap().position;
+        let coordinate = match axis {
+            Axis::Horizontal => &mut position.x,
+            Axis::Vertical => &mut position.y,
+        };
+        moved |= move_to(coordinate, center);
+    }
+    moved
+}
+
+/// Set coordinate to target unless it is already there, return true if it moved.
+fn move_to(coordinate: &mut f32, target: f32) -> bool {
+    if (*coordinate - target).abs() <= MOVE_TOLERANCE {
+        return false;
+    }
+    *coordinate = target;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_utils::graph;
+
+    fn positions(graph: &Graph, ids: &[NodeId]) -> Vec<Pos2> {
+        ids.iter().map(|id| graph.nodes()[id].position).collect()
+    }
+
+    #[test]
+    fn align_moves_nodes_to_common_border() {
+        let (mut graph, ids, _) = graph(3, &[]);
+        for (id, position) in ids.iter().zip([(0.0, 0.0), (50.0, 30.0), (20.0, -40.0)]) {
+            graph.node_mut(id).unwrap().position = position.into();
+        }
+
+        assert!(align(&mut graph, &ids, Alignment::Left));
+        for position in positions(&graph, &ids) {
+            assert_eq!(position.x, 0.0);
+        }
+        assert_eq!(positions(&graph, &ids)[2].y, -40.0);
+
+        assert!(!align(&mut graph, &ids, Alignment::Left));
+        assert!(!align(&mut graph, &ids, Alignment::CenterX));
+        assert!(align(&mut graph, &ids, Alignment::CenterY));
+        assert!(!align(&mut graph, &ids[..1], Alignment::Top));
+    }
+
+    #[test]
+    fn distribute_makes_gaps_equal() {
+        let (mut graph, ids, _) = graph(4, &[]);
+        for (id, x) in ids.iter().zip([0.0, 90.0, 10.0, 300.0]) {
+            graph.node_mut(id).unwrap().position = Pos2::new(x, 0.0);
+        }
+
+        assert!(distribute(&mut graph, &ids, Axis::Horizontal));
+        let xs = positions(&graph, &ids).into_iter().map(|p| p.x);
+        for (x, expected) in xs.zip([0.0, 200.0, 100.0, 300.0]) {
+            assert!(
+                (x - expected).abs() < MOVE_TOLERANCE,
+                "{x} isn't {expected}"
+            );
+        }
+
+        assert!(!distribute(&mut graph, &ids, Axis::Horizontal));
+        assert!(!distribute(&mut graph, &ids, Axis::Vertical));
+        assert!(!distribute(&mut graph, &ids[..2], Axis::Horizontal));
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::{BTreeSet, HashMap};
+
+use eframe::egui::{Pos2, Vec2};
+
+use crate::{
+    consts::{DEFAULT_LAYOUT_EDGE_LENGTH, LAYOUT_GRAVITY},
+    graph::{Graph, NodeId},
+};
+
+/// Steps of layout run at once, it usually settles much earlier.
+const MAX_ITERATIONS: usize = 500;
+/// Layout stops when nodes move less than this.
+pub const SETTLED_DISPLACEMENT: f32 = 0.1;
+/// Decrease of temperature after every step.
+pub const COOLING: f32 = 0.97;
+
+/// Fruchterman-Reingold spring embedder: nodes repel each other,
+/// edges pull connected nodes together.
+#[derive(Clone, Copy, Debug)]
+pub struct ForceLayout {
+    /// Preferred distance between borders of connected nodes.
+    pub edge_length: f32,
+}
+
+impl Default for ForceLayout {
+    fn default() -> Self {
+        Self {
+            edge_length: DEFAULT_LAYOUT_EDGE_LENGTH,
+        }
+    }
+}
+
+impl ForceLayout {
+    /// Temperature of continuous layout after graph was changed.
+    pub fn continuous_temperature(&self) -> f32 {
+        self.edge_length / 10.0
+    }
+
+    /// Final positions of nodes, graph itself is not changed.
+    /// Pinned and `fixed` nodes stay in place.
+    pub fn run(&self, graph: &Graph, fixed: &[NodeId]) -> HashMap<NodeId, Pos2> {
+        let mut graph = graph.clone();
+        let mut temperature = self.edge_length;
+
+        for _ in 0..MAX_ITERATIONS {
+            let displacement = self.step(&mut graph, fixed, temperature);
+            if displacement < SETTLED_DISPLACEMENT {
+                break;
+            }
+            temperature *= COOLING;
+        }
+
+        graph
+            .nodes()
+            .iter()
+            .map(|(id, node)| (*id, node.position))
+            .collect()
+    }
+
+    /// Move nodes once, every node moves at most by temperature.
+    /// Return the largest move.
+    pub fn step(&self, graph: &mut Graph, fixed: &[NodeId], temperature: f32) -> f32 {
+        let mut ids: Vec<_> = graph.nodes().keys().copied().collect();
+        ids.sort();
+        if ids.is_empty() {
+            return 0.0;
+        }
+
+        let positions: Vec<_> = ids.iter().map(|id| graph.nodes()[id].position).collect();
+        let sizes: Vec<_> = ids
+            .iter()
+            .map(|id| graph.nodes()[id].half_size().max_elem())
+            .collect();
+        let index = |id: &NodeId| ids.binary_search(id).ok();
+
+        // multi-edges pull like one edge, loops don't move anything
+        let springs: BTreeSet<_> = graph
+            .edges()
+            .values()
+            .filter(|edge| !edge.is_loop())
+            .filter_map(|edge| {
+                let (start, end) = (index(&edge.start_id)?, index(&edge.end_id)?);
+                Some((start.min(end), start.max(end)))
+            })
+            .collect();
+
+        let k = self.edge_length;
+        let mut forces = vec![Vec2::ZERO; ids.len()];
+
+        for i in 0..ids.len() {
+            for j in i + 1..ids.len() {
+                let (direction, distance) = border_distance(&positions, &sizes, i, j);
+                let force = direction * (k * k / distance);
+                forces[i] += force;
+                forces[j] -= force;
+            }
+        }
+
+        for &(i, j) in &springs {
+            let (direction, distance) = border_distance(&positions, &sizes, i, j);
+            let force = direction * (distance * distance / k);
+            forces[i] -= force;
+            forces[j] += force;
+        }
+
+        // weak pull to center keeps unconnected parts together
+        let center = positions
+            .iter()
+            .fold(Vec2::ZERO, |sum, p| sum + p.to_vec2())
+            / positions.len() as f32;
+        for (force, position) in forces.iter_mut().zip(&positions) {
+            *force += (center - position.to_vec2()) * LAYOUT_GRAVITY;
+        }
+
+        let mut max_displacement: f32 = 0.0;
+        for (i, id) in ids.iter().enumerate() {
+            let node = graph.node_mut(id).unwrap();
+            if node.pinned || fixed.contains(id) {
+                continue;
+            }
+
+            let length = forces[i].length();
+            if length > 0.0 {
+                let displacement = forces[i] / length * length.min(temperature);
+                node.position += displacement;
+                max_displacement = max_displacement.max(displacement.length());
+            }
+        }
+
+        max_displacement
+    }
+}
+
+/// Direction from node j to node i and distance between their borders (at least 1).
+fn border_distance(positions: &[Pos2], sizes: &[f32], i: usize, j: usize) -> (Vec2, f32) {
+    let delta = positions[i] - positions[j];
+    let length = delta.length();
+
+    let direction = if length > f32::EPSILON {
+        delta / length
+    } else {
+        // nodes at the same place are pushed apart in stable direction
+        Vec2::angled(i as f32 + j as f32)
+    };
+
+    (direction, (length - sizes[i] - sizes[j]).max(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{edge::Edge, Node};
+
+    fn add_node(graph: &mut Graph, x: f32, y: f32) -> NodeId {
+        graph.insert_node(Node::new(String::new(), Pos2::new(x, y)))
+    }
+
+    #[test]
+    fn pinned_and_fixed_nodes_stay_in_place() {
+        let mut graph = Graph::new();
+        let pinned = add_node(&mut graph, 0.0, 0.0);
+        let fixed = add_node(&mut graph, 5.0, 0.0);
+        let free = add_node(&mut graph, 0.0, 5.0);
+        graph.node_mut(&pinned).unwrap().pinned = true;
+        graph.insert_edge(Edge::new(pinned, fixed));
+        graph.insert_edge(Edge::new(fixed, free));
+
+        let positions = ForceLayout::default().run(&graph, &[fixed]);
+
+        assert_eq!(positions[&pinned], Pos2::new(0.0, 0.0));
+        assert_eq!(positions[&fixed], Pos2::new(5.0, 0.0));
+        assert_ne!(positions[&free], Pos2::new(0.0, 5.0));
+    }
+
+    #[test]
+    fn nodes_at_same_place_are_separated() {
+        let mut graph = Graph::new();
+        let ids: Vec<_> = (0..4).map(|_| add_node(&mut graph, 0.0, 0.0)).collect();
+        graph.insert_edge(Edge::new(ids[0], ids[1]));
+
+        let positions = ForceLayout::default().run(&graph, &[]);
+
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                let gap = positions[a].distance(positions[b])
+                    - graph.nodes()[a].half_size().max_elem()
+                    - graph.nodes()[b].half_size().max_elem();
+                assert!(gap > 1.0, "nodes overlap, gap {gap}");
+            }
+        }
+    }
+}
@@ -0,0 +1,552 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use eframe::egui::{Pos2, Vec2};
+
+use crate::{
+    consts::{DEFAULT_LAYER_SPACING, DEFAULT_NODE_SPACING},
+    graph::{edge::EdgeId, Graph, NodeId},
+};
+
+use super::center_offset;
+
+/// Sweeps of crossing reduction, best order of all sweeps is kept.
+const ORDERING_SWEEPS: usize = 24;
+/// Sweeps of moving nodes towards their neighbours inside of layer.
+const BALANCING_SWEEPS: usize = 8;
+/// Distance between bend points of parallel edges.
+const PARALLEL_EDGE_GAP: f32 = 12.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LayerDirection {
+    #[default]
+    TopToBottom,
+    LeftToRight,
+}
+
+impl LayerDirection {
+    /// Loops point along layers, so they don't cross into neighbour layers.
+    pub fn loop_rotation_angle(self) -> f32 {
+        match self {
+            LayerDirection::TopToBottom => 135.0,
+            LayerDirection::LeftToRight => 45.0,
+        }
+    }
+}
+
+/// Sugiyama layered layout: edges go from one layer to the next one
+/// in `direction`, with as few crossings as possible.
+#[derive(Clone, Copy, Debug)]
+pub struct LayeredLayout {
+    pub direction: LayerDirection,
+    /// Gap between borders of nodes of neighbour layers.
+    pub layer_spacing: f32,
+    /// Gap between borders of nodes in one layer.
+    pub node_spacing: f32,
+}
+
+impl Default for LayeredLayout {
+    fn default() -> Self {
+        Self {
+            direction: LayerDirection::default(),
+            layer_spacing: DEFAULT_LAYER_SPACING,
+            node_spacing: DEFAULT_NODE_SPACING,
+        }
+    }
+}
+
+/// Positions found by layered layout.
+#[derive(Clone, Debug, Default)]
+pub struct LayeredDrawing {
+    pub positions: HashMap<NodeId, Pos2>,
+    /// Bend points of every edge between different nodes,
+    /// empty for single edges between neighbour layers.
+    pub bend_points: HashMap<EdgeId, Vec<Pos2>>,
+}
+
+/// Nodes of layered graph: real nodes go first, then dummy nodes
+/// which split edges crossing several layers.
+struct LayeredGraph {
+    layers: Vec<Vec<usize>>,
+    /// Half of node extent along (x) and across (y) layer, zero for dummy nodes.
+    sizes: Vec<Vec2>,
+    /// Neighbours in previous layer.
+    up: Vec<Vec<usize>>,
+    /// Neighbours in next layer.
+    down: Vec<Vec<usize>>,
+    /// Dummy nodes of every arc from top to bottom.
+    chains: BTreeMap<(usize, usize), Vec<usize>>,
+}
+
+impl LayeredLayout {
+    /// Positions of nodes, centered at center of current drawing,
+    /// with bend points of edges going through dummy nodes.
+    pub fn run(&self, graph: &Graph) -> LayeredDrawing {
+        let mut ids: Vec<_> = graph.nodes().keys().copied().collect();
+        ids.sort();
+        if ids.is_empty() {
+            return LayeredDrawing::default();
+        }
+
+        let arcs = acyclic_arcs(graph, &ids);
+        let edges = edge_arcs(graph, &ids, &arcs);
+        let mut parallel: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for (_, arc, _) in &edges {
+            *parallel.entry(*arc).or_insert(0) += 1;
+        }
+
+        let node_layers = assign_layers(ids.len(), &arcs);
+        let sizes = ids
+            .iter()
+            .map(|id| {
+                let half_size = graph.nodes()[id].half_size();
+                match self.direction {
+                    LayerDirection::TopToBottom => half_size,
+                    LayerDirection::LeftToRight => Vec2::new(half_size.y, half_size.x),
+                }
+            })
+            .collect();
+        let mut layered = LayeredGraph::new(&node_layers, sizes, &parallel);
+        layered.reduce_crossings();
+
+        let along = layered.coordinates(self.node_spacing);
+        let across = layered.layer_coordinates(self.layer_spacing);
+        let point = |x: f32, y: f32| match self.direction {
+            LayerDirection::TopToBottom => Pos2::new(x, y),
+            LayerDirection::LeftToRight => Pos2::new(y, x),
+        };
+
+        let positions: Vec<_> = (0..ids.len())
+            .map(|node| point(along[node], across[node_layers[node]]))
+            .collect();
+        let offset = center_offset(graph, &positions);
+
+        // parallel edges are spread evenly around dummy nodes
+        let mut spread: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut bend_points = HashMap::with_capacity(edges.len());
+        for (id, arc, reversed) in edges {
+            let count = parallel[&arc];
+            let index = spread.entry(arc).or_insert(0);
+            let shift = (*index as f32 - (count - 1) as f32 / 2.0) * PARALLEL_EDGE_GAP;
+            *index += 1;
+
+            let (start, end) = arc;
+            let chain = &layered.chains[&arc];
+            let mut points: Vec<_> = if !chain.is_empty() {
+                chain
+                    .iter()
+                    .zip(node_layers[start] + 1..)
+                    .map(|(&dummy, layer)| point(along[dummy] + shift, across[layer]))
+                    .collect()
+            } else if count > 1 {
+                let x = (along[start] + along[end]) / 2.0 + shift;
+                let y = (across[node_layers[start]] + across[node_layers[end]]) / 2.0;
+                vec![point(x, y)]
+            } else {
+                Vec::new()
+            };
+
+            for point in &mut points {
+                *point += offset;
+            }
+            if reversed {
+                points.reverse();
+            }
+            bend_points.insert(id, points);
+        }
+
+        LayeredDrawing {
+            positions: ids
+                .iter()
+                .zip(positions)
+                .map(|(id, position)| (*id, position + offset))
+                .collect(),
+            bend_points,
+        }
+    }
+}
+
+/// Arcs between indices of `ids` without loops, duplicates and cycles.
+/// Oriented edges keep direction unless they close a cycle,
+/// unoriented edges follow order of oriented ones.
+fn acyclic_arcs(graph: &Graph, ids: &[NodeId]) -> BTreeSet<(usize, usize)> {
+    let index = |id: &NodeId| ids.binary_search(id).ok();
+    let mut oriented = vec![Vec::new(); ids.len()];
+    let mut unoriented = Vec::new();
+
+    let mut edges: Vec<_> = graph.edges().iter().collect();
+    edges.sort_by_key(|(id, _)| id.0);
+    for (_, edge) in edges {
+        let (Some(start), Some(end)) = (index(&edge.start_id), index(&edge.end_id)) else {
+            continue;
+        };
+        if start == end {
+            continue;
+        }
+
+        if edge.oriented {
+            oriented[start].push(end);
+        } else {
+            unoriented.push((start, end));
+        }
+    }
+
+    // depth-first search reverses edges going back to nodes on stack
+    let mut arcs = BTreeSet::new();
+    let mut state = vec![0u8; ids.len()]; // 0 - new, 1 - on stack, 2 - finished
+    let mut finished = Vec::with_capacity(ids.len());
+    for start in 0..ids.len() {
+        if state[start] != 0 {
+            continue;
+        }
+
+        state[start] = 1;
+        let mut stack = vec![(start, 0)];
+        while let Some((node, next_index)) = stack.last_mut() {
+            let node = *node;
+            let Some(&next) = oriented[node].get(*next_index) else {
+                state[node] = 2;
+                finished.push(node);
+                stack.pop();
+                continue;
+            };
+            *next_index += 1;
+
+            match state[next] {
+                0 => {
+                    arcs.insert((node, next));
+                    state[next] = 1;
+                    stack.push((next, 0));
+                }
+                1 => {
+                    arcs.insert((next, node));
+                }
+                _ => {
+                    arcs.insert((node, next));
+                }
+            }
+        }
+    }
+
+    // reversed finishing order is topological order of kept arcs
+    let mut rank = vec![0; ids.len()];
+    for (position, &node) in finished.iter().rev().enumerate() {
+        rank[node] = position;
+    }
+    for (start, end) in unoriented {
+        if rank[start] <= rank[end] {
+            arcs.insert((start, end));
+        } else {
+            arcs.insert((end, start));
+        }
+    }
+
+    arcs
+}
+
+/// Arc of every edge between different nodes in order of creation,
+/// true if edge goes against its arc.
+fn edge_arcs(
+    graph: &Graph,
+    ids: &[NodeId],
+    arcs: &BTreeSet<(usize, usize)>,
+) -> Vec<(EdgeId, (usize, usize), bool)> {
+    let index = |id: &NodeId| ids.binary_search(id).ok();
+
+    let mut edges: Vec<_> = graph
+        .edges()
+        .iter()
+        .filter_map(|(id, edge)| {
+            let (start, end) = (index(&edge.start_id)?, index(&edge.end_id)?);
+            if start == end {
+                None
+            } else if arcs.contains(&(start, end)) {
+                Some((*id, (start, end), false))
+            } else {
+                Some((*id, (end, start), true))
+            }
+        })
+        .collect();
+    edges.sort_by_key(|(id, _, _)| id.0);
+    edges
+}
+
+/// Layer of every node: longest path from sources,
+/// then sources are moved down next to their successors.
+fn assign_layers(node_count: usize, arcs: &BTreeSet<(usize, usize)>) -> Vec<usize> {
+    let mut in_degrees = vec![0; node_count];
+    let mut successors = vec![Vec::new(); node_count];
+    for &(start, end) in arcs {
+        in_degrees[end] += 1;
+        successors[start].push(end);
+    }
+
+    let mut order: Vec<_> = (0..node_count).filter(|&n| in_degrees[n] == 0).collect();
+    let mut remaining = in_degrees.clone();
+    let mut index = 0;
+    while let Some(&node) = order.get(index) {
+        index += 1;
+        for &next in &successors[node] {
+            remaining[next] -= 1;
+            if remaining[next] == 0 {
+                order.push(next);
+            }
+        }
+    }
+
+    let mut layers = vec![0; node_count];
+    for &node in &order {
+        for &next in &successors[node] {
+            layers[next] = layers[next].max(layers[node] + 1);
+        }
+    }
+
+    for &node in order.iter().rev() {
+        if in_degrees[node] == 0 {
+            if let Some(closest) = successors[node].iter().map(|&next| layers[next]).min() {
+                layers[node] = closest - 1;
+            }
+        }
+    }
+
+    layers
+}
+
+impl LayeredGraph {
+    /// Dummy nodes of arc are wide enough for all its parallel edges.
+    fn new(
+        node_layers: &[usize],
+        mut sizes: Vec<Vec2>,
+        parallel: &BTreeMap<(usize, usize), usize>,
+    ) -> Self {
+        let layer_count = node_layers.iter().max().map_or(0, |max| max + 1);
+        let mut layers = vec![Vec::new(); layer_count];
+        for (node, &layer) in node_layers.iter().enumerate() {
+            layers[layer].push(node);
+        }
+
+        let mut up = vec![Vec::new(); sizes.len()];
+        let mut down = vec![Vec::new(); sizes.len()];
+        let mut chains = BTreeMap::new();
+        for (&(start, end), &count) in parallel {
+            // chain of dummy nodes, one for every crossed layer
+            let half_width = (count - 1) as f32 * PARALLEL_EDGE_GAP / 2.0;
+            let mut chain = Vec::new();
+            let mut previous = start;
+            for layer in &mut layers[node_layers[start] + 1..node_layers[end]] {
+                let dummy = sizes.len();
+                sizes.push(Vec2::new(half_width, 0.0));
+                chain.push(dummy);
+                up.push(Vec::new());
+                down.push(Vec::new());
+                layer.push(dummy);
+
+                down[previous].push(dummy);
+                up[dummy].push(previous);
+                previous = dummy;
+            }
+            down[previous].push(end);
+            up[end].push(previous);
+            chains.insert((start, end), chain);
+        }
+
+        Self {
+            layers,
+            sizes,
+            up,
+            down,
+            chains,
+        }
+    }
+
+    /// Barycenter heuristic: node is put at average position of neighbours
+    /// in previous layer, sweeping down and up.
+    fn reduce_crossings(&mut self) {
+        let mut best = self.layers.clone();
+        let mut best_crossings = self.crossings();
+
+        for sweep in 0..ORDERING_SWEEPS {
+            let downwards = sweep % 2 == 0;
+            for step in 1..self.layers.len() {
+                let (layer, fixed, neighbours) = if downwards {
+                    (step, step - 1, &self.up)
+                } else {
+                    let layer = self.layers.len() - 1 - step;
+                    (layer, layer + 1, &self.down)
+                };
+
+                let mut positions = vec![0.0; self.sizes.len()];
+                for (position, &node) in self.layers[fixed].iter().enumerate() {
+                    positions[node] = position as f32;
+                }
+
+                let keys: HashMap<usize, f32> = self.layers[layer]
+                    .iter()
+                    .enumerate()
+                    .map(|(position, &node)| {
+                        let neighbours = &neighbours[node];
+                        let key = if neighbours.is_empty() {
+                            position as f32
+                        } else {
+                            neighbours.iter().map(|&n| positions[n]).sum::<f32>()
+                                / neighbours.len() as f32
+                        };
+                        (node, key)
+                    })
+                    .collect();
+                self.layers[layer].sort_by(|a, b| keys[a].total_cmp(&keys[b]));
+            }
+
+            let crossings = self.crossings();
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best = self.layers.clone();
+            }
+        }
+
+        self.layers = best;
+    }
+
+    fn crossings(&self) -> usize {
+        let mut crossings = 0;
+
+        for pair in self.layers.windows(2) {
+            let mut positions = vec![0; self.sizes.len()];
+            for layer in pair {
+                for (position, &node) in layer.iter().enumerate() {
+                    positions[node] = position;
+                }
+            }
+
+            let segments: Vec<_> = pair[0]
+                .iter()
+                .flat_map(|&node| self.down[node].iter().map(move |&next| (node, next)))
+                .map(|(node, next)| (positions[node], positions[next]))
+                .collect();
+            for (i, a) in segments.iter().enumerate() {
+                for b in &segments[i + 1..] {
+                    if (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1) {
+                        crossings += 1;
+                    }
+                }
+            }
+        }
+
+        crossings
+    }
+
+    /// Coordinate of every node along its layer.
+    fn coordinates(&self, spacing: f32) -> Vec<f32> {
+        let gap = |a: usize, b: usize| self.sizes[a].x + self.sizes[b].x + spacing;
+
+        let mut coordinates = vec![0.0; self.sizes.len()];
+        for layer in &self.layers {
+            for pair in layer.windows(2) {
+                coordinates[pair[1]] = coordinates[pair[0]] + gap(pair[0], pair[1]);
+            }
+        }
+
+        for sweep in 0..2 * BALANCING_SWEEPS {
+            let neighbours = if sweep % 2 == 0 { &self.up } else { &self.down };
+
+            for layer in &self.layers {
+                let desired: Vec<f32> = layer
+                    .iter()
+                    .map(|&node| {
+                        let neighbours = &neighbours[node];
+                        if neighbours.is_empty() {
+                            coordinates[node]
+                        } else {
+                            neighbours.iter().map(|&n| coordinates[n]).sum::<f32>()
+                                / neighbours.len() as f32
+                        }
+                    })
+                    .collect();
+
+                // closest placements to desired pushing to the right and to the left,
+                // their average keeps gaps too
+                let mut right = desired.clone();
+                for i in 1..layer.len() {
+                    right[i] = right[i].max(right[i - 1] + gap(layer[i - 1], layer[i]));
+                }
+                let mut left = desired;
+                for i in (0..layer.len().saturating_sub(1)).rev() {
+                    left[i] = left[i].min(left[i + 1] - gap(layer[i], layer[i + 1]));
+                }
+
+                for (i, &node) in layer.iter().enumerate() {
+                    coordinates[node] = (right[i] + left[i]) / 2.0;
+                }
+            }
+        }
+
+        coordinates
+    }
+
+    /// Coordinate of every layer across layers, thick layers get more space.
+    fn layer_coordinates(&self, spacing: f32) -> Vec<f32> {
+        let half_thickness: Vec<f32> = self
+            .layers
+            .iter()
+            .map(|layer| layer.iter().map(|&n| self.sizes[n].y).fold(0.0, f32::max))
+            .collect();
+
+        let mut coordinates = vec![0.0; self.layers.len()];
+        for layer in 1..self.layers.len() {
+            coordinates[layer] = coordinates[layer - 1]
+                + half_thickness[layer - 1]
+                + spacing
+                + half_thickness[layer];
+        }
+
+        coordinates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_utils::graph;
+
+    #[test]
+    fn long_edge_bends_beside_middle_layer() {
+        let (graph, nodes, edges) = graph(3, &[(0, 1), (1, 2), (0, 2)]);
+
+        let drawing = LayeredLayout::default().run(&graph);
+
+        let middle = drawing.positions[&nodes[1]];
+        let bends = &drawing.bend_points[&edges[2]];
+        assert_eq!(bends.len(), 1);
+        assert_eq!(bends[0].y, middle.y);
+        assert!((bends[0].x - middle.x).abs() > graph.nodes()[&nodes[1]].half_size().x);
+        assert!(drawing.bend_points[&edges[0]].is_empty());
+    }
+
+    #[test]
+    fn reversed_edge_bends_go_from_its_start() {
+        let (graph, nodes, edges) = graph(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+
+        let drawing = LayeredLayout::default().run(&graph);
+
+        let bends = &drawing.bend_points[&edges[3]];
+        assert_eq!(bends.len(), 2);
+        assert_eq!(bends[0].y, drawing.positions[&nodes[2]].y);
+        assert_eq!(bends[1].y, drawing.positions[&nodes[1]].y);
+    }
+
+    #[test]
+    fn parallel_edges_are_spread() {
+        let (graph, _, edges) = graph(3, &[(0, 1), (1, 2), (0, 2), (0, 2), (0, 1), (0, 1)]);
+
+        let drawing = LayeredLayout::default().run(&graph);
+
+        for pair in [[2, 3], [4, 5]] {
+            let first = &drawing.bend_points[&edges[pair[0]]];
+            let second = &drawing.bend_points[&edges[pair[1]]];
+            assert_eq!((first.len(), second.len()), (1, 1));
+            assert_eq!(first[0].y, second[0].y);
+            assert!((first[0].x - second[0].x).abs() >= PARALLEL_EDGE_GAP - 0.01);
+        }
+        // the third parallel edge between first layers is spread as well
+        assert!(!drawing.bend_points[&edges[0]].is_empty());
+    }
+}
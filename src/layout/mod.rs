@@ -0,0 +1,108 @@
+//! Automatic placement of nodes.
+
+pub mod align;
+pub mod force;
+pub mod layered;
+pub mod presets;
+
+use std::collections::HashMap;
+
+use eframe::egui::{Pos2, Rect, Vec2};
+
+use crate::{
+    consts::LAYOUT_ANIMATION_TIME,
+    graph::{edge::EdgeId, EdgeRouting, Graph, NodeId},
+};
+
+/// Move which puts center of positions at center of current drawing.
+fn center_offset(graph: &Graph, positions: &[Pos2]) -> Vec2 {
+    let current: Vec<_> = graph.nodes().values().map(|node| node.position).collect();
+    Rect::from_points(&current).center() - Rect::from_points(positions).center()
+}
+
+/// Positions of nodes moved so that their center is at center of current drawing.
+fn keep_center(graph: &Graph, ids: &[NodeId], positions: &[Pos2]) -> HashMap<NodeId, Pos2> {
+    let offset = center_offset(graph, positions);
+
+    ids.iter()
+        .zip(positions)
+        .map(|(id, position)| (*id, *position + offset))
+        .collect()
+}
+
+/// Smooth move of nodes from their current positions to layout positions.
+pub struct LayoutAnimation {
+    starts: HashMap<NodeId, Pos2>,
+    targets: HashMap<NodeId, Pos2>,
+    /// Start and target bend points of edges.
+    bends: HashMap<EdgeId, (Vec<Pos2>, Vec<Pos2>)>,
+    /// From 0 (start) to 1 (target).
+    progress: f32,
+}
+
+impl LayoutAnimation {
+    pub fn new(graph: &Graph, targets: HashMap<NodeId, Pos2>) -> Self {
+        let starts = targets
+            .keys()
+            .filter_map(|id| graph.node(id).map(|node| (*id, node.position)))
+            .collect();
+
+        Self {
+            starts,
+            targets,
+            bends: HashMap::new(),
+            progress: 0.0,
+        }
+    }
+
+    /// Also move bend points of edges, edges which get some become polylines.
+    pub fn with_bend_points(mut self, graph: &Graph, targets: HashMap<EdgeId, Vec<Pos2>>) -> Self {
+        self.bends = targets
+            .into_iter()
+            .filter_map(|(id, target)| {
+                let edge = graph.edge(&id)?;
+                let start = if edge.is_polyline() && edge.bend_points.len() == target.len() {
+                    edge.bend_points.clone()
+                } else {
+                    // new bend points start on straight line between nodes
+                    let start = graph.node(&edge.start_id)?.position;
+                    let end = graph.node(&edge.end_id)?.position;
+                    let parts = (target.len() + 1) as f32;
+                    (1..=target.len())
+                        .map(|i| start.lerp(end, i as f32 / parts))
+                        .collect()
+                };
+                Some((id, (start, target)))
+            })
+            .collect();
+        self
+    }
+
+    /// Move nodes by time passed since last frame.
+    /// Return true when nodes reached targets.
+    pub fn update(&mut self, graph: &mut Graph, dt: f32) -> bool {
+        self.progress = (self.progress + dt / LAYOUT_ANIMATION_TIME).min(1.0);
+        // smoothstep: slow start and slow end
+        let t = self.progress * self.progress * (3.0 - 2.0 * self.progress);
+
+        for (id, target) in &self.targets {
+            if let (Some(start), Some(node)) = (self.starts.get(id), graph.node_mut(id)) {
+                node.position = start.lerp(*target, t);
+            }
+        }
+        for (id, (starts, targets)) in &self.bends {
+            if let Some(edge) = graph.edge_mut(id) {
+                if !targets.is_empty() {
+                    edge.routing = EdgeRouting::Polyline;
+                }
+                edge.bend_points = starts
+                    .iter()
+                    .zip(targets)
+                    .map(|(start, target)| start.lerp(*target, t))
+                    .collect();
+            }
+        }
+
+        self.progress >= 1.0
+    }
+}
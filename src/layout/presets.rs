@@ -0,0 +1,481 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap, VecDeque},
+    f32::consts::TAU,
+};
+
+use eframe::egui::{Pos2, Vec2};
+
+use crate::{
+    consts::{DEFAULT_LAYER_SPACING, DEFAULT_NODE_SPACING},
+    error::GraphEditorError,
+    graph::{Graph, NodeId},
+};
+
+use super::keep_center;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeStyle {
+    /// Levels go from top to bottom.
+    #[default]
+    Tidy,
+    /// Levels are circles around root.
+    Radial,
+}
+
+/// Deterministic layouts of simple shapes.
+/// Nodes never overlap: gaps are measured between node borders.
+#[derive(Clone, Copy, Debug)]
+pub struct PresetLayout {
+    /// Gap between neighbour nodes.
+    pub node_spacing: f32,
+    /// Gap between tree levels and between bipartite columns.
+    pub level_spacing: f32,
+    /// Nodes of circle go in order of labels instead of creation order.
+    pub circle_by_label: bool,
+    pub tree_style: TreeStyle,
+}
+
+impl Default for PresetLayout {
+    fn default() -> Self {
+        Self {
+            node_spacing: DEFAULT_NODE_SPACING,
+            level_spacing: DEFAULT_LAYER_SPACING,
+            circle_by_label: false,
+            tree_style: TreeStyle::default(),
+        }
+    }
+}
+
+impl PresetLayout {
+    /// All nodes on one circle, first node is at the top.
+    pub fn circle(&self, graph: &Graph) -> HashMap<NodeId, Pos2> {
+        let mut ids = sorted_ids(graph);
+        if self.circle_by_label {
+            ids.sort_by(|a, b| label_order(&graph.nodes()[a].label, &graph.nodes()[b].label));
+        }
+        let sizes = sizes(graph, &ids);
+
+        // distance between centers of every node and the next one
+        let gaps: Vec<f32> = (0..ids.len())
+            .map(|i| {
+                sizes[i].max_elem() + sizes[(i + 1) % ids.len()].max_elem() + self.node_spacing
+            })
+            .collect();
+        let perimeter: f32 = gaps.iter().sum();
+
+        // chord between neighbours has to be as long as gap
+        let radius = if ids.len() < 2 {
+            0.0
+        } else {
+            gaps.iter()
+                .map(|gap| gap / (2.0 * (TAU / 2.0 * gap / perimeter).sin()))
+                .fold(0.0, f32::max)
+        };
+
+        let mut angle = -TAU / 4.0;
+        let positions: Vec<_> = gaps
+            .iter()
+            .map(|gap| {
+                let position = Pos2::ZERO + Vec2::angled(angle) * radius;
+                angle += TAU * gap / perimeter;
+                position
+            })
+            .collect();
+
+        keep_center(graph, &ids, &positions)
+    }
+
+    /// Nodes in rows of square grid in creation order.
+    pub fn grid(&self, graph: &Graph) -> HashMap<NodeId, Pos2> {
+        let ids = sorted_ids(graph);
+        let columns = (ids.len() as f32).sqrt().ceil().max(1.0) as usize;
+        let largest = sizes(graph, &ids).into_iter().fold(Vec2::ZERO, Vec2::max);
+        let cell = 2.0 * largest.max_elem() + self.node_spacing;
+
+        let positions: Vec<_> = (0..ids.len())
+            .map(|i| Pos2::new((i % columns) as f32, (i / columns) as f32) * cell)
+            .collect();
+
+        keep_center(graph, &ids, &positions)
+    }
+
+    /// Breadth-first search tree from root, edges are walked both ways.
+    /// Nodes unreachable from root make more trees next to it.
+    pub fn tree(&self, graph: &Graph, root: NodeId) -> HashMap<NodeId, Pos2> {
+        let ids = sorted_ids(graph);
+        let Ok(root) = ids.binary_search(&root) else {
+            return HashMap::new();
+        };
+        let sizes = sizes(graph, &ids);
+        let neighbours = neighbours(graph, &ids);
+
+        let mut roots = Vec::new();
+        let mut children = vec![Vec::new(); ids.len()];
+        let mut depths = vec![0; ids.len()];
+        let mut visited = vec![false; ids.len()];
+        // nodes of all trees in breadth-first order
+        let mut order = Vec::with_capacity(ids.len());
+
+        for start in std::iter::once(root).chain(0..ids.len()) {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            roots.push(start);
+
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                order.push(node);
+                for &next in &neighbours[node] {
+                    if !visited[next] {
+                        visited[next] = true;
+                        depths[next] = depths[node] + 1;
+                        children[node].push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        // width of subtree is wide enough for its node and all children side by side
+        let mut widths = vec![0.0; ids.len()];
+        for &node in order.iter().rev() {
+            let children_width = row_width(&children[node], &widths, self.node_spacing);
+            widths[node] = children_width.max(2.0 * sizes[node].x);
+        }
+
+        // position of every node inside of its row: [start, end) of subtree
+        let total_width = row_width(&roots, &widths, self.node_spacing);
+        let mut spans = vec![(0.0, 0.0); ids.len()];
+        let spacing = self.node_spacing;
+        place_row(&roots, (0.0, total_width), &widths, spacing, &mut spans);
+        for &node in &order {
+            place_row(&children[node], spans[node], &widths, spacing, &mut spans);
+        }
+        let middles: Vec<f32> = spans
+            .iter()
+            .map(|(start, end)| (start + end) / 2.0)
+            .collect();
+
+        // radial trees of forest are put around common center
+        let level_offset = match self.tree_style {
+            TreeStyle::Radial if roots.len() > 1 => 1,
+            _ => 0,
+        };
+        let levels: Vec<_> = depths.iter().map(|depth| depth + level_offset).collect();
+        let angles: Vec<_> = middles.iter().map(|m| TAU * m / total_width).collect();
+        let level_positions = match self.tree_style {
+            TreeStyle::Tidy => self.tidy_levels(&levels, &sizes),
+            TreeStyle::Radial => self.radial_levels(&levels, &sizes, &angles),
+        };
+
+        let positions: Vec<_> = (0..ids.len())
+            .map(|node| {
+                let level_position = level_positions[levels[node]];
+                match self.tree_style {
+                    TreeStyle::Tidy => Pos2::new(middles[node], level_position),
+                    TreeStyle::Radial => Pos2::ZERO + Vec2::angled(angles[node]) * level_position,
+                }
+            })
+            .collect();
+
+        keep_center(graph, &ids, &positions)
+    }
+
+    /// Distance of every tree level from the first one, thick levels get more space.
+    fn tidy_levels(&self, levels: &[usize], sizes: &[Vec2]) -> Vec<f32> {
+        let level_count = levels.iter().max().map_or(0, |max| max + 1);
+        let mut half_thickness = vec![0.0_f32; level_count];
+        for (node, &level) in levels.iter().enumerate() {
+            half_thickness[level] = half_thickness[level].max(sizes[node].y);
+        }
+
+        let mut positions = vec![0.0; level_count];
+        for level in 1..level_count {
+            positions[level] = positions[level - 1]
+                + half_thickness[level - 1]
+                + self.level_spacing
+                + half_thickness[level];
+        }
+        positions
+    }
+
+    /// Radius of every circle of radial tree. Circles are apart by level spacing
+    /// and every circle is big enough to keep node spacing between its nodes.
+    fn radial_levels(&self, levels: &[usize], sizes: &[Vec2], angles: &[f32]) -> Vec<f32> {
+        let level_count = levels.iter().max().map_or(0, |max| max + 1);
+        // nodes are turned around center, so they reach as far as their corners
+        let reaches: Vec<f32> = sizes.iter().map(|size| size.length()).collect();
+        let mut circles: Vec<Vec<usize>> = vec![Vec::new(); level_count];
+        for (node, &level) in levels.iter().enumerate() {
+            circles[level].push(node);
+        }
+        let half_thickness: Vec<f32> = circles
+            .iter()
+            .map(|circle| circle.iter().map(|&n| reaches[n]).fold(0.0, f32::max))
+            .collect();
+
+        let mut radii = vec![0.0; level_count];
+        for level in 1..level_count {
+            let mut radius = radii[level - 1]
+                + half_thickness[level - 1]
+                + self.level_spacing
+                + half_thickness[level];
+
+            // nodes at some angle from each other are 2·r·sin(angle / 2) apart
+            let circle = &circles[level];
+            for (i, &a) in circle.iter().enumerate() {
+                for &b in &circle[i + 1..] {
+                    let angle = (angles[b] - angles[a]).abs();
+                    let angle = angle.min(TAU - angle);
+                    let distance = reaches[a] + reaches[b] + self.node_spacing;
+                    radius = radius.max(distance / (2.0 * (angle / 2.0).sin()));
+                }
+            }
+            radii[level] = radius;
+        }
+        radii
+    }
+
+    /// Two columns with every edge going from one column to the other.
+    /// Fails with odd cycle when graph is not bipartite.
+    pub fn bipartite(&self, graph: &Graph) -> Result<HashMap<NodeId, Pos2>, GraphEditorError> {
+        let ids = sorted_ids(graph);
+        let label = |node: usize| graph.nodes()[&ids[node]].label.clone();
+
+        if let Some(edge) = graph.edges().values().find(|edge| edge.is_loop()) {
+            let label = graph.nodes()[&edge.start_id].label.clone();
+            return Err(GraphEditorError::NotBipartite(vec![label]));
+        }
+
+        let neighbours = neighbours(graph, &ids);
+        let mut sides: Vec<Option<bool>> = vec![None; ids.len()];
+        let mut parents = vec![None; ids.len()];
+
+        for start in 0..ids.len() {
+            if sides[start].is_some() {
+                continue;
+            }
+            sides[start] = Some(false);
+
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                let side = sides[node].unwrap();
+                for &next in &neighbours[node] {
+                    match sides[next] {
+                        None => {
+                            sides[next] = Some(!side);
+                            parents[next] = Some(node);
+                            queue.push_back(next);
+                        }
+                        Some(next_side) if next_side == side => {
+                            let cycle = odd_cycle(&parents, node, next);
+                            return Err(GraphEditorError::NotBipartite(
+                                cycle.into_iter().map(label).collect(),
+                            ));
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+
+        let left: Vec<_> = (0..ids.len())
+            .filter(|&n| sides[n] == Some(false))
+            .collect();
+        let mut right: Vec<_> = (0..ids.len()).filter(|&n| sides[n] == Some(true)).collect();
+
+        // right nodes go next to their neighbours to avoid crossings
+        let mut left_positions = vec![0.0; ids.len()];
+        for (position, &node) in left.iter().enumerate() {
+            left_positions[node] = position as f32;
+        }
+        let key = |node: usize| {
+            let neighbours = &neighbours[node];
+            if neighbours.is_empty() {
+                f32::INFINITY
+            } else {
+                neighbours.iter().map(|&n| left_positions[n]).sum::<f32>() / neighbours.len() as f32
+            }
+        };
+        right.sort_by(|&a, &b| key(a).total_cmp(&key(b)));
+
+        let sizes = sizes(graph, &ids);
+        let half_width = |column: &[usize]| column.iter().map(|&n| sizes[n].x).fold(0.0, f32::max);
+        let right_x = half_width(&left) + self.level_spacing + half_width(&right);
+
+        let mut positions = vec![Pos2::ZERO; ids.len()];
+        for (column, x) in [(&left, 0.0), (&right, right_x)] {
+            let mut y = -column
+                .iter()
+                .map(|&n| 2.0 * sizes[n].y + self.node_spacing)
+                .sum::<f32>()
+                / 2.0;
+            for &node in column.iter() {
+                positions[node] = Pos2::new(x, y + sizes[node].y);
+                y += 2.0 * sizes[node].y + self.node_spacing;
+            }
+        }
+
+        Ok(keep_center(graph, &ids, &positions))
+    }
+}
+
+fn sorted_ids(graph: &Graph) -> Vec<NodeId> {
+    let mut ids: Vec<_> = graph.nodes().keys().copied().collect();
+    ids.sort();
+    ids
+}
+
+/// Half sizes of nodes.
+fn sizes(graph: &Graph, ids: &[NodeId]) -> Vec<Vec2> {
+    ids.iter().map(|id| graph.nodes()[id].half_size()).collect()
+}
+
+/// Neighbours of every node by indices of `ids`, edges are walked both ways.
+fn neighbours(graph: &Graph, ids: &[NodeId]) -> Vec<Vec<usize>> {
+    let index = |id: &NodeId| ids.binary_search(id).ok();
+    let mut neighbours = vec![BTreeSet::new(); ids.len()];
+
+    for edge in graph.edges().values().filter(|edge| !edge.is_loop()) {
+        if let (Some(start), Some(end)) = (index(&edge.start_id), index(&edge.end_id)) {
+            neighbours[start].insert(end);
+            neighbours[end].insert(start);
+        }
+    }
+
+    neighbours
+        .into_iter()
+        .map(|set| set.into_iter().collect())
+        .collect()
+}
+
+/// Numeric labels are compared as numbers and go before others.
+fn label_order(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f32>(), b.trim().parse::<f32>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+fn row_width(nodes: &[usize], widths: &[f32], spacing: f32) -> f32 {
+    let gaps = nodes.len().saturating_sub(1) as f32 * spacing;
+    nodes.iter().map(|&node| widths[node]).sum::<f32>() + gaps
+}
+
+/// Put subtrees side by side in the middle of span [start, end).
+fn place_row(
+    nodes: &[usize],
+    (start, end): (f32, f32),
+    widths: &[f32],
+    spacing: f32,
+    spans: &mut [(f32, f32)],
+) {
+    let mut position = (start + end - row_width(nodes, widths, spacing)) / 2.0;
+    for &node in nodes {
+        spans[node] = (position, position + widths[node]);
+        position += widths[node] + spacing;
+    }
+}
+
+/// Cycle closed by edge between nodes of the same side: paths from both nodes
+/// to their common ancestor in search tree.
+fn odd_cycle(parents: &[Option<usize>], a: usize, b: usize) -> Vec<usize> {
+    let path_to_root = |mut node: usize| {
+        let mut path = vec![node];
+        while let Some(parent) = parents[node] {
+            path.push(parent);
+            node = parent;
+        }
+        path
+    };
+
+    let (mut a_path, mut b_path) = (path_to_root(a), path_to_root(b));
+    // drop common part above ancestor, ancestor itself stays in a_path
+    while a_path.len() > 1
+        && b_path.len() > 1
+        && a_path[a_path.len() - 2] == b_path[b_path.len() - 2]
+    {
+        a_path.pop();
+        b_path.pop();
+    }
+    b_path.pop();
+
+    a_path.reverse();
+    a_path.extend(b_path);
+    a_path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_utils::graph;
+
+    fn assert_no_overlaps(graph: &Graph, positions: &HashMap<NodeId, Pos2>) {
+        let ids = sorted_ids(graph);
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                let distance = positions[a].distance(positions[b]);
+                let reach =
+                    graph.nodes()[a].half_size().length() + graph.nodes()[b].half_size().length();
+                assert!(distance >= reach, "nodes {a:?} and {b:?} overlap");
+            }
+        }
+    }
+
+    #[test]
+    fn radial_tree_levels_grow_and_keep_nodes_apart() {
+        // root - child - 21 leaves, then root with many leaves and long chain
+        let mut edges = vec![(0, 1)];
+        edges.extend((2..23).map(|leaf| (1, leaf)));
+        let (first, _, _) = graph(23, &edges);
+        let mut edges: Vec<_> = (1..16).map(|leaf| (0, leaf)).collect();
+        edges.extend((16..22).map(|node| (node - 1, node)));
+        let (second, _, _) = graph(22, &edges);
+
+        let layout = PresetLayout {
+            tree_style: TreeStyle::Radial,
+            ..Default::default()
+        };
+        for (graph, leaf) in [(first, 22), (second, 21)] {
+            let ids = sorted_ids(&graph);
+            let positions = layout.tree(&graph, ids[0]);
+
+            assert_no_overlaps(&graph, &positions);
+            let root = positions[&ids[0]];
+            let path = [ids[0], ids[1], ids[leaf]];
+            for pair in path.windows(2) {
+                assert!(root.distance(positions[&pair[0]]) < root.distance(positions[&pair[1]]));
+            }
+        }
+    }
+
+    #[test]
+    fn bipartite_reports_odd_cycle() {
+        let (graph, _, _) = graph(6, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (4, 5)]);
+
+        let Err(GraphEditorError::NotBipartite(labels)) = PresetLayout::default().bipartite(&graph)
+        else {
+            panic!("odd cycle was laid out");
+        };
+
+        let mut labels = labels;
+        labels.sort();
+        assert_eq!(labels, ["0", "1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn bipartite_puts_edges_across_columns() {
+        let (graph, ids, _) = graph(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+
+        let positions = PresetLayout::default().bipartite(&graph).unwrap();
+
+        assert_eq!(positions[&ids[0]].x, positions[&ids[2]].x);
+        assert_eq!(positions[&ids[1]].x, positions[&ids[3]].x);
+        assert_ne!(positions[&ids[0]].x, positions[&ids[1]].x);
+        assert_no_overlaps(&graph, &positions);
+    }
+}
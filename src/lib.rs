@@ -1,12 +1,24 @@
+pub mod algorithm_editor;
 pub mod app;
 pub mod canvas;
+pub mod cli;
 pub mod comment_line;
 pub mod consts;
+pub mod document;
 pub mod edge_editor;
 pub mod edges_table;
 pub mod error;
+pub mod format;
 pub mod graph;
 pub mod graph_workspace;
+pub mod history;
+pub mod layout;
 pub mod node_editor;
+pub mod path_editor;
+pub mod player;
+pub mod render;
+pub mod scene;
+pub mod selection;
+pub mod styling_editor;
 pub mod toast;
 pub mod utils;
@@ -1,15 +1,44 @@
+use std::process::ExitCode;
+
 use eframe::egui;
-use graph_editor_egui::app::GraphEditor;
+use graph_editor_egui::{
+    app::GraphEditor,
+    cli::{self, Command, USAGE},
+};
+
+fn main() -> ExitCode {
+    match Command::parse(std::env::args().skip(1)) {
+        Ok(Command::Gui(file_path)) => run_gui(file_path),
+        Ok(command) => cli::run(command),
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
 
-fn main() -> eframe::Result {
+fn run_gui(file_path: Option<std::path::PathBuf>) -> ExitCode {
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1280.0, 720.0]),
         ..Default::default()
     };
 
-    eframe::run_native(
+    let result = eframe::run_native(
         "Graph editor",
         native_options,
-        Box::new(|_cc| Ok(Box::<GraphEditor>::default())),
-    )
+        Box::new(|cc| {
+            Ok(Box::new(match file_path {
+                Some(file_path) => GraphEditor::with_file(&cc.egui_ctx, file_path),
+                None => GraphEditor::default(),
+            }))
+        }),
+    );
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -3,9 +3,15 @@ use eframe::egui::{
 };
 
 use crate::{
-    consts::{MAX_NODE_LABEL_SIZE, MAX_NODE_SIZE, MIN_NODE_LABEL_SIZE, MIN_NODE_SIZE, UI_SPACE},
+    consts::{
+        MAX_NODE_ASPECT_RATIO, MAX_NODE_LABEL_SIZE, MAX_NODE_SIZE, MIN_NODE_ASPECT_RATIO,
+        MIN_NODE_LABEL_SIZE, MIN_NODE_SIZE, UI_SPACE,
+    },
     graph::NodeShape,
     graph_workspace::GraphWorkspace,
+    history::EditKind,
+    layout::align::{Alignment, Axis},
+    utils::widgets::attributes_editor,
 };
 
 pub struct NodeEditor;
@@ -22,22 +28,63 @@ impl NodeEditor {
 
         ui.separator();
 
-        if let Some(selected_node) = graph_workspace.selected_node_mut() {
+        if let Some(selected_id) = graph_workspace.selected_node_id() {
+            let selected_count = graph_workspace.selected_nodes().len();
+            if selected_count > 1 {
+                ui.label(format!(
+                    "{selected_count} nodes selected, color, size, shape, width and pin are applied to all"
+                ));
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Align:");
+                    for alignment in Alignment::ALL {
+                        if ui.button(alignment.name()).clicked() {
+                            graph_workspace.align_selected_nodes(alignment);
+                        }
+                    }
+                });
+                ui.add_enabled_ui(selected_count > 2, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Distribute:");
+                        if ui.button("Horizontally").clicked() {
+                            graph_workspace.distribute_selected_nodes(Axis::Horizontal);
+                        }
+                        if ui.button("Vertically").clicked() {
+                            graph_workspace.distribute_selected_nodes(Axis::Vertical);
+                        }
+                    });
+                });
+                ui.separator();
+            }
+
+            let selected_node = graph_workspace.selected_node_mut().unwrap();
+            let (color, size, shape, aspect_ratio, pinned) = (
+                selected_node.color,
+                selected_node.size,
+                selected_node.shape,
+                selected_node.aspect_ratio,
+                selected_node.pinned,
+            );
+            let mut changed = false;
+
             ui.horizontal(|ui| {
-                color_edit_button_rgba(
+                changed |= color_edit_button_rgba(
                     ui,
                     &mut selected_node.color,
                     egui::color_picker::Alpha::Opaque,
-                );
+                )
+                .changed();
 
                 ui.add_space(UI_SPACE);
 
-                ui.add(
-                    DragValue::new(&mut selected_node.size)
-                        .range(MIN_NODE_SIZE..=MAX_NODE_SIZE)
-                        .speed(0.2)
-                        .prefix("Size: "),
-                );
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut selected_node.size)
+                            .range(MIN_NODE_SIZE..=MAX_NODE_SIZE)
+                            .speed(0.2)
+                            .prefix("Size: "),
+                    )
+                    .changed();
             });
 
             ui.separator();
@@ -45,25 +92,52 @@ impl NodeEditor {
             ui.horizontal(|ui| {
                 ui.label("Shape:");
                 ui.add_space(UI_SPACE);
-                ui.selectable_value(&mut selected_node.shape, NodeShape::Circle, "⏺");
+                egui::ComboBox::from_id_salt("node_shape")
+                    .selected_text(selected_node.shape.name())
+                    .show_ui(ui, |ui| {
+                        for shape in NodeShape::ALL {
+                            changed |= ui
+                                .selectable_value(&mut selected_node.shape, shape, shape.name())
+                                .changed();
+                        }
+                    });
+
                 ui.add_space(UI_SPACE);
-                ui.selectable_value(&mut selected_node.shape, NodeShape::Square, "⏹");
+
+                changed |= ui
+                    .add_enabled(
+                        selected_node.shape.has_aspect_ratio(),
+                        DragValue::new(&mut selected_node.aspect_ratio)
+                            .range(MIN_NODE_ASPECT_RATIO..=MAX_NODE_ASPECT_RATIO)
+                            .speed(0.01)
+                            .prefix("Width: ")
+                            .suffix("×"),
+                    )
+                    .on_hover_text("Width relative to height")
+                    .changed();
             });
 
+            changed |= ui
+                .checkbox(&mut selected_node.pinned, "Pinned")
+                .on_hover_text("Automatic layout doesn't move pinned nodes")
+                .changed();
+
             ui.separator();
 
             ui.vertical(|ui| {
                 ui.horizontal(|ui| {
                     ui.label("Label: ");
-                    ui.text_edit_singleline(&mut selected_node.label);
+                    changed |= ui.text_edit_singleline(&mut selected_node.label).changed();
                 });
 
                 ui.add_space(UI_SPACE);
 
-                ui.checkbox(
-                    &mut selected_node.label_size_matches_node_size,
-                    "Match node size",
-                );
+                changed |= ui
+                    .checkbox(
+                        &mut selected_node.label_size_matches_node_size,
+                        "Match node size",
+                    )
+                    .changed();
 
                 if selected_node.label_size_matches_node_size {
                     ui.disable();
@@ -71,25 +145,64 @@ impl NodeEditor {
 
                 ui.add_space(UI_SPACE);
 
-                ui.add(
-                    DragValue::new(&mut selected_node.label_size)
-                        .range(MIN_NODE_LABEL_SIZE..=MAX_NODE_LABEL_SIZE)
-                        .speed(0.2)
-                        .prefix("Label size: "),
-                );
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut selected_node.label_size)
+                            .range(MIN_NODE_LABEL_SIZE..=MAX_NODE_LABEL_SIZE)
+                            .speed(0.2)
+                            .prefix("Label size: "),
+                    )
+                    .changed();
             });
 
+            ui.separator();
+            ui.label("Attributes:");
+            changed |= attributes_editor(ui, "node_attributes", &mut selected_node.attributes);
+
+            if changed {
+                // apply changed properties of primary node to whole selection
+                let (new_color, new_size, new_shape, new_aspect_ratio, new_pinned) = (
+                    selected_node.color,
+                    selected_node.size,
+                    selected_node.shape,
+                    selected_node.aspect_ratio,
+                    selected_node.pinned,
+                );
+
+                graph_workspace.update_selected_nodes(|node| {
+                    if new_color != color {
+                        node.color = new_color;
+                    }
+                    if new_size != size {
+                        node.size = new_size;
+                    }
+                    if new_shape != shape {
+                        node.shape = new_shape;
+                    }
+                    if new_aspect_ratio != aspect_ratio {
+                        node.aspect_ratio = new_aspect_ratio;
+                    }
+                    if new_pinned != pinned {
+                        node.pinned = new_pinned;
+                    }
+                });
+                graph_workspace.record(EditKind::NodeProperty(selected_id));
+            }
+
             ui.separator();
 
             ui.with_layout(Layout::right_to_left(egui::Align::TOP), |ui| {
+                let text = if selected_count > 1 {
+                    format!("Delete {selected_count} nodes")
+                } else {
+                    "Delete".to_owned()
+                };
+
                 if ui
-                    .add(
-                        Button::new(RichText::new("Delete").color(Color32::WHITE))
-                            .fill(Color32::RED),
-                    )
+                    .add(Button::new(RichText::new(text).color(Color32::WHITE)).fill(Color32::RED))
                     .clicked()
                 {
-                    graph_workspace.remove_selected_node();
+                    graph_workspace.remove_selected_nodes();
                 }
             });
         } else {
@@ -0,0 +1,147 @@
+use eframe::egui::{self, RichText};
+
+use crate::{
+    consts::UI_SPACE,
+    graph::{
+        path::{EdgeCost, GraphPath},
+        trace::Trace,
+        NodeId,
+    },
+    graph_workspace::GraphWorkspace,
+    player::Player,
+    utils::widgets::node_picker,
+};
+
+/// Shortest path search between two nodes.
+#[derive(Default)]
+pub struct PathEditor {
+    start: Option<NodeId>,
+    end: Option<NodeId>,
+    edge_cost: EdgeCost,
+    /// Path is searched again every frame, so it follows edits of graph.
+    searching: bool,
+    /// Replay of search, path is shown when it is finished.
+    player: Option<Player>,
+}
+
+impl PathEditor {
+    pub fn name(&self) -> &'static str {
+        "Shortest Path"
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, graph_workspace: &mut GraphWorkspace) {
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new(self.name()).size(24.0));
+        });
+
+        ui.separator();
+
+        // forget removed nodes
+        self.start = self.start.filter(|id| graph_workspace.node(id).is_some());
+        self.end = self.end.filter(|id| graph_workspace.node(id).is_some());
+
+        egui::Grid::new("path_nodes").num_columns(2).show(ui, |ui| {
+            for (name, node) in [("From", &mut self.start), ("To", &mut self.end)] {
+                ui.label(name);
+                node_picker(ui, name, graph_workspace, node);
+                ui.end_row();
+            }
+        });
+
+        ui.add_space(UI_SPACE);
+
+        let selected: Vec<_> = graph_workspace.selected_nodes().iter().copied().collect();
+        if ui
+            .add_enabled(
+                selected.len() == 2,
+                egui::Button::new("Use two selected nodes"),
+            )
+            .on_disabled_hover_text("Shift-click two nodes, the first one is start")
+            .clicked()
+        {
+            self.start = Some(selected[0]);
+            self.end = Some(selected[1]);
+        }
+
+        ui.separator();
+
+        ui.label("Edge cost");
+        ui.radio_value(&mut self.edge_cost, EdgeCost::Weight, "Weight");
+        ui.radio_value(&mut self.edge_cost, EdgeCost::Label, "Number in label")
+            .on_hover_text("Weight is used for edges without number in label");
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let can_search = self.start.is_some() && self.end.is_some();
+            if ui
+                .add_enabled(can_search, egui::Button::new("Find path"))
+                .clicked()
+            {
+                self.searching = true;
+                self.player = None;
+            }
+            if ui
+                .add_enabled(can_search, egui::Button::new("Animate"))
+                .clicked()
+            {
+                self.searching = true;
+                self.player = Some(Player::new(self.search_trace(graph_workspace)));
+            }
+            if ui.button("Clear").clicked() {
+                self.searching = false;
+                self.player = None;
+            }
+        });
+
+        let (Some(start), Some(end), true) = (self.start, self.end, self.searching) else {
+            return;
+        };
+
+        ui.add_space(UI_SPACE);
+
+        if let Some(player) = &mut self.player {
+            player.ui(ui, graph_workspace);
+            graph_workspace.set_overlay(Some(player.overlay()));
+
+            if !player.is_finished() {
+                return;
+            }
+            ui.separator();
+        }
+
+        match graph_workspace.shortest_path(start, end, self.edge_cost, &mut Trace::default()) {
+            Ok(path) => {
+                ui.label(RichText::new(format!("Total cost: {}", path.cost)).strong());
+                ui.label(path_text(graph_workspace, &path));
+
+                graph_workspace.set_highlighted_path(Some(path));
+            }
+            Err(err) => {
+                ui.colored_label(ui.visuals().error_fg_color, err.message());
+            }
+        }
+    }
+}
+
+impl PathEditor {
+    fn search_trace(&self, graph_workspace: &GraphWorkspace) -> Trace {
+        let mut trace = Trace::default();
+
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            // errors are shown after replay
+            let _ = graph_workspace.shortest_path(start, end, self.edge_cost, &mut trace);
+        }
+
+        trace
+    }
+}
+
+fn path_text(graph_workspace: &GraphWorkspace, path: &GraphPath) -> String {
+    path.nodes
+        .iter()
+        .filter_map(|id| graph_workspace.node(id))
+        .map(|node| node.label.as_str())
+        .collect::<Vec<_>>()
+        .join(" ➡ ")
+}
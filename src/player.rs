@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+
+use eframe::egui::{self, RichText, Slider};
+
+use crate::{
+    consts::{
+        MAX_PLAYER_SPEED, MIN_PLAYER_SPEED, TRACE_CURRENT_COLOR, TRACE_FRONTIER_COLOR,
+        TRACE_TREE_COLOR, TRACE_VISITED_COLOR, UI_SPACE,
+    },
+    graph::{
+        edge::EdgeId,
+        trace::{Step, Trace},
+        NodeId,
+    },
+    graph_workspace::GraphWorkspace,
+    scene::ColorMap,
+};
+
+/// Replays recorded algorithm trace step by step.
+pub struct Player {
+    trace: Trace,
+    /// Number of already replayed steps.
+    position: usize,
+    playing: bool,
+    /// Steps per second.
+    speed: f32,
+    /// Time since last step while playing.
+    elapsed: f32,
+}
+
+impl Player {
+    pub fn new(trace: Trace) -> Self {
+        Self {
+            trace,
+            position: 0,
+            playing: true,
+            speed: 2.0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.trace.len()
+    }
+
+    pub fn step_forward(&mut self) {
+        self.position = (self.position + 1).min(self.trace.len());
+    }
+
+    pub fn step_back(&mut self) {
+        self.position = self.position.saturating_sub(1);
+    }
+
+    /// Advance playback by frame time.
+    fn update(&mut self, ctx: &egui::Context) {
+        if !self.playing {
+            return;
+        }
+        if self.is_finished() {
+            self.playing = false;
+            return;
+        }
+
+        self.elapsed += ctx.input(|i| i.stable_dt);
+        while self.elapsed >= 1.0 / self.speed && !self.is_finished() {
+            self.elapsed -= 1.0 / self.speed;
+            self.step_forward();
+        }
+
+        ctx.request_repaint();
+    }
+
+    /// Playback controls, current step and known distances.
+    pub fn ui(&mut self, ui: &mut egui::Ui, graph_workspace: &GraphWorkspace) {
+        self.update(ui.ctx());
+
+        ui.horizontal(|ui| {
+            if ui.button("⏮").on_hover_text("To start").clicked() {
+                self.position = 0;
+            }
+            if ui.button("⏴").on_hover_text("Step back").clicked() {
+                self.playing = false;
+                self.step_back();
+            }
+            let play = if self.playing { "⏸" } else { "▶" };
+            if ui.button(play).on_hover_text("Play/pause").clicked() {
+                if self.is_finished() {
+                    self.position = 0;
+                }
+                self.playing = !self.playing;
+                self.elapsed = 0.0;
+            }
+            if ui.button("⏵").on_hover_text("Step forward").clicked() {
+                self.playing = false;
+                self.step_forward();
+            }
+            if ui.button("⏭").on_hover_text("To end").clicked() {
+                self.playing = false;
+                self.position = self.trace.len();
+            }
+        });
+
+        ui.add(
+            Slider::new(&mut self.speed, MIN_PLAYER_SPEED..=MAX_PLAYER_SPEED)
+                .logarithmic(true)
+                .text("steps/s"),
+        );
+
+        ui.add_space(UI_SPACE);
+
+        ui.label(format!("Step {} of {}", self.position, self.trace.len()));
+        let description = match self.position.checked_sub(1) {
+            Some(index) => describe(&self.trace.steps[index], graph_workspace),
+            None => "Not started".to_owned(),
+        };
+        ui.label(RichText::new(description).strong());
+
+        let state = self.state();
+        if !state.frontier.is_empty() {
+            let frontier: Vec<_> = state
+                .frontier
+                .iter()
+                .map(|id| label(graph_workspace, id))
+                .collect();
+            ui.label(format!("Frontier: {}", frontier.join(", ")));
+        }
+
+        if !state.distances.is_empty() {
+            ui.add_space(UI_SPACE);
+            egui::Grid::new("trace_distances")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (id, distance) in &state.distances {
+                        ui.label(label(graph_workspace, id));
+                        ui.label(distance.to_string());
+                        ui.end_row();
+                    }
+                });
+        }
+    }
+
+    /// State after replayed steps.
+    fn state(&self) -> TraceState {
+        let mut state = TraceState::default();
+
+        for step in &self.trace.steps[..self.position] {
+            match *step {
+                Step::Push(node) => state.frontier.push(node),
+                Step::Pop(node) => state.frontier.retain(|id| *id != node),
+                Step::Visit(node) => state.visited.push(node),
+                Step::Examine(_) => {}
+                Step::Relax {
+                    edge,
+                    node,
+                    distance,
+                } => {
+                    state.tree_edges.insert(node, edge);
+                    state.distances.insert(node, distance);
+                }
+            }
+        }
+
+        state
+    }
+
+    /// Highlight colors of replayed steps, last step is highlighted as current.
+    pub fn overlay(&self) -> ColorMap {
+        let state = self.state();
+        let mut overlay = ColorMap::default();
+
+        for id in &state.visited {
+            overlay.nodes.insert(*id, TRACE_VISITED_COLOR);
+        }
+        for id in &state.frontier {
+            overlay.nodes.insert(*id, TRACE_FRONTIER_COLOR);
+        }
+        for id in state.tree_edges.values() {
+            overlay.edges.insert(*id, TRACE_TREE_COLOR);
+        }
+
+        match self
+            .position
+            .checked_sub(1)
+            .map(|index| self.trace.steps[index])
+        {
+            Some(Step::Push(node) | Step::Pop(node) | Step::Visit(node)) => {
+                overlay.nodes.insert(node, TRACE_CURRENT_COLOR);
+            }
+            Some(Step::Examine(edge)) => {
+                overlay.edges.insert(edge, TRACE_CURRENT_COLOR);
+            }
+            Some(Step::Relax { edge, node, .. }) => {
+                overlay.edges.insert(edge, TRACE_CURRENT_COLOR);
+                overlay.nodes.insert(node, TRACE_CURRENT_COLOR);
+            }
+            None => {}
+        }
+
+        overlay
+    }
+}
+
+#[derive(Default)]
+struct TraceState {
+    frontier: Vec<NodeId>,
+    visited: Vec<NodeId>,
+    /// Edge of last relaxation of node.
+    tree_edges: BTreeMap<NodeId, EdgeId>,
+    distances: BTreeMap<NodeId, f32>,
+}
+
+fn label(graph_workspace: &GraphWorkspace, id: &NodeId) -> String {
+    graph_workspace
+        .node(id)
+        .map_or_else(|| format!("#{}", id.0), |node| node.label.clone())
+}
+
+fn describe(step: &Step, graph_workspace: &GraphWorkspace) -> String {
+    let edge_text = |id| match graph_workspace.edge(id) {
+        Some(edge) => format!(
+            "{} ➡ {}",
+            label(graph_workspace, &edge.start_id),
+            label(graph_workspace, &edge.end_id)
+        ),
+        None => "removed edge".to_owned(),
+    };
+
+    match step {
+        Step::Push(node) => format!("Add {} to frontier", label(graph_workspace, node)),
+        Step::Pop(node) => format!("Take {} from frontier", label(graph_workspace, node)),
+        Step::Visit(node) => format!("Visit {}", label(graph_workspace, node)),
+        Step::Examine(edge) => format!("Check edge {}, nothing new", edge_text(edge)),
+        Step::Relax {
+            edge,
+            node,
+            distance,
+        } => format!(
+            "Reach {} by edge {} with distance {distance}",
+            label(graph_workspace, node),
+            edge_text(edge)
+        ),
+    }
+}
@@ -0,0 +1,343 @@
+//! Offscreen rendering of graph to image, without window and GPU.
+//!
+//! Shapes are the same as on canvas, they are tessellated by epaint
+//! and triangles are rasterized in software.
+
+use std::path::Path;
+
+use eframe::{
+    egui::{Color32, Pos2, Rect, Vec2},
+    epaint::{
+        text::{FontDefinitions, Fonts},
+        ClippedShape, Mesh, Primitive, TessellationOptions, Tessellator, Vertex,
+    },
+};
+use image::RgbaImage;
+
+use crate::{
+    comment_line::group::CommentsGroup,
+    consts::{DEFAULT_RENDER_SCALE, MAX_RENDER_PIXELS, POINTS_PER_INCH, RENDER_MARGIN},
+    error::GraphEditorError,
+    graph::Graph,
+    scene,
+};
+
+/// Largest side of font atlas texture.
+const MAX_FONT_TEXTURE_SIDE: usize = 8192;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RenderOptions {
+    /// Pixels per canvas point.
+    pub scale: f32,
+    /// Background color, may be transparent.
+    pub background: Color32,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            scale: DEFAULT_RENDER_SCALE,
+            background: Color32::WHITE,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Scale for given resolution, canvas point is 1/72 inch.
+    pub fn scale_from_dpi(dpi: f32) -> f32 {
+        dpi / POINTS_PER_INCH
+    }
+}
+
+/// Render graph and comment lines to image, cropped to drawing with margin.
+/// Fail if image would have more than `MAX_RENDER_PIXELS` pixels.
+pub fn render(
+    graph: &Graph,
+    comment_lines: &CommentsGroup,
+    options: &RenderOptions,
+) -> Result<RgbaImage, GraphEditorError> {
+    let scale = options.scale;
+    let fonts = Fonts::new(scale, MAX_FONT_TEXTURE_SIDE, FontDefinitions::default());
+
+    let shapes = scene::shapes(graph, None, comment_lines, &fonts);
+
+    let mut bounds = shapes.iter().fold(Rect::NOTHING, |bounds, shape| {
+        bounds.union(shape.visual_bounding_rect())
+    });
+    if !bounds.is_positive() {
+        bounds = Rect::from_min_size(Pos2::ZERO, Vec2::splat(1.0));
+    }
+    let bounds = bounds.expand(RENDER_MARGIN);
+
+    let (width, height) = image_size(bounds.size() * scale)?;
+    let mut pixmap = Pixmap::new(width, height, options.background);
+
+    let shapes = shapes
+        .into_iter()
+        .map(|shape| ClippedShape {
+            clip_rect: Rect::EVERYTHING,
+            shape,
+        })
+        .collect();
+
+    // font atlas is complete only after all text was laid out
+    let atlas = fonts.texture_atlas();
+    let (font_texture, prepared_discs) = {
+        let atlas = atlas.lock();
+        (
+            Texture::from_font_image(atlas.image()),
+            atlas.prepared_discs(),
+        )
+    };
+
+    let mut tessellator = Tessellator::new(
+        scale,
+        TessellationOptions::default(),
+        fonts.font_image_size(),
+        prepared_discs,
+    );
+
+    for clipped in tessellator.tessellate_shapes(shapes) {
+        if let Primitive::Mesh(mesh) = clipped.primitive {
+            pixmap.draw_mesh(&mesh, &font_texture, bounds.min, scale);
+        }
+    }
+
+    Ok(pixmap.into_image())
+}
+
+/// Size of image in whole pixels, checked against `MAX_RENDER_PIXELS`.
+fn image_size(size: Vec2) -> Result<(u32, u32), GraphEditorError> {
+    let width = f64::from(size.x).ceil();
+    let height = f64::from(size.y).ceil();
+
+    let pixels = width * height;
+    if !pixels.is_finite() || pixels > MAX_RENDER_PIXELS as f64 {
+        return Err(GraphEditorError::ImageTooLarge(width, height));
+    }
+
+    Ok((width as u32, height as u32))
+}
+
+/// Render graph and save it as PNG file.
+pub fn save_png(
+    path: &Path,
+    graph: &Graph,
+    comment_lines: &CommentsGroup,
+    options: &RenderOptions,
+) -> Result<(), GraphEditorError> {
+    render(graph, comment_lines, options)?
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|_| GraphEditorError::FailedSaveFile)
+}
+
+/// Coverage texture of font atlas.
+struct Texture {
+    size: [usize; 2],
+    alpha: Vec<f32>,
+}
+
+impl Texture {
+    fn from_font_image(image: &eframe::epaint::FontImage) -> Self {
+        Self {
+            size: image.size,
+            // same coverage to alpha mapping as used by egui for font texture
+            alpha: image
+                .srgba_pixels(None)
+                .map(|color| color.a() as f32 / 255.0)
+                .collect(),
+        }
+    }
+
+    /// Bilinear sample of normalized texture coordinates.
+    fn sample(&self, uv: Pos2) -> f32 {
+        let [width, height] = self.size;
+        let x = (uv.x * width as f32 - 0.5).clamp(0.0, (width - 1) as f32);
+        let y = (uv.y * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+        let at = |x: usize, y: usize| self.alpha[y * width + x];
+        let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+        let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+/// Pixels with premultiplied gamma space colors, blended like egui does on GPU.
+struct Pixmap {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Pixmap {
+    fn new(width: u32, height: u32, background: Color32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color_to_array(background); width as usize * height as usize],
+        }
+    }
+
+    fn draw_mesh(&mut self, mesh: &Mesh, texture: &Texture, origin: Pos2, scale: f32) {
+        let to_pixels = |vertex: &Vertex| {
+            Pos2::new(
+                (vertex.pos.x - origin.x) * scale,
+                (vertex.pos.y - origin.y) * scale,
+            )
+        };
+
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = [
+                &mesh.vertices[triangle[0] as usize],
+                &mesh.vertices[triangle[1] as usize],
+                &mesh.vertices[triangle[2] as usize],
+            ];
+            let points = vertices.map(to_pixels);
+            self.draw_triangle(points, vertices, texture);
+        }
+    }
+
+    fn draw_triangle(
+        &mut self,
+        mut points: [Pos2; 3],
+        mut vertices: [&Vertex; 3],
+        texture: &Texture,
+    ) {
+        let mut area = edge_function(points[0], points[1], points[2]);
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+        // keep one winding, so that top-left rule works same for all triangles
+        if area < 0.0 {
+            points.swap(1, 2);
+            vertices.swap(1, 2);
+            area = -area;
+        }
+
+        let min_x = points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+        let max_x = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+        let min_y = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_y = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+
+        let x0 = (min_x.floor().max(0.0)) as u32;
+        let y0 = (min_y.floor().max(0.0)) as u32;
+        let x1 = (max_x.ceil().max(0.0) as u32).min(self.width);
+        let y1 = (max_y.ceil().max(0.0) as u32).min(self.height);
+
+        let edges = [(1, 2), (2, 0), (0, 1)];
+        let top_left = edges.map(|(a, b)| is_top_left(points[a], points[b]));
+        let colors = vertices.map(|vertex| color_to_array(vertex.color));
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let center = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+                let mut weights = [0.0; 3];
+                let mut inside = true;
+                for (i, &(a, b)) in edges.iter().enumerate() {
+                    let w = edge_function(points[a], points[b], center);
+                    // pixels on shared edge are drawn by only one triangle
+                    if w < 0.0 || (w == 0.0 && !top_left[i]) {
+                        inside = false;
+                        break;
+                    }
+                    weights[i] = w / area;
+                }
+                if !inside {
+                    continue;
+                }
+
+                let uv = vertices
+                    .iter()
+                    .zip(weights)
+                    .fold(Vec2::ZERO, |uv, (vertex, w)| uv + vertex.uv.to_vec2() * w);
+                let coverage = texture.sample(uv.to_pos2());
+
+                let mut source = [0.0; 4];
+                for (color, w) in colors.iter().zip(weights) {
+                    for channel in 0..4 {
+                        source[channel] += color[channel] * w;
+                    }
+                }
+
+                let pixel = &mut self.pixels[(y * self.width + x) as usize];
+                let transparency = 1.0 - source[3] * coverage;
+                for channel in 0..4 {
+                    pixel[channel] = source[channel] * coverage + pixel[channel] * transparency;
+                }
+            }
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let raw = self
+            .pixels
+            .into_iter()
+            .flat_map(|[r, g, b, a]| {
+                let to_u8 = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                Color32::from_rgba_premultiplied(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+                    .to_srgba_unmultiplied()
+            })
+            .collect();
+
+        RgbaImage::from_raw(self.width, self.height, raw).expect("Pixmap has width * height pixels")
+    }
+}
+
+fn color_to_array(color: Color32) -> [f32; 4] {
+    color.to_array().map(|channel| channel as f32 / 255.0)
+}
+
+/// Doubled signed area of triangle (a, b, p), positive when p is left of a->b.
+fn edge_function(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Whether triangle edge a->b owns pixels lying exactly on it.
+fn is_top_left(a: Pos2, b: Pos2) -> bool {
+    let direction = b - a;
+    direction.y < 0.0 || (direction.y == 0.0 && direction.x > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use eframe::egui::pos2;
+
+    use super::*;
+    use crate::{consts::MAX_RENDER_SCALE, graph::Node};
+
+    #[test]
+    fn too_large_image_is_error() {
+        let mut graph = Graph::new();
+        graph.insert_node(Node::new("a".to_owned(), pos2(0.0, 0.0)));
+        graph.insert_node(Node::new("b".to_owned(), pos2(100_000.0, 100_000.0)));
+        let options = RenderOptions {
+            scale: MAX_RENDER_SCALE,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            render(&graph, &CommentsGroup::new(), &options),
+            Err(GraphEditorError::ImageTooLarge(..))
+        ));
+    }
+
+    #[test]
+    fn image_covers_drawing_with_margin() {
+        let mut graph = Graph::new();
+        graph.insert_node(Node::new("a".to_owned(), pos2(0.0, 0.0)));
+        let options = RenderOptions {
+            scale: 1.0,
+            ..Default::default()
+        };
+
+        let image = render(&graph, &CommentsGroup::new(), &options).unwrap();
+        assert!(image.width() as f32 >= 2.0 * RENDER_MARGIN);
+        assert!(image.height() as f32 >= 2.0 * RENDER_MARGIN);
+    }
+}
@@ -0,0 +1,223 @@
+//! Shapes of graph and comment lines, shared by canvas and offscreen rendering.
+
+use std::{collections::HashMap, sync::Arc};
+
+use eframe::{
+    egui::{vec2, Color32, FontId, Galley, Pos2, Shape, Stroke},
+    epaint::{text::Fonts, CubicBezierShape, QuadraticBezierShape, TextShape},
+};
+
+use crate::{
+    comment_line::group::CommentsGroup,
+    consts::{DASH_GAP_COEF, DASH_LENGTH_COEF, DOT_SPACING_COEF, DOUBLE_LINE_OFFSET_COEF},
+    graph::{edge::EdgeId, Edge, Graph, LineStyle, NodeId},
+    utils::geometry::{
+        edge::{
+            arrow_head_geometry, edges_geometry, label_background, label_text_position,
+            ArrowHeadGeometry, EdgeCurve, EdgeGeometry,
+        },
+        route::EdgeRoutes,
+    },
+};
+
+/// Temporary colors of nodes and edges, e.g. to show algorithm result.
+/// Saved colors of graph stay untouched.
+#[derive(Clone, Debug, Default)]
+pub struct ColorMap {
+    pub nodes: HashMap<NodeId, Color32>,
+    pub edges: HashMap<EdgeId, Color32>,
+}
+
+impl ColorMap {
+    /// Copy of graph with colors replaced.
+    pub fn recolor(&self, graph: &Graph) -> Graph {
+        let mut graph = graph.clone();
+
+        for (id, color) in &self.nodes {
+            if let Some(node) = graph.node_mut(id) {
+                node.color = (*color).into();
+            }
+        }
+        for (id, color) in &self.edges {
+            if let Some(edge) = graph.edge_mut(id) {
+                edge.color = (*color).into();
+            }
+        }
+
+        graph
+    }
+}
+
+/// All shapes in drawing order: edges, nodes, comment lines.
+/// Orthogonal edges missing in `routes` are routed now.
+pub fn shapes(
+    graph: &Graph,
+    routes: Option<&EdgeRoutes>,
+    comment_lines: &CommentsGroup,
+    fonts: &Fonts,
+) -> Vec<Shape> {
+    let mut shapes = edges_shapes(graph, routes, fonts);
+    shapes.extend(nodes_shapes(graph, fonts));
+    shapes.extend(comment_lines_shapes(comment_lines));
+    shapes
+}
+
+pub fn edges_shapes(graph: &Graph, routes: Option<&EdgeRoutes>, fonts: &Fonts) -> Vec<Shape> {
+    let mut shapes = Vec::new();
+
+    for (id, geometry) in edges_geometry(graph, routes) {
+        edge_shapes(&mut shapes, &graph.edges()[&id], &geometry, fonts);
+    }
+
+    shapes
+}
+
+pub fn nodes_shapes(graph: &Graph, fonts: &Fonts) -> Vec<Shape> {
+    let mut nodes: Vec<_> = graph.nodes().iter().collect();
+    // keep overlapping nodes in same order between frames and renders
+    nodes.sort_by_key(|(id, _)| id.0);
+
+    nodes
+        .into_iter()
+        .flat_map(|(_, node)| node.shapes(fonts))
+        .collect()
+}
+
+pub fn comment_lines_shapes(comment_lines: &CommentsGroup) -> Vec<Shape> {
+    comment_lines
+        .iter()
+        .filter(|(_, line)| line.len() >= 2)
+        .map(|(_, line)| Shape::line(line.points.clone(), line.stroke))
+        .collect()
+}
+
+/// Shapes of edge (or loop edge) by its geometry: curve, label and arrow.
+fn edge_shapes(shapes: &mut Vec<Shape>, edge: &Edge, geometry: &EdgeGeometry, fonts: &Fonts) {
+    let stroke = Stroke::new(edge.width, edge.color);
+
+    shapes.extend(styled_curve_shapes(
+        &geometry.curve,
+        edge.line_style,
+        stroke,
+    ));
+
+    if let Some((background, text_position, angle, galley)) =
+        edge_label_layout(edge, geometry, fonts)
+    {
+        shapes.push(Shape::convex_polygon(
+            background.to_vec(),
+            Color32::WHITE,
+            Stroke::NONE,
+        ));
+        shapes.push(
+            TextShape::new(text_position, galley, Color32::BLACK)
+                .with_angle(angle)
+                .into(),
+        );
+    }
+
+    let (source_head, target_head) = edge.arrow_heads();
+    for (head, arrow) in [
+        (source_head, geometry.source_arrow),
+        (target_head, geometry.target_arrow),
+    ] {
+        let Some((from, to)) = arrow else {
+            continue;
+        };
+
+        match arrow_head_geometry(head, from, to, edge.width) {
+            Some(ArrowHeadGeometry::Filled(points)) => {
+                shapes.push(Shape::convex_polygon(points, edge.color, stroke));
+            }
+            Some(ArrowHeadGeometry::Open(points)) => {
+                shapes.push(Shape::line(points.to_vec(), stroke));
+            }
+            Some(ArrowHeadGeometry::Circle { center, radius }) => {
+                shapes.push(Shape::circle_filled(center, radius, Color32::WHITE));
+                shapes.push(Shape::circle_stroke(center, radius, stroke));
+            }
+            None => {}
+        }
+    }
+}
+
+/// Shapes of edge curve drawn in line style.
+fn styled_curve_shapes(curve: &EdgeCurve, style: LineStyle, stroke: Stroke) -> Vec<Shape> {
+    let width = stroke.width;
+
+    match style {
+        LineStyle::Solid => vec![curve_shape(curve, stroke)],
+        LineStyle::Dashed => Shape::dashed_line(
+            &curve.points(),
+            stroke,
+            DASH_LENGTH_COEF * width,
+            DASH_GAP_COEF * width,
+        ),
+        LineStyle::Dotted => Shape::dotted_line(
+            &curve.points(),
+            stroke.color,
+            DOT_SPACING_COEF * width,
+            width / 2.0,
+        ),
+        LineStyle::Double => {
+            let offset = DOUBLE_LINE_OFFSET_COEF * width;
+            let stroke = Stroke::new(width / 2.0, stroke.color);
+            vec![
+                Shape::line(curve.offset_points(offset), stroke),
+                Shape::line(curve.offset_points(-offset), stroke),
+            ]
+        }
+    }
+}
+
+/// Shape of edge curve without fill.
+pub fn curve_shape(curve: &EdgeCurve, stroke: Stroke) -> Shape {
+    match curve {
+        EdgeCurve::Quadratic(points) => {
+            QuadraticBezierShape::from_points_stroke(*points, false, Color32::TRANSPARENT, stroke)
+                .into()
+        }
+        EdgeCurve::Cubic(points) => {
+            CubicBezierShape::from_points_stroke(*points, false, Color32::TRANSPARENT, stroke)
+                .into()
+        }
+        EdgeCurve::Polyline(points) => Shape::line(points.clone(), stroke),
+    }
+}
+
+/// Label layout of edge: background corners, text position, angle and text galley.
+/// None if edge has no label.
+fn edge_label_layout(
+    edge: &Edge,
+    geometry: &EdgeGeometry,
+    fonts: &Fonts,
+) -> Option<([Pos2; 4], Pos2, f32, Arc<Galley>)> {
+    if edge.label.is_empty() {
+        return None;
+    }
+
+    let center = geometry.label_center;
+
+    let galley = fonts.layout_no_wrap(
+        edge.label.clone(),
+        FontId::proportional(edge.label_size),
+        Color32::BLACK,
+    );
+    let galley_size = galley.size();
+    let padding = vec2(edge.padding_x, edge.padding_y);
+    let angle = geometry.label_angle;
+
+    let background = label_background(center, galley_size, padding, angle);
+    let text_position = label_text_position(center, galley_size, padding, angle);
+
+    Some((background, text_position, angle, galley))
+}
+
+/// Corners of edge label background, None if edge has no label.
+pub fn edge_label_background(
+    edge: &Edge,
+    geometry: &EdgeGeometry,
+    fonts: &Fonts,
+) -> Option<[Pos2; 4]> {
+    edge_label_layout(edge, geometry, fonts).map(|(background, ..)| background)
+}
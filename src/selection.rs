@@ -0,0 +1,71 @@
+/// Ordered set of selected items.
+/// The last selected item is primary, it is shown in editors.
+#[derive(Clone, Debug)]
+pub struct Selection<T> {
+    items: Vec<T>,
+}
+
+impl<T> Default for Selection<T> {
+    fn default() -> Self {
+        Self { items: Vec::new() }
+    }
+}
+
+impl<T: Copy + PartialEq> Selection<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn primary(&self) -> Option<T> {
+        self.items.last().copied()
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.items.contains(item)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Replace selection with single item (or nothing).
+    pub fn set(&mut self, item: Option<T>) {
+        self.items.clear();
+        self.items.extend(item);
+    }
+
+    /// Add item to selection, making it primary.
+    pub fn insert(&mut self, item: T) {
+        self.remove(&item);
+        self.items.push(item);
+    }
+
+    pub fn remove(&mut self, item: &T) {
+        self.items.retain(|i| i != item);
+    }
+
+    /// Remove item if it is selected, otherwise add it.
+    pub fn toggle(&mut self, item: T) {
+        if self.contains(&item) {
+            self.remove(&item);
+        } else {
+            self.items.push(item);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.items.retain(f);
+    }
+}
@@ -0,0 +1,333 @@
+use eframe::egui::{
+    self, color_picker::color_edit_button_rgba, Button, Color32, DragValue, Layout, RichText,
+    TextEdit, Ui,
+};
+
+use crate::{
+    consts::{MAX_EDGE_WIDTH, MAX_NODE_SIZE, MIN_EDGE_WIDTH, MIN_NODE_SIZE, UI_SPACE},
+    graph::{
+        styling::{Comparison, Condition, RuleTarget, StyleRule},
+        LineStyle, NodeShape,
+    },
+    graph_workspace::GraphWorkspace,
+    history::EditKind,
+    utils::widgets::node_picker,
+};
+
+/// Rules which style nodes and edges by degree, label, orientation or component.
+pub struct StylingEditor;
+
+impl StylingEditor {
+    pub fn name(&self) -> &'static str {
+        "Styling Rules"
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, graph_workspace: &mut GraphWorkspace) {
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new(self.name()).size(24.0));
+        });
+
+        ui.separator();
+
+        ui.label("Rules are applied from top to bottom, later rules win.");
+        ui.add_space(UI_SPACE);
+
+        // node picker reads workspace, so rules are edited as a copy
+        let mut rules = graph_workspace.style_rules().to_vec();
+        let mut edit = None;
+        let mut removed = None;
+        let mut moved_up = None;
+
+        egui::ScrollArea::vertical()
+            .max_height(ui.available_height() - 40.0)
+            .show(ui, |ui| {
+                for (index, rule) in rules.iter_mut().enumerate() {
+                    ui.group(|ui| {
+                        ui.horizontal(|ui| {
+                            let mut changed = ui
+                                .checkbox(&mut rule.enabled, format!("Rule {}", index + 1))
+                                .changed();
+                            changed |= target_combo(ui, index, rule);
+                            if changed {
+                                edit = Some(EditKind::StyleRule(index));
+                            }
+
+                            ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.button("✖").on_hover_text("Remove rule").clicked() {
+                                    removed = Some(index);
+                                }
+                                if ui
+                                    .add_enabled(index > 0, Button::new("⏶"))
+                                    .on_hover_text("Move rule up")
+                                    .clicked()
+                                {
+                                    moved_up = Some(index);
+                                }
+                            });
+                        });
+
+                        ui.add_enabled_ui(rule.enabled, |ui| {
+                            let mut changed = condition_ui(ui, index, rule, graph_workspace);
+                            changed |= style_ui(ui, index, rule);
+                            changed |= ui
+                                .checkbox(
+                                    &mut rule.restyle_non_default,
+                                    "Restyle non-default values",
+                                )
+                                .on_hover_text(
+                                    "Also restyle values which differ from defaults, \
+                                     e.g. colors set in node or edge editor",
+                                )
+                                .changed();
+                            if changed {
+                                edit = Some(EditKind::StyleRule(index));
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(index) = removed {
+            rules.remove(index);
+            edit = Some(EditKind::StyleRules);
+        }
+        if let Some(index) = moved_up {
+            rules.swap(index - 1, index);
+            edit = Some(EditKind::StyleRules);
+        }
+
+        ui.add_space(UI_SPACE);
+        ui.horizontal_wrapped(|ui| {
+            for target in RuleTarget::ALL {
+                if ui
+                    .button(format!("Add rule for {}", target.name().to_lowercase()))
+                    .clicked()
+                {
+                    rules.push(StyleRule::new(target));
+                    edit = Some(EditKind::StyleRules);
+                }
+            }
+        });
+
+        if let Some(kind) = edit {
+            graph_workspace.set_style_rules(rules);
+            graph_workspace.record(kind);
+        }
+    }
+}
+
+fn target_combo(ui: &mut Ui, index: usize, rule: &mut StyleRule) -> bool {
+    let mut changed = false;
+
+    egui::ComboBox::from_id_salt(("style_rule_target", index))
+        .selected_text(rule.target.name())
+        .show_ui(ui, |ui| {
+            for target in RuleTarget::ALL {
+                changed |= ui
+                    .selectable_value(&mut rule.target, target, target.name())
+                    .changed();
+            }
+        });
+
+    if !rule.condition.fits(rule.target) {
+        rule.condition = Condition::Always;
+    }
+
+    changed
+}
+
+fn condition_ui(
+    ui: &mut Ui,
+    index: usize,
+    rule: &mut StyleRule,
+    graph_workspace: &GraphWorkspace,
+) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("If:");
+        egui::ComboBox::from_id_salt(("style_rule_condition", index))
+            .selected_text(rule.condition.name())
+            .show_ui(ui, |ui| {
+                for condition in Condition::all(rule.target) {
+                    let selected = rule.condition.name() == condition.name();
+                    if ui.selectable_label(selected, condition.name()).clicked() && !selected {
+                        rule.condition = condition;
+                        changed = true;
+                    }
+                }
+            });
+
+        match &mut rule.condition {
+            Condition::Degree(comparison, bound) => {
+                egui::ComboBox::from_id_salt(("style_rule_comparison", index))
+                    .selected_text(comparison.symbol())
+                    .width(40.0)
+                    .show_ui(ui, |ui| {
+                        for variant in Comparison::ALL {
+                            changed |= ui
+                                .selectable_value(comparison, variant, variant.symbol())
+                                .changed();
+                        }
+                    });
+                changed |= ui.add(DragValue::new(bound).speed(0.1)).changed();
+            }
+            Condition::LabelMatches(pattern) => {
+                changed |= ui
+                    .add(
+                        TextEdit::singleline(pattern)
+                            .hint_text("regex")
+                            .desired_width(120.0),
+                    )
+                    .changed();
+            }
+            Condition::Component(node) => {
+                let old = *node;
+                node_picker(
+                    ui,
+                    &format!("style_rule_component_{index}"),
+                    graph_workspace,
+                    node,
+                );
+                changed |= *node != old;
+            }
+            Condition::Always | Condition::Loop | Condition::Unoriented => {}
+        }
+    });
+
+    if let Some(error) = rule.condition.error(graph_workspace.graph()) {
+        ui.label(RichText::new(error).color(Color32::RED));
+    }
+
+    changed
+}
+
+fn style_ui(ui: &mut Ui, index: usize, rule: &mut StyleRule) -> bool {
+    let mut changed = false;
+
+    ui.label("Then set:");
+    egui::Grid::new(("style_rule_outputs", index))
+        .num_columns(2)
+        .show(ui, |ui| match rule.target {
+            RuleTarget::Nodes => {
+                let style = &mut rule.node_style;
+                changed |= optional(
+                    ui,
+                    "Color",
+                    &mut style.color,
+                    Color32::BLUE.into(),
+                    |ui, color| {
+                        color_edit_button_rgba(ui, color, egui::color_picker::Alpha::Opaque)
+                            .changed()
+                    },
+                );
+                changed |= optional(
+                    ui,
+                    "Shape",
+                    &mut style.shape,
+                    NodeShape::Square,
+                    |ui, shape| {
+                        let mut changed = false;
+                        egui::ComboBox::from_id_salt(("style_rule_shape", index))
+                            .selected_text(shape.name())
+                            .show_ui(ui, |ui| {
+                                for variant in NodeShape::ALL {
+                                    changed |= ui
+                                        .selectable_value(shape, variant, variant.name())
+                                        .changed();
+                                }
+                            });
+                        changed
+                    },
+                );
+                changed |= optional(
+                    ui,
+                    "Size",
+                    &mut style.size,
+                    2.0 * MIN_NODE_SIZE,
+                    |ui, size| {
+                        ui.add(
+                            DragValue::new(size)
+                                .range(MIN_NODE_SIZE..=MAX_NODE_SIZE)
+                                .speed(0.2),
+                        )
+                        .changed()
+                    },
+                );
+            }
+            RuleTarget::Edges => {
+                let style = &mut rule.edge_style;
+                changed |= optional(
+                    ui,
+                    "Color",
+                    &mut style.color,
+                    Color32::BLUE.into(),
+                    |ui, color| {
+                        color_edit_button_rgba(ui, color, egui::color_picker::Alpha::Opaque)
+                            .changed()
+                    },
+                );
+                changed |= optional(
+                    ui,
+                    "Width",
+                    &mut style.width,
+                    MAX_EDGE_WIDTH,
+                    |ui, width| {
+                        ui.add(
+                            DragValue::new(width)
+                                .range(MIN_EDGE_WIDTH..=MAX_EDGE_WIDTH)
+                                .speed(0.2),
+                        )
+                        .changed()
+                    },
+                );
+                changed |= optional(
+                    ui,
+                    "Line",
+                    &mut style.line_style,
+                    LineStyle::Dashed,
+                    |ui, line_style| {
+                        let mut changed = false;
+                        egui::ComboBox::from_id_salt(("style_rule_line", index))
+                            .selected_text(line_style.name())
+                            .show_ui(ui, |ui| {
+                                for variant in LineStyle::ALL {
+                                    changed |= ui
+                                        .selectable_value(line_style, variant, variant.name())
+                                        .changed();
+                                }
+                            });
+                        changed
+                    },
+                );
+            }
+        });
+
+    changed
+}
+
+/// Checkbox turning value on and off with editor of value when it is on.
+/// Return true if value was changed.
+fn optional<T>(
+    ui: &mut Ui,
+    name: &str,
+    value: &mut Option<T>,
+    default: T,
+    edit: impl FnOnce(&mut Ui, &mut T) -> bool,
+) -> bool {
+    let mut enabled = value.is_some();
+    let mut changed = ui.checkbox(&mut enabled, name).changed();
+
+    if changed {
+        *value = enabled.then_some(default);
+    }
+    match value {
+        Some(value) => changed |= edit(ui, value),
+        None => {
+            ui.label("unchanged");
+        }
+    }
+    ui.end_row();
+
+    changed
+}
@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+
+use eframe::{
+    egui::{Pos2, Rect, Vec2},
+    emath::Rot2,
+};
+
+use crate::{
+    consts::{
+        ARROW_CIRCLE_COEF, ARROW_HALF_ANGLE, ARROW_LEN_COEF, CONTROL_OFFSET, DELTA_ANGLE,
+        MIN_NODE_SIZE, ROUTE_SPACING,
+    },
+    graph::{edge::EdgeId, ArrowHead, Edge, EdgeRouting, Graph, Node, NodeId},
+};
+
+use super::route::{orthogonal_route, EdgeRoutes};
+
+/// Curve of drawn edge.
+#[derive(Clone, Debug)]
+pub enum EdgeCurve {
+    /// Curved edge between two nodes: [start, control, end].
+    Quadratic([Pos2; 3]),
+    /// Loop edge: [start, control1, control2, end].
+    Cubic([Pos2; 4]),
+    /// Straight segments from start to end, at least two points.
+    Polyline(Vec<Pos2>),
+}
+
+impl EdgeCurve {
+    pub fn start(&self) -> Pos2 {
+        match self {
+            EdgeCurve::Quadratic(points) => points[0],
+            EdgeCurve::Cubic(points) => points[0],
+            EdgeCurve::Polyline(points) => points[0],
+        }
+    }
+
+    pub fn end(&self) -> Pos2 {
+        match self {
+            EdgeCurve::Quadratic(points) => points[2],
+            EdgeCurve::Cubic(points) => points[3],
+            EdgeCurve::Polyline(points) => points[points.len() - 1],
+        }
+    }
+
+    /// Point of curve at `t` in [0; 1], polyline is walked by its length.
+    pub fn sample(&self, t: f32) -> Pos2 {
+        let h = 1.0 - t;
+
+        match self {
+            EdgeCurve::Quadratic([p0, p1, p2]) => {
+                (p0.to_vec2() * h * h + p1.to_vec2() * 2.0 * t * h + p2.to_vec2() * t * t).to_pos2()
+            }
+            EdgeCurve::Cubic([p0, p1, p2, p3]) => (p0.to_vec2() * h * h * h
+                + p1.to_vec2() * 3.0 * t * h * h
+                + p2.to_vec2() * 3.0 * t * t * h
+                + p3.to_vec2() * t * t * t)
+                .to_pos2(),
+            EdgeCurve::Polyline(points) => {
+                let (segment, fraction) = polyline_position(points, t);
+                points[segment] + (points[segment + 1] - points[segment]) * fraction
+            }
+        }
+    }
+
+    /// Points of polyline approximating curve.
+    pub fn points(&self) -> Vec<Pos2> {
+        match self {
+            EdgeCurve::Polyline(points) => points.clone(),
+            _ => (0..=CURVE_SEGMENTS)
+                .map(|i| self.sample(i as f32 / CURVE_SEGMENTS as f32))
+                .collect(),
+        }
+    }
+
+    /// Points of polyline parallel to curve, shifted to the right side by offset.
+    pub fn offset_points(&self, offset: f32) -> Vec<Pos2> {
+        offset_polyline(&self.points(), offset)
+    }
+
+    /// Approximate distance from point to curve.
+    pub fn distance(&self, pos: Pos2) -> f32 {
+        self.points()
+            .windows(2)
+            .map(|segment| segment_distance(segment[0], segment[1], pos))
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+/// Polyline parallel to given one, shifted to the right side by offset.
+pub fn offset_polyline(points: &[Pos2], offset: f32) -> Vec<Pos2> {
+    let normal = |a: Pos2, b: Pos2| {
+        let normal = (b - a).normalized().rot90();
+        if normal.is_finite() {
+            normal
+        } else {
+            Vec2::ZERO
+        }
+    };
+
+    (0..points.len())
+        .map(|i| {
+            let incoming = normal(points[i.saturating_sub(1)], points[i]);
+            let outgoing = normal(points[i], points[(i + 1).min(points.len() - 1)]);
+            // corners are mitered, so that parallel segments keep distance
+            let miter = (incoming + outgoing).normalized();
+            let scale = miter.dot(if incoming == Vec2::ZERO {
+                outgoing
+            } else {
+                incoming
+            });
+            if miter.is_finite() && scale > 0.1 {
+                points[i] + miter * (offset / scale)
+            } else {
+                points[i]
+            }
+        })
+        .collect()
+}
+
+/// Number of segments used to approximate curve.
+const CURVE_SEGMENTS: usize = 32;
+
+/// Segment of polyline and fraction of it at `t` of polyline length.
+fn polyline_position(points: &[Pos2], t: f32) -> (usize, f32) {
+    let lengths: Vec<f32> = points.windows(2).map(|s| s[0].distance(s[1])).collect();
+    let mut rest = t.clamp(0.0, 1.0) * lengths.iter().sum::<f32>();
+
+    for (segment, &length) in lengths.iter().enumerate() {
+        if rest <= length || segment == lengths.len() - 1 {
+            let fraction = if length > 0.0 { rest / length } else { 0.0 };
+            return (segment, fraction.clamp(0.0, 1.0));
+        }
+        rest -= length;
+    }
+
+    (0, 0.0)
+}
+
+pub fn segment_distance(a: Pos2, b: Pos2, pos: Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+
+    if length_sq == 0.0 {
+        return a.distance(pos);
+    }
+
+    let t = ((pos - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    (a + ab * t).distance(pos)
+}
+
+/// Whether convex polygon (e.g. label background) contains point.
+pub fn convex_polygon_contains(polygon: &[Pos2], pos: Pos2) -> bool {
+    let side = |a: Pos2, b: Pos2| (b - a).x * (pos - a).y - (b - a).y * (pos - a).x;
+    let sides: Vec<_> = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| side(a, b))
+        .collect();
+
+    sides.iter().all(|&s| s >= 0.0) || sides.iter().all(|&s| s <= 0.0)
+}
+
+/// Geometry of edge, as it is drawn on canvas.
+#[derive(Clone, Debug)]
+pub struct EdgeGeometry {
+    pub curve: EdgeCurve,
+    /// Middle of curve, where label is placed.
+    pub label_center: Pos2,
+    /// Angle of label along curve at its middle.
+    pub label_angle: f32,
+    /// Arrow direction (from, to) at start node, if it has arrowhead.
+    pub source_arrow: Option<(Pos2, Pos2)>,
+    /// Arrow direction (from, to) at end node, if it has arrowhead.
+    pub target_arrow: Option<(Pos2, Pos2)>,
+}
+
+/// Calculate geometry of all edges, orthogonal edges missing in `routes` are routed now.
+/// Edges between same nodes are shifted to avoid overlapping.
+pub fn edges_geometry(graph: &Graph, routes: Option<&EdgeRoutes>) -> Vec<(EdgeId, EdgeGeometry)> {
+    shifted_edges(graph)
+        .into_iter()
+        .map(|(id, edge, shift)| {
+            let geometry = if edge.is_loop() {
+                loop_geometry(graph, edge, shift)
+            } else {
+                edge_geometry(graph, id, edge, shift, routes)
+            };
+            (id, geometry)
+        })
+        .collect()
+}
+
+/// Edges with their shifts: index for loops, and shifts symmetric around zero
+/// for edges between same two nodes.
+pub(super) fn shifted_edges(graph: &Graph) -> Vec<(EdgeId, &Edge, f32)> {
+    let mut grouped_edges = HashMap::<(NodeId, NodeId), Vec<(EdgeId, &Edge)>>::new();
+
+    for (&id, edge) in graph.edges() {
+        let edge_order = if edge.start_id < edge.end_id {
+            (edge.start_id, edge.end_id)
+        } else {
+            (edge.end_id, edge.start_id)
+        };
+
+        grouped_edges
+            .entry(edge_order)
+            .and_modify(|v| v.push((id, edge)))
+            .or_insert(vec![(id, edge)]);
+    }
+
+    let mut shifted = Vec::with_capacity(graph.edges().len());
+
+    for ((start_id, end_id), edges) in grouped_edges {
+        if start_id == end_id {
+            // iterate over loops
+            for (index, &(id, edge)) in edges.iter().enumerate() {
+                shifted.push((id, edge, index as f32));
+            }
+        } else {
+            // Calc shifts to avoid edges overlapping
+            let edges_number = (edges.len() / 2) as isize;
+            let shifting =
+                (-edges_number..=edges_number).filter(|&n| edges.len() % 2 != 0 || n != 0);
+
+            for (&(id, edge), shift) in edges.iter().zip(shifting) {
+                shifted.push((id, edge, shift as f32));
+            }
+        }
+    }
+
+    shifted
+}
+
+/// Calculate border intersection to draw an edge on the boundary of nodes
+fn calculate_border_intersection(node1: &Node, node2: &Node) -> (Pos2, Pos2) {
+    let direction = (node2.position - node1.position).normalized();
+
+    let start = node1.border_point_in_direction(direction);
+    let end = node2.border_point_in_direction(-direction);
+
+    (start, end)
+}
+
+/// Geometry of edge between two different nodes.
+fn edge_geometry(
+    graph: &Graph,
+    id: EdgeId,
+    edge: &Edge,
+    shift: f32,
+    routes: Option<&EdgeRoutes>,
+) -> EdgeGeometry {
+    let (node_start, node_end) = (&graph.nodes()[&edge.start_id], &graph.nodes()[&edge.end_id]);
+
+    let points = match edge.routing {
+        EdgeRouting::Curved => return curved_geometry(graph, edge, shift),
+        EdgeRouting::Straight => {
+            let (start, end, _) = shifted_border_points(graph, edge, shift);
+            vec![start, end]
+        }
+        EdgeRouting::Polyline => {
+            let first = edge
+                .bend_points
+                .first()
+                .copied()
+                .unwrap_or(node_end.position);
+            let last = edge
+                .bend_points
+                .last()
+                .copied()
+                .unwrap_or(node_start.position);
+
+            let mut points = Vec::with_capacity(edge.bend_points.len() + 2);
+            points.push(node_start.border_point_in_direction(first - node_start.position));
+            points.extend(&edge.bend_points);
+            points.push(node_end.border_point_in_direction(last - node_end.position));
+            points
+        }
+        EdgeRouting::Orthogonal => routes
+            .and_then(|routes| routes.get(&id))
+            .cloned()
+            .unwrap_or_else(|| orthogonal_route(graph, edge, route_offset(edge, shift))),
+    };
+
+    polyline_geometry(edge, points)
+}
+
+/// Sign making edges between same nodes in opposite directions shift to the same side.
+fn direction_sign(edge: &Edge) -> f32 {
+    if edge.start_id < edge.end_id {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+/// Distance of orthogonal route from route of single edge, for parallel edges.
+pub(super) fn route_offset(edge: &Edge, shift: f32) -> f32 {
+    shift * direction_sign(edge) * ROUTE_SPACING
+}
+
+/// Ends of edge on node borders, rotated apart for parallel edges, and direction sign.
+fn shifted_border_points(graph: &Graph, edge: &Edge, shift: f32) -> (Pos2, Pos2, f32) {
+    let direction_sign = direction_sign(edge);
+
+    let (node_start, node_end) = (&graph.nodes()[&edge.start_id], &graph.nodes()[&edge.end_id]);
+    let (start, end) = calculate_border_intersection(node_start, node_end);
+
+    // Calc edge start and end to avoid edges overlaping
+    // based on shift and direction_sign
+    let alpha = DELTA_ANGLE * shift * direction_sign;
+    let start = node_start.rotate_border_point(start, alpha);
+    let end = node_end.rotate_border_point(end, -alpha);
+
+    (start, end, direction_sign)
+}
+
+/// Geometry of curved edge between two different nodes.
+fn curved_geometry(graph: &Graph, edge: &Edge, shift: f32) -> EdgeGeometry {
+    let (start, end, direction_sign) = shifted_border_points(graph, edge, shift);
+
+    // Calc edge control for curve
+    let direction = direction_sign * (start - end).normalized();
+    let midpoint = Pos2::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+    let control = midpoint + direction.rot90() * shift * CONTROL_OFFSET;
+
+    let curve = EdgeCurve::Quadratic([start, control, end]);
+    let (source_arrow, target_arrow) = edge.arrow_heads();
+
+    EdgeGeometry {
+        // Calc curve middle to place label in center of edge
+        label_center: curve.sample(0.5),
+        label_angle: label_angle(start, end),
+        curve,
+        source_arrow: (source_arrow != ArrowHead::None).then_some((control, start)),
+        target_arrow: (target_arrow != ArrowHead::None).then_some((control, end)),
+    }
+}
+
+/// Geometry of edge drawn as straight segments through points.
+fn polyline_geometry(edge: &Edge, points: Vec<Pos2>) -> EdgeGeometry {
+    let count = points.len();
+    // label lies on segment in the middle of polyline
+    let (segment, _) = polyline_position(&points, 0.5);
+    let (source_arrow, target_arrow) = edge.arrow_heads();
+
+    let source_arrow = (source_arrow != ArrowHead::None).then_some((points[1], points[0]));
+    let target_arrow =
+        (target_arrow != ArrowHead::None).then_some((points[count - 2], points[count - 1]));
+    let label_angle = label_angle(points[segment], points[segment + 1]);
+    let curve = EdgeCurve::Polyline(points);
+
+    EdgeGeometry {
+        label_center: curve.sample(0.5),
+        label_angle,
+        curve,
+        source_arrow,
+        target_arrow,
+    }
+}
+
+/// Geometry of loop edge.
+fn loop_geometry(graph: &Graph, edge: &Edge, shift: f32) -> EdgeGeometry {
+    let node = &graph.nodes()[&edge.start_id];
+
+    let rotation_angle = edge.loop_rotation_angle.to_radians();
+
+    // Calculate border points based on rotation angle.
+    // Start point is north of node + rotation angle.
+    let start = node.rotate_border_point(node.position - Vec2::new(0.0, node.size), rotation_angle);
+    // End point is west of node + rotation angle.
+    let end = node.rotate_border_point(node.position - Vec2::new(node.size, 0.0), rotation_angle);
+
+    // Calc direction of vectors:
+    // direction1: start -> node.center (node.position)
+    let direction1 = (node.position - start).normalized();
+    // direction2: end   -> node.center (node.position)
+    let direction2 = (node.position - end).normalized();
+
+    // Calc offset based on node size and shift (possible multiple loops)
+    let offset = CONTROL_OFFSET * (node.size / MIN_NODE_SIZE) * (1.0 + shift);
+    // Calc controls for curve
+    let control1 = start - direction1 * offset;
+    let control2 = end - direction2 * offset;
+
+    let curve = EdgeCurve::Cubic([start, control1, control2, end]);
+    let (source_arrow, target_arrow) = edge.arrow_heads();
+
+    EdgeGeometry {
+        // Calc curve middle to place label in center of edge
+        label_center: curve.sample(0.5),
+        label_angle: label_angle(start, end),
+        curve,
+        source_arrow: (source_arrow != ArrowHead::None).then_some((control1, start)),
+        target_arrow: (target_arrow != ArrowHead::None).then_some((control2, end)),
+    }
+}
+
+/// Angle of edge label, autorotated to be never upside down.
+pub fn label_angle(start: Pos2, end: Pos2) -> f32 {
+    let direction = (end - start).normalized();
+
+    if direction.x <= 0.0 {
+        // add PI to autorotate label
+        direction.angle() + std::f32::consts::PI
+    } else {
+        direction.angle()
+    }
+}
+
+/// Corners of label background, rotated around label center.
+pub fn label_background(center: Pos2, galley_size: Vec2, padding: Vec2, angle: f32) -> [Pos2; 4] {
+    let rect = Rect::from_center_size(center, galley_size + padding).expand(3.0);
+    let rotation = Rot2::from_angle(angle);
+    let rotate = |p: Pos2| center + rotation * (p - center);
+
+    [
+        rotate(rect.left_top()),
+        rotate(rect.right_top()),
+        rotate(rect.right_bottom()),
+        rotate(rect.left_bottom()),
+    ]
+}
+
+/// Position of left top corner of rotated label text.
+pub fn label_text_position(center: Pos2, galley_size: Vec2, padding: Vec2, angle: f32) -> Pos2 {
+    // Compute rotated offset
+    let half_width = (galley_size.x + padding.x) / 2.0;
+    let half_height = (galley_size.y + padding.y) / 2.0;
+
+    // Offset to center the rotated text
+    let offset_x = half_width * angle.cos() - half_height * angle.sin();
+    let offset_y = half_width * angle.sin() + half_height * angle.cos();
+
+    // Adjust the position to properly center the text
+    center - Vec2::new(offset_x, offset_y)
+}
+
+/// Points of arrow triangle at `end`, directed from `start`.
+pub fn arrow_points(start: Pos2, end: Pos2, width: f32) -> [Pos2; 3] {
+    let direction = (end - start).normalized();
+    let rotation = Rot2::from_angle(ARROW_HALF_ANGLE);
+
+    let arrow_left = end - ARROW_LEN_COEF * width * (rotation * direction);
+    let arrow_right = end - ARROW_LEN_COEF * width * (rotation.inverse() * direction);
+
+    [end - 0.6 * width * direction, arrow_left, arrow_right]
+}
+
+/// Outline of arrowhead.
+#[derive(Clone, Debug)]
+pub enum ArrowHeadGeometry {
+    /// Polygon filled with edge color.
+    Filled(Vec<Pos2>),
+    /// Two lines meeting at middle point.
+    Open([Pos2; 3]),
+    /// Circle filled with white.
+    Circle { center: Pos2, radius: f32 },
+}
+
+/// Arrowhead at `end` of edge with given width, directed from `start`.
+pub fn arrow_head_geometry(
+    head: ArrowHead,
+    start: Pos2,
+    end: Pos2,
+    width: f32,
+) -> Option<ArrowHeadGeometry> {
+    let [tip, left, right] = arrow_points(start, end, width);
+
+    match head {
+        ArrowHead::None => None,
+        ArrowHead::Filled => Some(ArrowHeadGeometry::Filled(vec![tip, left, right])),
+        ArrowHead::Open => Some(ArrowHeadGeometry::Open([left, tip, right])),
+        ArrowHead::Diamond => {
+            let back = left + (right - tip);
+            Some(ArrowHeadGeometry::Filled(vec![tip, left, back, right]))
+        }
+        ArrowHead::Circle => {
+            let radius = ARROW_CIRCLE_COEF * width;
+            let direction = (end - start).normalized();
+            Some(ArrowHeadGeometry::Circle {
+                center: end - direction * (radius + width / 2.0),
+                radius,
+            })
+        }
+    }
+}
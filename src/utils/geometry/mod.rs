@@ -1,2 +1,3 @@
-pub mod circle;
-pub mod square;
\ No newline at end of file
+pub mod edge;
+pub mod route;
+pub mod shape;
\ No newline at end of file
@@ -0,0 +1,389 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use eframe::egui::{Pos2, Rect, Vec2};
+
+use crate::{
+    consts::{ROUTE_BEND_PENALTY, ROUTE_MARGIN},
+    graph::{edge::EdgeId, Edge, EdgeRouting, Graph, Node},
+};
+
+use super::edge::{offset_polyline, route_offset, shifted_edges};
+
+/// Steps of searching border point between point inside and outside of node.
+const BISECTION_STEPS: usize = 16;
+/// Coordinates closer than this are merged into one grid line.
+const GRID_TOLERANCE: f32 = 0.5;
+
+/// Moves between neighbour grid crossings: right, left, down, up.
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Routes of orthogonal edges found for graph, searching them every frame is too slow.
+#[derive(Default)]
+pub struct EdgeRoutes(HashMap<EdgeId, Vec<Pos2>>);
+
+impl EdgeRoutes {
+    pub fn resolve(graph: &Graph) -> Self {
+        let routes = shifted_edges(graph)
+            .into_iter()
+            .filter(|(_, edge, _)| edge.routing == EdgeRouting::Orthogonal && !edge.is_loop())
+            .map(|(id, edge, shift)| (id, orthogonal_route(graph, edge, route_offset(edge, shift))))
+            .collect();
+
+        Self(routes)
+    }
+
+    pub fn get(&self, id: &EdgeId) -> Option<&Vec<Pos2>> {
+        self.0.get(id)
+    }
+}
+
+/// Route of edge made of horizontal and vertical segments, going around
+/// bounding boxes of other nodes, from border of start node to border of end node.
+/// Parallel edges are moved apart by `offset` to the right side of route.
+pub fn orthogonal_route(graph: &Graph, edge: &Edge, offset: f32) -> Vec<Pos2> {
+    let (start_node, end_node) = (&graph.nodes()[&edge.start_id], &graph.nodes()[&edge.end_id]);
+    let (start, end) = (start_node.position, end_node.position);
+    let clearance = |node: &Node| node.geometry().bounding_rect().expand(ROUTE_MARGIN);
+
+    // node covering one of ends can't be avoided
+    let obstacles: Vec<Rect> = graph
+        .nodes()
+        .iter()
+        .filter(|(id, _)| **id != edge.start_id && **id != edge.end_id)
+        .map(|(_, node)| clearance(node))
+        .filter(|rect| !rect.contains(start) && !rect.contains(end))
+        .collect();
+    let ends = [clearance(start_node), clearance(end_node)];
+
+    let route = grid_route(start, end, &obstacles, &ends)
+        .unwrap_or_else(|| vec![start, Pos2::new(end.x, start.y), end]);
+    let route = offset_polyline(&simplify(route), offset);
+
+    let mut route = clip_to_border(route, start_node);
+    route.reverse();
+    let mut route = clip_to_border(route, end_node);
+    route.reverse();
+
+    if route.len() < 2 {
+        vec![start, end]
+    } else {
+        route
+    }
+}
+
+/// Cheapest path along grid lines through borders of obstacles,
+/// every bend costs as extra length. Path leaves `ends` boxes straight,
+/// without bends inside of them. None if obstacles block all paths.
+fn grid_route(start: Pos2, end: Pos2, obstacles: &[Rect], ends: &[Rect]) -> Option<Vec<Pos2>> {
+    let bounds = obstacles
+        .iter()
+        .fold(Rect::from_two_pos(start, end), |bounds, rect| {
+            bounds.union(*rect)
+        })
+        .expand(ROUTE_MARGIN);
+
+    let grid_lines = |coordinate: fn(Pos2) -> f32| {
+        let mut lines = vec![
+            coordinate(start),
+            coordinate(end),
+            (coordinate(start) + coordinate(end)) / 2.0,
+            coordinate(bounds.min),
+            coordinate(bounds.max),
+        ];
+        for rect in obstacles.iter().chain(ends) {
+            lines.extend([coordinate(rect.min), coordinate(rect.max)]);
+        }
+        lines.sort_by(f32::total_cmp);
+        lines.dedup_by(|a, b| (*a - *b).abs() < GRID_TOLERANCE);
+        lines
+    };
+    let (xs, ys) = (grid_lines(|p| p.x), grid_lines(|p| p.y));
+    let (width, height) = (xs.len(), ys.len());
+    let index = |i: usize, j: usize| j * width + i;
+
+    // crossings and segments to the next crossing strictly inside of some obstacle
+    let inside = |value: f32, min: f32, max: f32| {
+        value > min + GRID_TOLERANCE && value < max - GRID_TOLERANCE
+    };
+    let mut blocked = vec![false; width * height];
+    let mut blocked_right = vec![false; width * height];
+    let mut blocked_down = vec![false; width * height];
+    for rect in obstacles {
+        let columns: Vec<_> = (0..width)
+            .filter(|&i| inside(xs[i], rect.min.x, rect.max.x))
+            .collect();
+        let rows: Vec<_> = (0..height)
+            .filter(|&j| inside(ys[j], rect.min.y, rect.max.y))
+            .collect();
+
+        for &j in &rows {
+            for &i in &columns {
+                blocked[index(i, j)] = true;
+            }
+            for i in 0..width - 1 {
+                if inside((xs[i] + xs[i + 1]) / 2.0, rect.min.x, rect.max.x) {
+                    blocked_right[index(i, j)] = true;
+                }
+            }
+        }
+        for &i in &columns {
+            for j in 0..height - 1 {
+                if inside((ys[j] + ys[j + 1]) / 2.0, rect.min.y, rect.max.y) {
+                    blocked_down[index(i, j)] = true;
+                }
+            }
+        }
+    }
+
+    let straight: Vec<bool> = (0..width * height)
+        .map(|crossing| {
+            let (x, y) = (xs[crossing % width], ys[crossing / width]);
+            ends.iter()
+                .any(|rect| inside(x, rect.min.x, rect.max.x) && inside(y, rect.min.y, rect.max.y))
+        })
+        .collect();
+
+    let closest = |lines: &[f32], value: f32| {
+        (0..lines.len())
+            .min_by(|&a, &b| {
+                (lines[a] - value)
+                    .abs()
+                    .total_cmp(&(lines[b] - value).abs())
+            })
+            .unwrap()
+    };
+    let start_index = index(closest(&xs, start.x), closest(&ys, start.y));
+    let end_index = index(closest(&xs, end.x), closest(&ys, end.y));
+
+    // Dijkstra over (crossing, direction of arrival) states,
+    // costs are non-negative, so their bits are ordered as costs
+    let state_count = width * height * DIRECTIONS.len();
+    let mut costs = vec![f32::INFINITY; state_count];
+    let mut previous = vec![usize::MAX; state_count];
+    let mut queue = BinaryHeap::new();
+    for direction in 0..DIRECTIONS.len() {
+        let state = start_index * DIRECTIONS.len() + direction;
+        costs[state] = 0.0;
+        queue.push(Reverse((0.0_f32.to_bits(), state)));
+    }
+
+    let mut finish = None;
+    while let Some(Reverse((cost_bits, state))) = queue.pop() {
+        let cost = f32::from_bits(cost_bits);
+        if cost > costs[state] {
+            continue;
+        }
+
+        let (crossing, direction) = (state / DIRECTIONS.len(), state % DIRECTIONS.len());
+        if crossing == end_index {
+            finish = Some(state);
+            break;
+        }
+
+        let (i, j) = (crossing % width, crossing / width);
+        for (next_direction, (di, dj)) in DIRECTIONS.into_iter().enumerate() {
+            let (Some(ni), Some(nj)) = (i.checked_add_signed(di), j.checked_add_signed(dj)) else {
+                continue;
+            };
+            if ni >= width || nj >= height || blocked[index(ni, nj)] {
+                continue;
+            }
+            let segment_blocked = match (di, dj) {
+                (1, 0) => blocked_right[index(i, j)],
+                (-1, 0) => blocked_right[index(ni, nj)],
+                (0, 1) => blocked_down[index(i, j)],
+                _ => blocked_down[index(ni, nj)],
+            };
+            if segment_blocked {
+                continue;
+            }
+
+            let bend = if next_direction == direction || crossing == start_index {
+                0.0
+            } else if straight[crossing] {
+                continue;
+            } else {
+                ROUTE_BEND_PENALTY
+            };
+            let length = (xs[ni] - xs[i]).abs() + (ys[nj] - ys[j]).abs();
+            let next_cost = cost + length + bend;
+            let next_state = index(ni, nj) * DIRECTIONS.len() + next_direction;
+            if next_cost < costs[next_state] {
+                costs[next_state] = next_cost;
+                previous[next_state] = state;
+                queue.push(Reverse((next_cost.to_bits(), next_state)));
+            }
+        }
+    }
+
+    let mut state = finish?;
+    let mut route = Vec::new();
+    loop {
+        let crossing = state / DIRECTIONS.len();
+        route.push(Pos2::new(xs[crossing % width], ys[crossing / width]));
+        if previous[state] == usize::MAX {
+            break;
+        }
+        state = previous[state];
+    }
+    route.reverse();
+
+    // grid lines are close to, but not always exactly at, ends
+    route[0] = start;
+    let last = route.len() - 1;
+    route[last] = end;
+
+    Some(route)
+}
+
+/// Remove repeated points and points in the middle of straight run.
+fn simplify(points: Vec<Pos2>) -> Vec<Pos2> {
+    let mut simplified: Vec<Pos2> = Vec::with_capacity(points.len());
+
+    for point in points {
+        if simplified
+            .last()
+            .is_some_and(|last| last.distance(point) < GRID_TOLERANCE)
+        {
+            continue;
+        }
+        if let [.., a, b] = simplified[..] {
+            let cross = |u: Vec2, v: Vec2| u.x * v.y - u.y * v.x;
+            if cross(b - a, point - b).abs() < GRID_TOLERANCE {
+                simplified.pop();
+            }
+        }
+        simplified.push(point);
+    }
+
+    simplified
+}
+
+/// Polyline starting inside of node, cut where it leaves node.
+fn clip_to_border(points: Vec<Pos2>, node: &Node) -> Vec<Pos2> {
+    let Some(exit) = points.iter().position(|&point| !node.is_clicked(point)) else {
+        return points;
+    };
+    if exit == 0 {
+        return points;
+    }
+
+    // border of node shape is crossed once between point inside and outside
+    let (mut inside, mut outside) = (points[exit - 1], points[exit]);
+    for _ in 0..BISECTION_STEPS {
+        let middle = inside + (outside - inside) / 2.0;
+        if node.is_clicked(middle) {
+            inside = middle;
+        } else {
+            outside = middle;
+        }
+    }
+
+    let mut clipped = vec![outside];
+    clipped.extend(&points[exit..]);
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_utils::graph;
+    use crate::utils::geometry::edge::{edges_geometry, EdgeCurve};
+
+    fn is_orthogonal(route: &[Pos2]) -> bool {
+        route.windows(2).all(|segment| {
+            let delta = segment[1] - segment[0];
+            delta.x.abs() < GRID_TOLERANCE || delta.y.abs() < GRID_TOLERANCE
+        })
+    }
+
+    #[test]
+    fn grid_route_without_obstacles_is_straight() {
+        let (start, end) = (Pos2::new(0.0, 0.0), Pos2::new(100.0, 0.0));
+
+        let route = simplify(grid_route(start, end, &[], &[]).unwrap());
+
+        assert_eq!(route, vec![start, end]);
+    }
+
+    #[test]
+    fn grid_route_goes_around_obstacle() {
+        let (start, end) = (Pos2::new(0.0, 0.0), Pos2::new(100.0, 0.0));
+        let obstacle = Rect::from_center_size(Pos2::new(50.0, 0.0), Vec2::splat(20.0));
+
+        let route = grid_route(start, end, &[obstacle], &[]).unwrap();
+
+        assert_eq!((route[0], route[route.len() - 1]), (start, end));
+        assert!(is_orthogonal(&route));
+        for segment in route.windows(2) {
+            let middle = segment[0] + (segment[1] - segment[0]) / 2.0;
+            assert!(!obstacle.shrink(GRID_TOLERANCE).contains(middle));
+        }
+    }
+
+    #[test]
+    fn simplify_removes_repeated_and_straight_run_points() {
+        let points = vec![
+            Pos2::new(0.0, 0.0),
+            Pos2::new(0.0, 0.0),
+            Pos2::new(10.0, 0.0),
+            Pos2::new(20.0, 0.0),
+            Pos2::new(20.0, 10.0),
+            Pos2::new(20.0, 30.0),
+        ];
+
+        assert_eq!(
+            simplify(points),
+            vec![
+                Pos2::new(0.0, 0.0),
+                Pos2::new(20.0, 0.0),
+                Pos2::new(20.0, 30.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn clip_to_border_starts_route_on_node_border() {
+        let node = Node::new("0".to_string(), Pos2::new(0.0, 0.0));
+        let points = vec![node.position, Pos2::new(100.0, 0.0), Pos2::new(100.0, 50.0)];
+
+        let clipped = clip_to_border(points, &node);
+
+        assert_eq!(clipped.len(), 3);
+        assert!((clipped[0].x - node.size).abs() < 0.1);
+        assert_eq!(
+            clipped[1..],
+            [Pos2::new(100.0, 0.0), Pos2::new(100.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn clip_to_border_keeps_route_starting_outside() {
+        let node = Node::new("0".to_string(), Pos2::new(0.0, 0.0));
+        let points = vec![Pos2::new(50.0, 0.0), Pos2::new(100.0, 0.0)];
+
+        assert_eq!(clip_to_border(points.clone(), &node), points);
+    }
+
+    #[test]
+    fn resolved_routes_are_used_for_geometry() {
+        let (mut graph, nodes, edges) = graph(2, &[(0, 1), (0, 1)]);
+        graph.node_mut(&nodes[1]).unwrap().position = Pos2::new(200.0, 100.0);
+        for id in &edges {
+            graph.edge_mut(id).unwrap().routing = EdgeRouting::Orthogonal;
+        }
+
+        let routes = EdgeRoutes::resolve(&graph);
+
+        for (id, geometry) in edges_geometry(&graph, None) {
+            let EdgeCurve::Polyline(points) = geometry.curve else {
+                panic!("orthogonal edge isn't polyline");
+            };
+            assert!(is_orthogonal(&points));
+            assert_eq!(routes.get(&id), Some(&points));
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use eframe::{
+    egui::{Color32, Pos2, Rect, Shape, Stroke, StrokeKind, Vec2},
+    emath::Rot2,
+    epaint::{CircleShape, EllipseShape, RectShape},
+};
+
+use crate::graph::NodeShape;
+
+use super::edge::convex_polygon_contains;
+
+/// Corner radius of rounded rectangle relative to its smaller half side.
+const CORNER_RADIUS_FACTOR: f32 = 0.4;
+/// Corner radius of square, it's drawn almost sharp.
+const SQUARE_CORNER_RADIUS: f32 = 2.0;
+
+/// Node shape placed at `center`, `half_size` is half of its bounding box.
+#[derive(Clone, Copy, Debug)]
+pub struct ShapeGeometry {
+    pub shape: NodeShape,
+    pub center: Pos2,
+    pub half_size: Vec2,
+}
+
+impl ShapeGeometry {
+    pub fn new(shape: NodeShape, center: Pos2, half_size: Vec2) -> Self {
+        Self {
+            shape,
+            center,
+            half_size,
+        }
+    }
+
+    /// Same shape grown by offset on every side.
+    pub fn expand(self, offset: f32) -> Self {
+        Self {
+            half_size: self.half_size + Vec2::splat(offset),
+            ..self
+        }
+    }
+
+    pub fn bounding_rect(&self) -> Rect {
+        Rect::from_center_size(self.center, 2.0 * self.half_size)
+    }
+
+    /// Corners of polygonal shapes in clockwise order, None for round shapes.
+    pub fn polygon(&self) -> Option<Vec<Pos2>> {
+        let corners: &[(f32, f32)] = match self.shape {
+            NodeShape::Square => &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)],
+            NodeShape::Diamond => &[(0.0, -1.0), (1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)],
+            NodeShape::Triangle => &[(0.0, -1.0), (1.0, 1.0), (-1.0, 1.0)],
+            NodeShape::Hexagon => &[
+                (-0.5, -1.0),
+                (0.5, -1.0),
+                (1.0, 0.0),
+                (0.5, 1.0),
+                (-0.5, 1.0),
+                (-1.0, 0.0),
+            ],
+            NodeShape::Circle | NodeShape::Ellipse | NodeShape::RoundedRect => return None,
+        };
+
+        Some(
+            corners
+                .iter()
+                .map(|&(x, y)| self.center + Vec2::new(x, y) * self.half_size)
+                .collect(),
+        )
+    }
+
+    pub fn corner_radius(&self) -> f32 {
+        match self.shape {
+            NodeShape::Square => SQUARE_CORNER_RADIUS,
+            NodeShape::RoundedRect => CORNER_RADIUS_FACTOR * self.half_size.min_elem(),
+            _ => 0.0,
+        }
+    }
+
+    pub fn shape(&self, fill: Color32, stroke: Stroke) -> Shape {
+        match self.shape {
+            NodeShape::Circle => Shape::Circle(CircleShape {
+                center: self.center,
+                radius: self.half_size.x,
+                fill,
+                stroke,
+            }),
+            NodeShape::Ellipse => Shape::Ellipse(EllipseShape {
+                center: self.center,
+                radius: self.half_size,
+                fill,
+                stroke,
+            }),
+            NodeShape::Square | NodeShape::RoundedRect => Shape::Rect(RectShape::new(
+                self.bounding_rect(),
+                self.corner_radius(),
+                fill,
+                stroke,
+                StrokeKind::Inside,
+            )),
+            NodeShape::Diamond | NodeShape::Triangle | NodeShape::Hexagon => {
+                Shape::convex_polygon(self.polygon().unwrap_or_default(), fill, stroke)
+            }
+        }
+    }
+
+    pub fn contains(&self, pos: Pos2) -> bool {
+        let offset = pos - self.center;
+
+        match self.shape {
+            NodeShape::Circle | NodeShape::Ellipse => (offset / self.half_size).length_sq() < 1.0,
+            NodeShape::RoundedRect => {
+                if !self.bounding_rect().contains(pos) {
+                    return false;
+                }
+                // in corner square only points of corner circle are inside
+                let radius = self.corner_radius();
+                let corner_center = self.half_size - Vec2::splat(radius);
+                let outside = offset.abs() - corner_center;
+                outside.x <= 0.0 || outside.y <= 0.0 || outside.length() <= radius
+            }
+            NodeShape::Square | NodeShape::Diamond | NodeShape::Triangle | NodeShape::Hexagon => {
+                self.polygon()
+                    .is_some_and(|polygon| convex_polygon_contains(&polygon, pos))
+            }
+        }
+    }
+
+    /// Point where ray from center in direction crosses border.
+    pub fn border_point(&self, direction: Vec2) -> Pos2 {
+        let direction = direction.normalized();
+        if !direction.is_finite() || direction == Vec2::ZERO {
+            return self.center;
+        }
+
+        let distance = match self.shape {
+            NodeShape::Circle | NodeShape::Ellipse => 1.0 / (direction / self.half_size).length(),
+            NodeShape::RoundedRect => self.rounded_rect_ray(direction),
+            NodeShape::Square | NodeShape::Diamond | NodeShape::Triangle | NodeShape::Hexagon => {
+                self.polygon_ray(direction)
+            }
+        };
+
+        self.center + direction * distance
+    }
+
+    /// Rotate point of border around center by angle (in radians),
+    /// the result is put back on border.
+    pub fn rotate_border_point(&self, border_pos: Pos2, alpha: f32) -> Pos2 {
+        self.border_point(Rot2::from_angle(alpha) * (border_pos - self.center))
+    }
+
+    /// Distance from center to closest crossing of polygon side along unit direction.
+    fn polygon_ray(&self, direction: Vec2) -> f32 {
+        let cross = |a: Vec2, b: Vec2| a.x * b.y - a.y * b.x;
+        let polygon = self.polygon().unwrap_or_default();
+
+        polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .filter_map(|(&a, &b)| {
+                // center + t * direction = a + s * (b - a)
+                let side = b - a;
+                let denominator = cross(direction, side);
+                if denominator.abs() < f32::EPSILON {
+                    return None;
+                }
+                let t = cross(a - self.center, side) / denominator;
+                let s = cross(a - self.center, direction) / denominator;
+                (t >= 0.0 && (0.0..=1.0).contains(&s)).then_some(t)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    fn rounded_rect_ray(&self, direction: Vec2) -> f32 {
+        // crossing with rectangle
+        let distance = (self.half_size / direction.abs()).min_elem();
+        let hit = direction * distance;
+
+        let radius = self.corner_radius();
+        let corner_center = self.half_size - Vec2::splat(radius);
+        if hit.x.abs() <= corner_center.x || hit.y.abs() <= corner_center.y {
+            return distance;
+        }
+
+        // crossing with corner circle, farther root of quadratic equation
+        let corner = Vec2::new(
+            corner_center.x * hit.x.signum(),
+            corner_center.y * hit.y.signum(),
+        );
+        let projection = direction.dot(corner);
+        let discriminant = projection * projection - corner.length_sq() + radius * radius;
+
+        projection + discriminant.max(0.0).sqrt()
+    }
+}
@@ -46,7 +46,7 @@ pub fn crop_color_image(
     let (cropped_width, cropped_height) = (x1 - x0, y1 - y0);
     let mut cropped_pixels = Vec::with_capacity(cropped_width * cropped_height);
 
-    for y in y0..=y1 {
+    for y in y0..y1 {
         let start = y * width + x0;
         let end = y * width + x1;
 
@@ -1,2 +1,3 @@
 pub mod geometry;
-pub mod image;
\ No newline at end of file
+pub mod image;
+pub mod widgets;
\ No newline at end of file
@@ -0,0 +1,120 @@
+use eframe::egui::{Button, ComboBox, DragValue, Grid, Response, TextEdit, Ui};
+
+use crate::{
+    graph::{
+        attribute::{AttributeKind, AttributeValue, Attributes},
+        NodeId,
+    },
+    graph_workspace::GraphWorkspace,
+};
+
+/// Combo box choosing one of graph nodes by label.
+pub fn node_picker(
+    ui: &mut Ui,
+    id_salt: &str,
+    graph_workspace: &GraphWorkspace,
+    node: &mut Option<NodeId>,
+) {
+    ComboBox::from_id_salt(id_salt)
+        .selected_text(node_name(graph_workspace, *node))
+        .show_ui(ui, |ui| {
+            for id in graph_workspace.node_ids() {
+                ui.selectable_value(node, Some(id), node_name(graph_workspace, Some(id)));
+            }
+        });
+}
+
+/// Label of node with its id, labels may repeat.
+pub fn node_name(graph_workspace: &GraphWorkspace, id: Option<NodeId>) -> String {
+    match id.and_then(|id| graph_workspace.node(&id).map(|node| (id, node))) {
+        Some((id, node)) => format!("{} (#{})", node.label, id.0),
+        None => "None".to_owned(),
+    }
+}
+
+/// Editor of attribute value matching its kind.
+pub fn attribute_value_edit(ui: &mut Ui, value: &mut AttributeValue) -> Response {
+    match value {
+        AttributeValue::String(text) => ui.add(TextEdit::singleline(text).desired_width(100.0)),
+        AttributeValue::Number(number) => ui.add(DragValue::new(number).speed(0.1)),
+        AttributeValue::Bool(flag) => ui.checkbox(flag, ""),
+    }
+}
+
+/// Attributes with editable values and kinds, which can be moved up or removed,
+/// and field to add new attribute by name.
+/// Return true if attributes were changed.
+pub fn attributes_editor(ui: &mut Ui, id_salt: &str, attributes: &mut Attributes) -> bool {
+    let mut changed = false;
+    let (mut move_up, mut remove) = (None, None);
+
+    Grid::new(id_salt).num_columns(4).show(ui, |ui| {
+        for (index, attribute) in attributes.iter_mut().enumerate() {
+            ui.label(&attribute.name);
+            changed |= attribute_value_edit(ui, &mut attribute.value).changed();
+
+            let mut kind = attribute.value.kind();
+            ComboBox::from_id_salt((id_salt, index))
+                .selected_text(kind.name())
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for variant in AttributeKind::ALL {
+                        ui.selectable_value(&mut kind, variant, variant.name());
+                    }
+                });
+            if kind != attribute.value.kind() {
+                attribute.value = attribute.value.convert(kind);
+                changed = true;
+            }
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(index > 0, Button::new("⏶"))
+                    .on_hover_text("Move up")
+                    .clicked()
+                {
+                    move_up = Some(index);
+                }
+                if ui.button("✖").on_hover_text("Remove").clicked() {
+                    remove = Some(attribute.name.clone());
+                }
+            });
+            ui.end_row();
+        }
+    });
+
+    if let Some(index) = move_up {
+        attributes.move_up(index);
+        changed = true;
+    }
+    if let Some(name) = remove {
+        attributes.remove(&name);
+        changed = true;
+    }
+
+    // name of new attribute is typed during several frames, ui memory keeps it
+    let name_id = ui.id().with((id_salt, "new_attribute"));
+    let mut name: String = ui.data_mut(|data| data.get_temp(name_id).unwrap_or_default());
+    ui.horizontal(|ui| {
+        ui.add(
+            TextEdit::singleline(&mut name)
+                .hint_text("New attribute")
+                .desired_width(120.0),
+        );
+
+        let trimmed = name.trim().to_owned();
+        let valid = !trimmed.is_empty() && attributes.get(&trimmed).is_none();
+        if ui
+            .add_enabled(valid, Button::new("Add"))
+            .on_hover_text("Names of attributes are unique")
+            .clicked()
+        {
+            attributes.set(&trimmed, AttributeValue::default_of(AttributeKind::String));
+            name.clear();
+            changed = true;
+        }
+    });
+    ui.data_mut(|data| data.insert_temp(name_id, name));
+
+    changed
+}